@@ -14,6 +14,10 @@ struct Args {
     #[arg(short, long)]
     env: Vec<String>,
 
+    /// Directory to run the program in (defaults to the daemon's)
+    #[arg(long)]
+    cwd: Option<String>,
+
     /// The program to execute
     program: String,
 
@@ -54,7 +58,7 @@ async fn main() {
             &args.program,
             &args.args,
             args.env,
-            None,
+            args.cwd.as_deref(),
             |line| print!("{}", line),
             |line| eprint!("{}", line),
         )
@@ -234,7 +234,7 @@ async fn handle_client(
                 env,
                 working_dir,
             } => {
-                execute_command(&writer_arc, program, args, env, working_dir).await?;
+                execute_command(&writer_arc, &reader, program, args, env, working_dir).await?;
             }
         }
     }
@@ -244,6 +244,7 @@ async fn handle_client(
 
 async fn execute_command(
     writer: &Arc<Mutex<tokio::net::unix::WriteHalf<'_>>>,
+    reader: &tokio::net::unix::ReadHalf<'_>,
     program: String,
     args: Vec<String>,
     env: Vec<String>,
@@ -278,7 +279,7 @@ async fn execute_command(
             std::process::exit(1);
         }
         Fork::Parent(pid, master) => {
-            let exit_code = read_pty_output(writer.clone(), master, pid).await?;
+            let exit_code = read_pty_output(writer.clone(), reader, master, pid).await?;
             let mut w = writer.lock().await;
             write_message(&mut *w, &DaemonMessage::Completed { exit_code }).await?;
         }
@@ -287,8 +288,29 @@ async fn execute_command(
     Ok(())
 }
 
+/// SIGTERM the command's process group, falling back to the process alone if
+/// it didn't become a group leader.
+fn terminate(pid: libc::pid_t) {
+    unsafe {
+        if libc::kill(-pid, libc::SIGTERM) != 0 {
+            libc::kill(pid, libc::SIGTERM);
+        }
+    }
+}
+
+/// Resolves once the client hangs up. The client sends nothing while a
+/// command runs, so unexpected data just ends the watch and is left in the
+/// socket for the message loop.
+async fn client_gone(reader: &tokio::net::unix::ReadHalf<'_>) {
+    match reader.ready(tokio::io::Interest::READABLE).await {
+        Ok(ready) if !ready.is_read_closed() => std::future::pending().await,
+        _ => {}
+    }
+}
+
 async fn read_pty_output(
     writer: Arc<Mutex<tokio::net::unix::WriteHalf<'_>>>,
+    reader: &tokio::net::unix::ReadHalf<'_>,
     master: pty::prelude::Master,
     pid: libc::pid_t,
 ) -> Result<i32> {
@@ -315,7 +337,13 @@ async fn read_pty_output(
                 Ok(line) => {
                     let msg = DaemonMessage::Output(line);
                     let mut w = writer_output.lock().await;
-                    let _ = write_message(&mut *w, &msg).await;
+                    if write_message(&mut *w, &msg).await.is_err() {
+                        // The client went away (cancelled or timed out in the
+                        // GUI). Don't leave a root process running unattended.
+                        warn!("Client disconnected, terminating pid {}", pid);
+                        terminate(pid);
+                        break;
+                    }
                 }
                 Err(e) => {
                     if e.kind() != std::io::ErrorKind::UnexpectedEof {
@@ -327,9 +355,15 @@ async fn read_pty_output(
         }
     };
 
+    // A command that has gone quiet (e.g. pacman waiting on its lock) never
+    // hits a failed write, so also watch the socket itself.
     tokio::select! {
         _ = read_handle => {},
         _ = output_task => {},
+        _ = client_gone(reader) => {
+            warn!("Client disconnected, terminating pid {}", pid);
+            terminate(pid);
+        }
     }
 
     let exit_code = tokio::task::spawn_blocking(move || {
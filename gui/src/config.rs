@@ -21,6 +21,15 @@ pub mod links {
     pub const TOOLKIT_REPO: &str = "https://github.com/MurderFromMars/CyberXero-Toolkit.git";
 }
 
+/// Flatpak remote used for every Flatpak install.
+pub mod flatpak {
+    /// Name of the Flathub remote.
+    pub const FLATHUB: &str = "flathub";
+
+    /// `.flatpakrepo` file used to add Flathub when it's missing.
+    pub const FLATHUB_REPO: &str = "https://dl.flathub.org/repo/flathub.flatpakrepo";
+}
+
 /// Binary paths for system executables.
 pub mod paths {
     use std::path::PathBuf;
@@ -1,58 +1,111 @@
 //! AUR helper detection and management.
 //!
-//! This module handles detection and access to AUR helpers (paru/yay)
-//! used for installing packages from the Arch User Repository.
+//! This module handles detection and access to AUR helpers used for
+//! installing packages from the Arch User Repository.
+//!
+//! Only helpers that accept pacman-style operations (`-S`, `-Q`, `-Rns`) are
+//! supported, since every sequence in the toolkit is written that way. Aura
+//! is deliberately absent: its AUR operations live under `-A`.
 
-use log::debug;
+use log::{debug, warn};
 use std::env;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::{PoisonError, RwLock};
+
+use crate::core::settings;
+
+/// Detected AUR helper: `None` until the first lookup, then whatever that
+/// lookup found. Not a `OnceLock`, since [`refresh`] has to replace the
+/// answer when paru gets installed while the toolkit is running.
+static AUR_HELPER: RwLock<Option<Option<&'static str>>> = RwLock::new(None);
 
-/// Global storage for the detected AUR helper.
-static AUR_HELPER: OnceLock<String> = OnceLock::new();
+/// Default priority order for AUR helper detection.
+pub const AUR_HELPERS: [&str; 4] = ["paru", "yay", "pikaur", "trizen"];
 
-/// Priority order for AUR helper detection.
-const AUR_HELPERS: [&str; 2] = ["paru", "yay"];
+/// Environment variable that overrides the saved preference for one run.
+pub const PREFERENCE_ENV: &str = "CYBERXERO_TOOLKIT_AUR_HELPER";
+
+/// The user's preferred helper, if any: [`PREFERENCE_ENV`] first, then the
+/// `aur_helper` setting. Unsupported names are ignored with a warning.
+fn preferred() -> Option<&'static str> {
+    let raw = env::var(PREFERENCE_ENV).ok().or_else(settings::aur_helper)?;
+    let name = raw.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let found = AUR_HELPERS.into_iter().find(|h| *h == name);
+    if found.is_none() {
+        warn!("Ignoring unsupported AUR helper preference: {}", name);
+    }
+    found
+}
 
 /// Detect and return the available AUR helper.
 ///
-/// Searches for AUR helpers in priority order (paru, then yay).
+/// Tries the preferred helper first, then the default priority order
+/// (paru, yay, pikaur, trizen). A preferred helper that isn't installed
+/// falls through to the next one found.
 /// Returns the first found helper or None if none are available.
 pub fn detect() -> Option<&'static str> {
-    for &helper in AUR_HELPERS.iter() {
+    let preferred = preferred();
+    let order = preferred
+        .into_iter()
+        .chain(AUR_HELPERS.into_iter().filter(|h| Some(*h) != preferred));
+
+    for helper in order {
         if is_executable_in_path(helper) {
             debug!("Found AUR helper: {}", helper);
             return Some(helper);
         }
+        if Some(helper) == preferred {
+            warn!("Preferred AUR helper {} is not installed", helper);
+        }
     }
 
     debug!("No AUR helper found");
     None
 }
 
+/// Flag the helper uses to pick its privilege escalation program, if it has
+/// one. Helpers without it call `sudo` directly, which the bundled shim on
+/// `PATH` forwards to the auth daemon.
+pub fn sudo_flag(helper: &str) -> Option<&'static str> {
+    match helper {
+        "paru" | "yay" => Some("--sudo"),
+        _ => None,
+    }
+}
+
 /// Initialize the global AUR helper.
 ///
 /// Should be called once at startup after dependency checks pass.
 /// Returns true if an AUR helper was found and initialized.
 pub fn init() -> bool {
-    if let Some(helper) = detect() {
-        let _ = AUR_HELPER.set(helper.to_string());
-        true
-    } else {
-        false
-    }
+    refresh().is_some()
 }
 
-/// Get the initialized AUR helper.
-///
-/// Returns None if no helper has been initialized.
+/// Scan `PATH` again and remember the result, for when a helper appears or
+/// the preference changes mid-session.
+pub fn refresh() -> Option<&'static str> {
+    let found = detect();
+    *AUR_HELPER.write().unwrap_or_else(PoisonError::into_inner) = Some(found);
+    found
+}
+
+/// Get the AUR helper, detecting it on first use. Later calls are answered
+/// from memory without touching the filesystem.
 pub fn get() -> Option<&'static str> {
-    AUR_HELPER.get().map(String::as_str)
+    let cached = *AUR_HELPER.read().unwrap_or_else(PoisonError::into_inner);
+    match cached {
+        Some(helper) => helper,
+        None => refresh(),
+    }
 }
 
 /// Check if a command is executable in PATH.
-fn is_executable_in_path(cmd: &str) -> bool {
+pub(crate) fn is_executable_in_path(cmd: &str) -> bool {
     if cmd.contains(std::path::MAIN_SEPARATOR) {
         return PathBuf::from(cmd).is_file();
     }
@@ -87,4 +140,10 @@ mod tests {
         // This test just verifies the function doesn't panic
         let _ = detect();
     }
+
+    #[test]
+    fn test_get_returns_cached_detection() {
+        let found = refresh();
+        assert_eq!(get(), found);
+    }
 }
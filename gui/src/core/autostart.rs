@@ -4,10 +4,10 @@
 //! in the user's autostart directory.
 
 use crate::config;
+use crate::core::escalation;
 use std::fs;
 use std::os::unix::fs::symlink;
 use std::path::PathBuf;
-use std::process::Command;
 
 /// Get the autostart desktop file path
 pub fn get_autostart_path() -> PathBuf {
@@ -56,8 +56,14 @@ pub fn disable() -> Result<(), std::io::Error> {
 
     let system_path = config::paths::system_autostart();
     if system_path.exists() {
-        // Use pkexec to remove the file with root privileges
-        let status = Command::new("pkexec").arg("rm").arg(system_path).status()?;
+        // Remove the file with root privileges
+        let tool = escalation::get().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No privilege escalation tool found (install polkit, sudo or doas)",
+            )
+        })?;
+        let status = tool.command().arg("rm").arg(system_path).status()?;
 
         if !status.success() {
             return Err(std::io::Error::new(
@@ -1,10 +1,11 @@
 //! Daemon management for cyberxero-auth.
 
 use crate::config;
+use crate::core::escalation;
 use anyhow::{Context, Result};
 use log::{info, warn};
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 use std::time::Duration;
 use cyberxero_auth::shared::is_daemon_running;
 
@@ -26,12 +27,17 @@ pub fn start_daemon() -> Result<()> {
         return Ok(());
     }
 
+    let tool = escalation::get().ok_or_else(|| {
+        anyhow::anyhow!("No privilege escalation tool found (install polkit, sudo or doas)")
+    })?;
+
     let daemon_path = get_daemon_path();
     let current_uid = unsafe { libc::getuid() };
     let current_pid = std::process::id();
-    info!("Starting daemon via pkexec: {}", daemon_path.display());
+    info!("Starting daemon via {}: {}", tool, daemon_path.display());
 
-    let mut child = Command::new("pkexec")
+    let mut child = tool
+        .command()
         .arg(daemon_path.as_os_str())
         .arg("--uid")
         .arg(current_uid.to_string())
@@ -40,7 +46,7 @@ pub fn start_daemon() -> Result<()> {
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .spawn()
-        .context("Failed to spawn pkexec")?;
+        .with_context(|| format!("Failed to spawn {}", tool))?;
 
     let socket_path = cyberxero_auth::shared::get_socket_path(None)?;
     let start = std::time::Instant::now();
@@ -53,9 +59,9 @@ pub fn start_daemon() -> Result<()> {
             return Ok(());
         }
 
-        // Check if pkexec has exited (including zombie state)
+        // Check if the launcher has exited (including zombie state)
         if let Ok(Some(_status)) = child.try_wait() {
-            anyhow::bail!("pkexec process has exited (may have been cancelled)");
+            anyhow::bail!("{} process has exited (may have been cancelled)", tool);
         }
 
         if start.elapsed() >= timeout {
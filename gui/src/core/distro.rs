@@ -0,0 +1,103 @@
+//! Distribution detection from `/etc/os-release`.
+//!
+//! The toolkit targets any Arch-based system, but a few features behave
+//! differently on some of them (SteamOS keeps user apps in `~/Applications`
+//! and can add shortcuts to Steam). Detection lives here so those branches
+//! share one answer and can be tested against sample os-release files.
+
+use std::path::Path;
+
+const OS_RELEASE: &str = "/etc/os-release";
+
+/// Steam's helper for adding non-Steam shortcuts. SteamOS ships it, and so
+/// do handheld spins of other distros that copy its session.
+const STEAMOS_ADD_TO_STEAM: &str = "/usr/bin/steamos-add-to-steam";
+
+/// Distributions the toolkit recognises by their os-release `ID`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distro {
+    Arch,
+    EndeavourOS,
+    CachyOS,
+    SteamOS,
+    XeroLinux,
+    Unknown,
+}
+
+impl Distro {
+    fn from_id(id: &str) -> Self {
+        match id {
+            "arch" => Distro::Arch,
+            "endeavouros" => Distro::EndeavourOS,
+            "cachyos" => Distro::CachyOS,
+            "steamos" => Distro::SteamOS,
+            "xerolinux" => Distro::XeroLinux,
+            _ => Distro::Unknown,
+        }
+    }
+
+    /// Name for display, e.g. in the About dialog.
+    pub fn name(self) -> &'static str {
+        match self {
+            Distro::Arch => "Arch Linux",
+            Distro::EndeavourOS => "EndeavourOS",
+            Distro::CachyOS => "CachyOS",
+            Distro::SteamOS => "SteamOS",
+            Distro::XeroLinux => "XeroLinux",
+            Distro::Unknown => "Unknown",
+        }
+    }
+}
+
+/// The running distribution; [`Distro::Unknown`] when os-release is
+/// missing or names something else.
+pub fn detect_distro() -> Distro {
+    std::fs::read_to_string(OS_RELEASE)
+        .map(|text| parse_distro(&text))
+        .unwrap_or(Distro::Unknown)
+}
+
+/// Whether Steam's shortcut helper is available, which is what the
+/// SteamOS-specific paths actually depend on.
+pub fn is_steamos() -> bool {
+    detect_distro() == Distro::SteamOS || Path::new(STEAMOS_ADD_TO_STEAM).exists()
+}
+
+/// Whether the system is managed by pacman at all. Checked against the
+/// binary rather than os-release, which derivatives don't always fill in.
+pub fn is_pacman_based() -> bool {
+    Path::new("/usr/bin/pacman").exists()
+}
+
+fn parse_distro(os_release: &str) -> Distro {
+    os_release
+        .lines()
+        .find_map(|line| line.strip_prefix("ID="))
+        .map(|id| Distro::from_id(id.trim().trim_matches(['"', '\''])))
+        .unwrap_or(Distro::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_known_distros() {
+        let arch = "NAME=\"Arch Linux\"\nPRETTY_NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling";
+        assert_eq!(parse_distro(arch), Distro::Arch);
+
+        let cachy = "NAME=\"CachyOS Linux\"\nID=\"cachyos\"\nID_LIKE=\"arch\"";
+        assert_eq!(parse_distro(cachy), Distro::CachyOS);
+
+        let steamos = "NAME=\"SteamOS\"\nID=steamos\nID_LIKE=arch\nVARIANT_ID=steamdeck";
+        assert_eq!(parse_distro(steamos), Distro::SteamOS);
+    }
+
+    #[test]
+    fn test_parse_ignores_id_like_and_unknown() {
+        // ID_LIKE=arch alone must not make a derivative look like Arch.
+        let manjaro = "NAME=\"Manjaro Linux\"\nID=manjaro\nID_LIKE=arch";
+        assert_eq!(parse_distro(manjaro), Distro::Unknown);
+        assert_eq!(parse_distro(""), Distro::Unknown);
+    }
+}
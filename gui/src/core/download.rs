@@ -2,14 +2,19 @@
 //! progress reporting. Used exclusively by the Arch-ISO download dialog
 //! but kept in `core` so anything else that needs a big background fetch
 //! can reuse the pause/cancel machinery.
+//!
+//! All requests go through one client, and so through the same proxy when
+//! one is configured (see [`proxy`]).
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use log::info;
 use regex::Regex;
+use sha2::{Digest, Sha256, Sha512};
 
 /// Live view of a transfer in flight.
 #[derive(Clone, Debug)]
@@ -20,12 +25,16 @@ pub struct Progress {
     pub bytes_per_second: f64,
 }
 
-/// Shared switches the UI holds on to so it can pause/resume/cancel the
-/// transfer from the main thread while the worker thread drives I/O.
+/// Shared switches the UI holds on to so it can pause/resume/cancel or
+/// throttle the transfer from the main thread while the worker thread
+/// drives I/O.
 #[derive(Clone, Default)]
 pub struct TransferFlags {
     pause: Arc<AtomicBool>,
     cancel: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    /// Bytes per second; 0 means unlimited.
+    rate_limit: Arc<AtomicU64>,
 }
 
 impl TransferFlags {
@@ -48,42 +57,541 @@ impl TransferFlags {
     fn is_cancelled(&self) -> bool {
         self.cancel.load(Ordering::Relaxed)
     }
+
+    /// Like [`request_cancel`](Self::request_cancel), but leaves the `.part`
+    /// file in place so a later download of the same file resumes from it.
+    pub fn request_stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Cancelled or stopped: either way the transfer should wind down.
+    fn is_halted(&self) -> bool {
+        self.is_cancelled() || self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Cap the transfer at `bytes_per_second`, or lift the cap with 0.
+    /// Takes effect from the next chunk.
+    pub fn set_rate_limit(&self, bytes_per_second: u64) {
+        self.rate_limit.store(bytes_per_second, Ordering::Relaxed);
+    }
+
+    fn rate_limit(&self) -> u64 {
+        self.rate_limit.load(Ordering::Relaxed)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared client
+// ---------------------------------------------------------------------------
+
+/// Budget for small requests (mirror listings, checksum files).
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Budget for fetching the detached signature.
+const SIGNATURE_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long an idle pooled connection is kept for the next request.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Runtime every transfer in this module is driven on. The pooled
+/// connections of [`client`] belong to the runtime that opened them, so
+/// callers share this one instead of spinning up their own.
+pub fn runtime() -> Result<&'static tokio::runtime::Runtime> {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if let Some(rt) = RUNTIME.get() {
+        return Ok(rt);
+    }
+    let rt = tokio::runtime::Runtime::new().context("start tokio runtime")?;
+    Ok(RUNTIME.get_or_init(|| rt))
+}
+
+/// Proxy URL for every request: the `download_proxy` setting, else
+/// `HTTPS_PROXY`, else `ALL_PROXY` (upper or lower case). Both `http://`
+/// and `socks5://` proxies work. Resolved once, with the client.
+fn proxy() -> Option<&'static str> {
+    static PROXY: OnceLock<Option<String>> = OnceLock::new();
+    PROXY
+        .get_or_init(|| {
+            crate::core::settings::download_proxy()
+                .filter(|p| !p.trim().is_empty())
+                .or_else(|| {
+                    ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+                        .iter()
+                        .find_map(|var| {
+                            std::env::var(var).ok().filter(|v| !v.trim().is_empty())
+                        })
+                })
+        })
+        .as_deref()
+}
+
+/// `scheme://host:port` of a proxy URL, so credentials never reach logs or
+/// error dialogs.
+fn redact_proxy(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(u) => match (u.host_str(), u.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{}://{host}:{port}", u.scheme()),
+            (Some(host), None) => format!("{}://{host}", u.scheme()),
+            _ => String::from("(unparseable proxy URL)"),
+        },
+        Err(_) => String::from("(unparseable proxy URL)"),
+    }
+}
+
+/// HTTP client shared by every request here, built once so TLS setup and
+/// the connection pool carry over from the mirror lookup to the transfer.
+/// Only connect and stall limits are set client-wide; short requests add
+/// their own total timeout, the transfer itself has none.
+fn client() -> Result<&'static reqwest::Client> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(STALL_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT);
+    if let Some(url) = proxy() {
+        info!("downloads go through proxy {}", redact_proxy(url));
+        let proxy = reqwest::Proxy::all(url)
+            .with_context(|| format!("invalid proxy URL {}", redact_proxy(url)))?;
+        builder = builder.proxy(proxy);
+    }
+    let client = builder.build().context("build http client")?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Wrap a failed request, naming the proxy when the connection couldn't
+/// be made: with a proxy configured that's the hop that failed, not the
+/// mirror behind it.
+fn request_error(e: reqwest::Error, what: &str) -> anyhow::Error {
+    match proxy() {
+        Some(url) if e.is_connect() => anyhow::Error::new(e).context(format!(
+            "{what}: could not connect through proxy {}",
+            redact_proxy(url)
+        )),
+        _ => anyhow::Error::new(e).context(what.to_owned()),
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Mirror lookup
 // ---------------------------------------------------------------------------
 
-const MIRROR_URL: &str = "https://fastly.mirror.pkgbuild.com/iso/latest/";
-const ARCH_ISO_PATTERN: &str = r"archlinux-\d{4}\.\d{2}\.\d{2}-x86_64\.iso";
+/// Where a distribution's published checksum lives.
+enum ChecksumSource {
+    /// A `sha256sums.txt` next to the ISO listing every image.
+    Sha256Sums,
+    /// A file named after the ISO plus this suffix, holding its hash.
+    Sidecar(&'static str),
+}
+
+/// A distribution whose latest ISO can be discovered from a plain mirror
+/// directory listing.
+pub struct IsoSource {
+    pub name: &'static str,
+    /// ISO directories tried in order.
+    mirrors: &'static [&'static str],
+    /// Matches ISO filenames in a listing. Its `date` group orders
+    /// listings that keep several releases, and is shown as the version.
+    pattern: &'static str,
+    checksum: ChecksumSource,
+    /// Whether a detached `.sig` from the Arch release key is published,
+    /// for [`verify_signature`].
+    pub arch_signed: bool,
+}
+
+/// Every distribution the download dialog offers, Arch first.
+pub static ISO_SOURCES: &[IsoSource] = &[
+    IsoSource {
+        name: "Arch Linux",
+        // The official CDN and GeoDNS front, then long-standing tier-1
+        // mirrors.
+        mirrors: &[
+            "https://fastly.mirror.pkgbuild.com/iso/latest/",
+            "https://geo.mirror.pkgbuild.com/iso/latest/",
+            "https://mirrors.kernel.org/archlinux/iso/latest/",
+            "https://mirror.rackspace.com/archlinux/iso/latest/",
+        ],
+        pattern: r"archlinux-(?P<date>\d{4}\.\d{2}\.\d{2})-x86_64\.iso",
+        checksum: ChecksumSource::Sha256Sums,
+        arch_signed: true,
+    },
+    IsoSource {
+        name: "EndeavourOS",
+        mirrors: &[
+            "https://mirror.alpix.eu/endeavouros/iso/",
+            "https://mirrors.gigenet.com/endeavouros/iso/",
+        ],
+        pattern: r"EndeavourOS_[A-Za-z0-9-]+?-(?P<date>\d{4}\.\d{2}\.\d{2})\.iso",
+        checksum: ChecksumSource::Sidecar(".sha512sum"),
+        arch_signed: false,
+    },
+];
 
-/// Resolve the latest Arch Linux ISO to `(filename, absolute url)`.
-pub async fn latest_arch_iso() -> Result<(String, String)> {
-    info!("resolving latest Arch ISO");
+impl IsoSource {
+    /// Release date in `filename`, e.g. `2025.01.01`.
+    pub fn version(&self, filename: &str) -> Option<String> {
+        Regex::new(self.pattern)
+            .ok()?
+            .captures(filename)?
+            .name("date")
+            .map(|m| m.as_str().to_owned())
+    }
+}
+
+/// Resolve the latest ISO of `source` to `(filename, absolute url)`.
+///
+/// Mirrors are tried in order; a timeout, non-200 or unparseable listing
+/// falls through to the next one. The returned URL points at whichever
+/// mirror answered.
+pub async fn latest_iso(source: &IsoSource) -> Result<(String, String)> {
+    info!("resolving latest {} ISO", source.name);
 
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .context("build http client")?;
+    let client = client()?;
+    let re = Regex::new(source.pattern)?;
 
+    let mut last_err = None;
+    for &mirror in source.mirrors {
+        match find_iso_on(client, &re, mirror).await {
+            Ok(filename) => {
+                let url = format!("{mirror}{filename}");
+                info!("latest ISO: {filename} via {mirror}");
+                return Ok((filename, url));
+            }
+            Err(e) => {
+                info!("mirror {mirror} unusable: {e:#}");
+                last_err = Some(e);
+            }
+        }
+    }
+
+    Err(last_err
+        .unwrap_or_else(|| anyhow::anyhow!("no mirrors configured"))
+        .context("all mirrors failed"))
+}
+
+async fn find_iso_on(client: &reqwest::Client, re: &Regex, mirror: &str) -> Result<String> {
     let listing = client
-        .get(MIRROR_URL)
+        .get(mirror)
+        .timeout(LOOKUP_TIMEOUT)
         .send()
         .await
+        .map_err(|e| request_error(e, "fetch mirror index"))?
+        .error_for_status()
         .context("fetch mirror index")?
         .text()
         .await
         .context("read mirror index body")?;
 
-    let re = Regex::new(ARCH_ISO_PATTERN)?;
-    let filename = re
-        .find(&listing)
+    newest_match(re, &listing).context("no ISO filename matched in mirror listing")
+}
+
+/// The match in `listing` with the latest `date`. Dates compare part by
+/// part as numbers, so an unpadded `2024.9.30` still sorts before
+/// `2024.10.01`.
+fn newest_match(re: &Regex, listing: &str) -> Option<String> {
+    let date = |c: &regex::Captures| -> Vec<u32> {
+        c.name("date")
+            .map(|m| {
+                m.as_str()
+                    .split('.')
+                    .map(|part| part.parse().unwrap_or(0))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    re.captures_iter(listing)
+        .max_by(|a, b| date(a).cmp(&date(b)))
+        .and_then(|c| c.get(0))
         .map(|m| m.as_str().to_owned())
-        .context("no ISO filename matched in mirror listing")?;
+}
+
+/// `url` followed by the same file on every other mirror of `source`, for
+/// [`stream_to_file`] to fall back on.
+pub fn mirror_urls(source: &IsoSource, url: &str) -> Vec<String> {
+    let mut urls = vec![url.to_owned()];
+    if let Some((_, filename)) = url.rsplit_once('/') {
+        urls.extend(
+            source
+                .mirrors
+                .iter()
+                .map(|m| format!("{m}{filename}"))
+                .filter(|u| u != url),
+        );
+    }
+    urls
+}
+
+/// Look up the published checksum for the ISO at `iso_url` on the same
+/// mirror: a SHA-256 for Arch, a SHA-512 for EndeavourOS.
+pub async fn iso_checksum(source: &IsoSource, iso_url: &str) -> Result<String> {
+    let (dir, filename) = iso_url
+        .rsplit_once('/')
+        .context("ISO url has no filename")?;
+
+    let sums_url = match source.checksum {
+        ChecksumSource::Sha256Sums => format!("{dir}/sha256sums.txt"),
+        ChecksumSource::Sidecar(suffix) => format!("{iso_url}{suffix}"),
+    };
+    let sums = client()?
+        .get(&sums_url)
+        .timeout(LOOKUP_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| request_error(e, "fetch checksum file"))?
+        .error_for_status()
+        .context("fetch checksum file")?
+        .text()
+        .await
+        .context("read checksum file body")?;
 
-    let url = format!("{MIRROR_URL}{filename}");
-    info!("latest ISO: {filename}");
-    Ok((filename, url))
+    let sidecar = matches!(source.checksum, ChecksumSource::Sidecar(_));
+    find_checksum(&sums, filename, sidecar)
+        .with_context(|| format!("no checksum listed for {filename}"))
+}
+
+/// The hash for `filename` in a `*sum`-style listing of `hash  name` or
+/// `hash *name` (binary mode) lines. A `sidecar` file describes a single
+/// image, so a line holding only a hash counts too.
+fn find_checksum(sums: &str, filename: &str, sidecar: bool) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let mut fields = line.trim().splitn(2, char::is_whitespace);
+        let hash = fields.next().filter(|h| !h.is_empty())?;
+        let listed = match fields.next() {
+            Some(name) => name.trim().trim_start_matches('*') == filename,
+            None => sidecar,
+        };
+        listed.then(|| hash.to_ascii_lowercase())
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Integrity check
+// ---------------------------------------------------------------------------
+
+/// The downloaded file doesn't match its published checksum. Kept as its
+/// own type so callers can tell corruption apart from network failures via
+/// `anyhow::Error::downcast_ref`.
+#[derive(Debug)]
+pub struct ChecksumMismatch {
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Checksum mismatch (expected {}, got {}). The corrupted file was deleted.",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// Fingerprint of the Arch Linux release signing key (Pierre Schmitz), as
+/// published on <https://archlinux.org/download/>.
+const RELEASE_KEY_FINGERPRINT: &str = "3E80CA1A8B89F69CBA57D98A76A5EF9054449A5C";
+
+/// Outcome of [`verify_signature`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Good signature from the release key.
+    Verified,
+    /// Bad signature, or a good one from some other key. The file has been
+    /// deleted.
+    Invalid,
+    /// Couldn't check: gpg is missing, or the release key isn't in the
+    /// user's keyring or has expired there. The file is left alone.
+    KeyUnavailable,
+}
+
+/// Check `path` against the detached `<iso_url>.sig` using the user's gpg
+/// keyring. The key is never fetched implicitly; users import it with
+/// `gpg --auto-key-locate clear,wkd --locate-external-keys pierre@archlinux.org`.
+pub async fn verify_signature(iso_url: &str, path: &str) -> Result<SignatureStatus> {
+    info!("verifying PGP signature of {path}");
+
+    let sig = client()?
+        .get(format!("{iso_url}.sig"))
+        .timeout(SIGNATURE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| request_error(e, "fetch signature"))?
+        .error_for_status()
+        .context("fetch signature")?
+        .bytes()
+        .await
+        .context("read signature body")?;
+
+    let sig_path = format!("{path}.sig");
+    tokio::fs::write(&sig_path, &sig)
+        .await
+        .context("write signature file")?;
+
+    let output = tokio::process::Command::new("gpg")
+        .args(["--batch", "--status-fd", "1", "--verify", &sig_path, path])
+        .output()
+        .await;
+    let _ = tokio::fs::remove_file(&sig_path).await;
+
+    let output = match output {
+        Ok(o) => o,
+        Err(e) => {
+            info!("could not run gpg: {e}");
+            return Ok(SignatureStatus::KeyUnavailable);
+        }
+    };
+
+    let status = parse_gpg_status(&String::from_utf8_lossy(&output.stdout));
+    info!("signature check: {status:?}");
+    if status == SignatureStatus::Invalid {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+    Ok(status)
+}
+
+/// Interpret gpg's `--status-fd` output. Only a valid signature whose key
+/// list includes the release key counts; a bad or revoked one, or a valid
+/// one from any other key, is rejected. A good signature from an expired
+/// key can't be vouched for until the key is refreshed, so it is reported
+/// like a missing key.
+fn parse_gpg_status(status: &str) -> SignatureStatus {
+    let mut rejected = false;
+    let mut expired = false;
+    let mut valid = None;
+    for line in status.lines() {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some("[GNUPG:]") {
+            continue;
+        }
+        match fields.next() {
+            Some("BADSIG" | "REVKEYSIG") => rejected = true,
+            Some("EXPKEYSIG") => expired = true,
+            Some("VALIDSIG") => {
+                // Signing subkey fingerprint first, primary key last.
+                valid = Some(fields.any(|f| f.eq_ignore_ascii_case(RELEASE_KEY_FINGERPRINT)));
+            }
+            _ => {}
+        }
+    }
+    match valid {
+        _ if rejected => SignatureStatus::Invalid,
+        Some(false) => SignatureStatus::Invalid,
+        Some(true) if expired => SignatureStatus::KeyUnavailable,
+        Some(true) => SignatureStatus::Verified,
+        None => SignatureStatus::KeyUnavailable,
+    }
+}
+
+/// Not enough free space on the destination filesystem to finish the
+/// transfer. Raised before any bytes are written.
+#[derive(Debug)]
+pub struct InsufficientSpace {
+    /// Bytes still to be written, including the safety margin.
+    pub needed: u64,
+    pub available: u64,
+}
+
+impl fmt::Display for InsufficientSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Not enough disk space: {} needed but only {} available. Free up {} and try again.",
+            humanize_bytes(self.needed),
+            humanize_bytes(self.available),
+            humanize_bytes(self.needed.saturating_sub(self.available)),
+        )
+    }
+}
+
+impl std::error::Error for InsufficientSpace {}
+
+/// The transfer ended with a different byte count than the server
+/// announced, e.g. a proxy cut the stream short or padded it. The partial
+/// file is deleted rather than handed to the checksum step.
+#[derive(Debug)]
+pub struct IncompleteDownload {
+    pub expected: u64,
+    pub received: u64,
+}
+
+impl fmt::Display for IncompleteDownload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The download ended at {} of the {} the server announced. The file was deleted; \
+             please try again.",
+            humanize_bytes(self.received),
+            humanize_bytes(self.expected),
+        )
+    }
+}
+
+impl std::error::Error for IncompleteDownload {}
+
+/// Extra headroom required on top of the remaining download size.
+const SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+/// Free bytes available to unprivileged users on the filesystem holding
+/// `path`.
+fn available_space(path: &str) -> Result<u64> {
+    use std::ffi::CString;
+
+    let c_path = CString::new(path).context("path contains a NUL byte")?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("statvfs");
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Hash `path` in fixed-size chunks and compare against `expected`, a
+/// SHA-256 or SHA-512 told apart by length. On mismatch the file is deleted
+/// and a [`ChecksumMismatch`] is returned.
+pub async fn verify_checksum(path: &str, expected: &str) -> Result<()> {
+    use sha2::digest::DynDigest;
+    use tokio::io::AsyncReadExt;
+
+    let expected = expected.trim().to_ascii_lowercase();
+    let mut hasher: Box<dyn DynDigest> = if expected.len() == 128 {
+        info!("verifying SHA-512 of {path}");
+        Box::new(Sha512::new())
+    } else {
+        info!("verifying SHA-256 of {path}");
+        Box::new(Sha256::new())
+    };
+
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .context("open file for verification")?;
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .await
+            .context("read file for verification")?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    drop(file);
+
+    let actual: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+
+    if actual != expected {
+        let _ = tokio::fs::remove_file(path).await;
+        return Err(ChecksumMismatch { expected, actual }.into());
+    }
+
+    info!("checksum OK");
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -91,19 +599,40 @@ pub async fn latest_arch_iso() -> Result<(String, String)> {
 // ---------------------------------------------------------------------------
 
 const SPEED_WINDOW: usize = 20;
+/// Files smaller than this aren't worth splitting across connections.
+const SEGMENT_MIN_SIZE: u64 = 64 * 1024 * 1024;
+/// Consecutive failures after which a segment gives up, and the transfer
+/// falls back to a single stream.
+const SEGMENT_RETRIES: u32 = 5;
 const PROGRESS_TICK: Duration = Duration::from_millis(100);
 const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+/// No bytes for this long counts as a stalled connection.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
 
-/// Stream `url` into `dest`, calling `on_progress` roughly every 100ms.
+/// Stream the file at `urls[0]` into [`part_path`] of `dest`, calling
+/// `on_progress` roughly every 100ms. The remaining `urls` are mirrors of
+/// the same file. `dest` itself is only created by [`commit_download`],
+/// once the caller has verified the `.part` file.
 ///
+/// - Picks up an existing `.part` file (e.g. left behind when the app was
+///   closed mid-transfer, or by a crash) instead of starting over.
 /// - Reconnects automatically on transient errors, using HTTP `Range` to
-///   resume from where we left off.
+///   resume from where we left off. Servers that ignore `Range` get the
+///   file truncated and re-fetched from the start.
+/// - Each failed attempt, including a stalled stream, moves on to the next
+///   mirror in `urls`, wrapping around.
 /// - Honours [`TransferFlags::set_paused`] by dropping the current
 ///   connection and sleeping until the flag clears.
 /// - Honours [`TransferFlags::request_cancel`] by bailing out and deleting
-///   the partial file.
+///   the partial file, and [`TransferFlags::request_stop`] by bailing out
+///   and keeping it.
+/// - Honours [`TransferFlags::set_rate_limit`] by sleeping between chunks
+///   whenever it gets ahead of the allowed rate.
+/// - Splits a fresh download of a large file across several connections
+///   (the `download_segments` setting) when the server advertises
+///   `Accept-Ranges: bytes`, falling back to one stream if that fails.
 pub async fn stream_to_file<F>(
-    url: String,
+    urls: Vec<String>,
     dest: String,
     mut on_progress: F,
     flags: TransferFlags,
@@ -112,38 +641,120 @@ where
     F: FnMut(Progress) + Send + 'static,
 {
     use futures_util::StreamExt;
-    use reqwest::header::RANGE;
+    use reqwest::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+    use reqwest::StatusCode;
     use tokio::io::AsyncWriteExt;
 
-    info!("stream_to_file: {url} -> {dest}");
+    anyhow::ensure!(!urls.is_empty(), "no download url given");
+    let part = part_path(&dest);
+    info!("stream_to_file: {} -> {part}", urls[0]);
 
-    let client = reqwest::Client::builder()
-        .connect_timeout(Duration::from_secs(30))
-        .build()
-        .context("build http client")?;
+    let client = client()?;
+    let mut mirror = 0usize;
 
-    let mut file = tokio::fs::File::create(&dest)
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part)
         .await
-        .context("create destination file")?;
+        .context("open destination file")?;
 
     // Try HEAD first; a failure here is non-fatal, since the first GET
     // response will usually report `Content-Length` too.
     let mut total: u64 = 0;
-    if let Ok(head) = client.head(&url).send().await {
+    let mut accepts_ranges = false;
+    if let Ok(head) = client.head(&urls[0]).send().await {
         if let Some(len) = head.content_length() {
             total = len;
             info!("total size from HEAD: {total}");
         }
+        accepts_ranges = head
+            .headers()
+            .get(ACCEPT_RANGES)
+            .is_some_and(|v| v.as_bytes() == b"bytes");
+    }
+
+    // A segmented run writes out of order, so its length says nothing
+    // about how much arrived; if one was interrupted, start over.
+    let marker = segment_marker(&part);
+    if tokio::fs::try_exists(&marker).await.unwrap_or(false) {
+        info!("discarding interrupted segmented download");
+        file.set_len(0).await.context("truncate destination file")?;
+        let _ = tokio::fs::remove_file(&marker).await;
+    }
+
+    let mut received = file
+        .metadata()
+        .await
+        .context("stat destination file")?
+        .len();
+    if received > 0 {
+        if total > 0 && received > total {
+            info!("partial file larger than remote ({received} > {total}); restarting");
+            file.set_len(0).await.context("truncate destination file")?;
+            received = 0;
+        } else {
+            info!("found partial download of {received} bytes");
+        }
+    }
+
+    if total > 0 {
+        let needed = total.saturating_sub(received) + SPACE_MARGIN;
+        match available_space(&part) {
+            Ok(available) if available < needed => {
+                drop(file);
+                if received == 0 {
+                    let _ = tokio::fs::remove_file(&part).await;
+                }
+                return Err(InsufficientSpace { needed, available }.into());
+            }
+            Ok(_) => {}
+            Err(e) => info!("free space check skipped: {e:#}"),
+        }
+    }
+
+    let segments = u64::from(crate::core::settings::download_segments().max(1));
+    if received == 0 && segments > 1 && accepts_ranges && total >= SEGMENT_MIN_SIZE {
+        info!("fetching in {segments} segments");
+        match fetch_segmented(client, &urls, &part, total, segments, &flags, &mut on_progress)
+            .await
+        {
+            Ok(()) => {
+                let _ = tokio::fs::remove_file(&marker).await;
+                drop(file);
+                on_progress(Progress {
+                    bytes_received: total,
+                    bytes_total: total,
+                    bytes_per_second: 0.0,
+                });
+                info!("transfer complete: {part}");
+                return Ok(());
+            }
+            Err(_) if flags.is_cancelled() => {
+                let _ = tokio::fs::remove_file(&marker).await;
+                cleanup_partial(file, &part).await;
+                anyhow::bail!("Download cancelled");
+            }
+            // The marker stays, so the next attempt starts this one over.
+            Err(_) if flags.is_halted() => {
+                info!("transfer stopped; keeping {part}");
+                anyhow::bail!("Download cancelled");
+            }
+            Err(e) => {
+                info!("segmented download failed ({e:#}); falling back to one stream");
+                file.set_len(0).await.context("truncate destination file")?;
+                let _ = tokio::fs::remove_file(&marker).await;
+            }
+        }
     }
 
-    let mut received: u64 = 0;
     let mut window = SpeedWindow::with_capacity(SPEED_WINDOW);
     let mut last_tick = Instant::now();
-    let mut last_bytes: u64 = 0;
+    let mut last_bytes: u64 = received;
 
     loop {
-        if flags.is_cancelled() {
-            cleanup_partial(file, &dest).await;
+        if flags.is_halted() {
+            halt(file, &part, &flags).await;
             anyhow::bail!("Download cancelled");
         }
         if flags.is_paused() {
@@ -154,7 +765,8 @@ where
             break;
         }
 
-        let mut request = client.get(&url);
+        let url = &urls[mirror];
+        let mut request = client.get(url);
         if received > 0 {
             info!("resuming at byte {received}");
             request = request.header(RANGE, format!("bytes={received}-"));
@@ -163,12 +775,48 @@ where
         let response = match request.send().await {
             Ok(r) => r,
             Err(e) => {
-                info!("connect error: {e}; retrying in {:?}", RETRY_BACKOFF);
+                info!(
+                    "{:#}; retrying in {:?}",
+                    request_error(e, "connect error"),
+                    RETRY_BACKOFF
+                );
+                mirror = next_mirror(mirror, &urls);
                 tokio::time::sleep(RETRY_BACKOFF).await;
                 continue;
             }
         };
 
+        let status = response.status();
+
+        // Asked for a range but got the whole body back: start over.
+        if received > 0 && status == StatusCode::OK {
+            info!("server ignored Range; restarting from zero");
+            file.set_len(0).await.context("truncate destination file")?;
+            received = 0;
+            last_bytes = 0;
+        }
+
+        // A resumed response has to describe the same file we started.
+        if status == StatusCode::PARTIAL_CONTENT {
+            let remote_total = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit('/').next())
+                .and_then(|v| v.parse::<u64>().ok());
+            if let Some(remote_total) = remote_total {
+                if total > 0 && remote_total != total {
+                    info!("remote size changed ({total} -> {remote_total}); restarting");
+                    file.set_len(0).await.context("truncate destination file")?;
+                    received = 0;
+                    last_bytes = 0;
+                    total = remote_total;
+                    continue;
+                }
+                total = remote_total;
+            }
+        }
+
         if total == 0 {
             if let Some(len) = response.content_length() {
                 total = received + len;
@@ -176,25 +824,26 @@ where
             }
         }
 
-        let status = response.status();
         if !status.is_success() {
             info!("HTTP {status}");
-            if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+            if status == StatusCode::RANGE_NOT_SATISFIABLE
                 && total > 0
                 && received >= total
             {
                 break;
             }
+            mirror = next_mirror(mirror, &urls);
             tokio::time::sleep(RETRY_BACKOFF).await;
             continue;
         }
 
         let mut stream = response.bytes_stream();
         let mut interrupted = false;
+        let mut pacer = Pacer::default();
 
         while let Some(chunk) = stream.next().await {
-            if flags.is_cancelled() {
-                cleanup_partial(file, &dest).await;
+            if flags.is_halted() {
+                halt(file, &part, &flags).await;
                 anyhow::bail!("Download cancelled");
             }
             if flags.is_paused() {
@@ -206,6 +855,7 @@ where
                 Ok(bytes) => {
                     file.write_all(&bytes).await?;
                     received += bytes.len() as u64;
+                    pacer.pace(bytes.len() as u64, flags.rate_limit()).await;
 
                     let now = Instant::now();
                     if now.duration_since(last_tick) >= PROGRESS_TICK {
@@ -224,6 +874,7 @@ where
                 Err(e) => {
                     info!("chunk error: {e}");
                     interrupted = true;
+                    mirror = next_mirror(mirror, &urls);
                     break;
                 }
             }
@@ -235,6 +886,16 @@ where
     }
 
     file.flush().await?;
+
+    if total > 0 && received != total {
+        info!("size mismatch: received {received} of {total} bytes");
+        cleanup_partial(file, &part).await;
+        return Err(IncompleteDownload {
+            expected: total,
+            received,
+        }
+        .into());
+    }
     drop(file);
 
     on_progress(Progress {
@@ -243,7 +904,253 @@ where
         bytes_per_second: 0.0,
     });
 
-    info!("transfer complete: {dest}");
+    info!("transfer complete: {part}");
+    Ok(())
+}
+
+fn next_mirror(current: usize, urls: &[String]) -> usize {
+    let next = (current + 1) % urls.len();
+    if next != current {
+        info!("switching to mirror {}", urls[next]);
+    }
+    next
+}
+
+/// Present while a segmented download is writing into `part`.
+fn segment_marker(part: &str) -> String {
+    format!("{part}.segmented")
+}
+
+/// Fetch `total` bytes as `segments` concurrent ranges, each written at its
+/// own offset in `part`, reporting combined progress. Any segment that
+/// fails for good takes the rest down with it.
+async fn fetch_segmented<F>(
+    client: &'static reqwest::Client,
+    urls: &[String],
+    part: &str,
+    total: u64,
+    segments: u64,
+    flags: &TransferFlags,
+    on_progress: &mut F,
+) -> Result<()>
+where
+    F: FnMut(Progress),
+{
+    tokio::fs::write(segment_marker(part), b"")
+        .await
+        .context("create segment marker")?;
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(part)
+        .await
+        .context("open destination file")?
+        .set_len(total)
+        .await
+        .context("preallocate destination file")?;
+
+    // Segments move between mirrors on failure, so only hand them to
+    // mirrors that serve byte ranges of this exact file; `urls[0]` was
+    // already probed by the caller.
+    let probes = urls[1..].iter().map(|url| async move {
+        match client.head(url).timeout(LOOKUP_TIMEOUT).send().await {
+            Ok(head) if serves_ranges_of(&head, total) => Some(url.clone()),
+            Ok(_) => {
+                info!("not using {url} for segments: no range support or a different size");
+                None
+            }
+            Err(e) => {
+                info!("not using {url} for segments: {e}");
+                None
+            }
+        }
+    });
+    let usable = futures_util::future::join_all(probes).await;
+    let urls: Vec<String> = std::iter::once(urls[0].clone())
+        .chain(usable.into_iter().flatten())
+        .collect();
+
+    let received = Arc::new(AtomicU64::new(0));
+    let size = total.div_ceil(segments);
+    let mut tasks = tokio::task::JoinSet::new();
+    for index in 0..segments {
+        let start = index * size;
+        if start >= total {
+            break;
+        }
+        tasks.spawn(fetch_segment(Segment {
+            client,
+            urls: urls.clone(),
+            // Spread the segments over the mirrors from the start.
+            mirror: index as usize % urls.len(),
+            part: part.to_owned(),
+            start,
+            end: (start + size).min(total) - 1,
+            flags: flags.clone(),
+            received: received.clone(),
+            share: segments,
+        }));
+    }
+
+    let mut window = SpeedWindow::with_capacity(SPEED_WINDOW);
+    let mut ticker = tokio::time::interval(PROGRESS_TICK);
+    let mut last_tick = Instant::now();
+    let mut last_bytes = 0u64;
+    loop {
+        tokio::select! {
+            joined = tasks.join_next() => match joined {
+                None => break,
+                Some(Ok(Ok(()))) => {}
+                Some(Ok(Err(e))) => return Err(e),
+                Some(Err(e)) => return Err(e).context("segment task"),
+            },
+            _ = ticker.tick() => {
+                let now = Instant::now();
+                let bytes = received.load(Ordering::Relaxed);
+                let elapsed = now.duration_since(last_tick).as_secs_f64();
+                if elapsed > 0.0 {
+                    window.push(bytes.saturating_sub(last_bytes) as f64 / elapsed);
+                }
+                on_progress(Progress {
+                    bytes_received: bytes,
+                    bytes_total: total,
+                    bytes_per_second: window.average(),
+                });
+                last_tick = now;
+                last_bytes = bytes;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a HEAD response advertises byte ranges for a file of `total`
+/// bytes.
+fn serves_ranges_of(head: &reqwest::Response, total: u64) -> bool {
+    head.status().is_success()
+        && head.content_length() == Some(total)
+        && head
+            .headers()
+            .get(reqwest::header::ACCEPT_RANGES)
+            .is_some_and(|v| v.as_bytes() == b"bytes")
+}
+
+/// One byte range of a segmented download, `start..=end`.
+struct Segment {
+    client: &'static reqwest::Client,
+    urls: Vec<String>,
+    mirror: usize,
+    part: String,
+    start: u64,
+    end: u64,
+    flags: TransferFlags,
+    /// Bytes received across all segments.
+    received: Arc<AtomicU64>,
+    /// Number of segments sharing the rate limit.
+    share: u64,
+}
+
+async fn fetch_segment(mut seg: Segment) -> Result<()> {
+    use futures_util::StreamExt;
+    use reqwest::header::RANGE;
+    use reqwest::StatusCode;
+    use std::io::SeekFrom;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(&seg.part)
+        .await
+        .context("open destination file")?;
+    let mut pos = seg.start;
+    let mut failures = 0u32;
+
+    while pos <= seg.end {
+        if seg.flags.is_halted() {
+            anyhow::bail!("Download cancelled");
+        }
+        if seg.flags.is_paused() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+
+        let request = seg
+            .client
+            .get(&seg.urls[seg.mirror])
+            .header(RANGE, format!("bytes={pos}-{}", seg.end));
+        let failure = match request.send().await {
+            Ok(r) if r.status() == StatusCode::PARTIAL_CONTENT => {
+                failures = 0;
+                file.seek(SeekFrom::Start(pos))
+                    .await
+                    .context("seek in destination file")?;
+
+                let mut stream = r.bytes_stream();
+                let mut pacer = Pacer::default();
+                let mut failure = None;
+                while let Some(chunk) = stream.next().await {
+                    if seg.flags.is_halted() {
+                        anyhow::bail!("Download cancelled");
+                    }
+                    if seg.flags.is_paused() {
+                        break;
+                    }
+                    match chunk {
+                        Ok(bytes) => {
+                            let take = bytes.len().min((seg.end + 1 - pos) as usize);
+                            file.write_all(&bytes[..take]).await?;
+                            pos += take as u64;
+                            seg.received.fetch_add(take as u64, Ordering::Relaxed);
+                            let limit = match seg.flags.rate_limit() {
+                                0 => 0,
+                                limit => (limit / seg.share).max(1),
+                            };
+                            pacer.pace(take as u64, limit).await;
+                            if pos > seg.end {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            failure = Some(e.to_string());
+                            break;
+                        }
+                    }
+                }
+                failure
+            }
+            Ok(r) if r.status() == StatusCode::OK => {
+                anyhow::bail!("server ignored Range for a segment")
+            }
+            Ok(r) => Some(format!("HTTP {}", r.status())),
+            Err(e) => Some(format!("{:#}", request_error(e, "connect error"))),
+        };
+
+        if let Some(why) = failure {
+            failures += 1;
+            info!("segment at byte {pos}: {why}");
+            if failures > SEGMENT_RETRIES {
+                anyhow::bail!("segment {}-{} failed: {why}", seg.start, seg.end);
+            }
+            seg.mirror = next_mirror(seg.mirror, &seg.urls);
+            tokio::time::sleep(RETRY_BACKOFF).await;
+        }
+    }
+
+    file.flush().await?;
+    Ok(())
+}
+
+/// Where [`stream_to_file`] writes until the download is verified.
+pub fn part_path(dest: &str) -> String {
+    format!("{dest}.part")
+}
+
+/// Move a finished, verified `.part` file into place at `dest`. Only a
+/// complete download ever appears under its real name.
+pub async fn commit_download(dest: &str) -> Result<()> {
+    tokio::fs::rename(part_path(dest), dest)
+        .await
+        .with_context(|| format!("move download into place at {dest}"))?;
+    info!("saved {dest}");
     Ok(())
 }
 
@@ -252,6 +1159,59 @@ async fn cleanup_partial(file: tokio::fs::File, path: &str) {
     let _ = tokio::fs::remove_file(path).await;
 }
 
+/// Wind down a halted single-stream transfer: delete the partial file on
+/// cancel, flush and keep it on stop.
+async fn halt(mut file: tokio::fs::File, path: &str, flags: &TransferFlags) {
+    use tokio::io::AsyncWriteExt;
+
+    if flags.is_cancelled() {
+        cleanup_partial(file, path).await;
+    } else {
+        let _ = file.flush().await;
+        info!("transfer stopped; keeping {path}");
+    }
+}
+
+/// Keeps a stream under a byte rate by sleeping off whatever it's ahead of
+/// schedule. Restarts its schedule whenever the limit changes, so raising or
+/// lifting the cap mid-transfer takes effect at once instead of paying back
+/// a debt built up under the old one.
+struct Pacer {
+    limit: u64,
+    since: Instant,
+    bytes: u64,
+}
+
+impl Default for Pacer {
+    fn default() -> Self {
+        Self {
+            limit: 0,
+            since: Instant::now(),
+            bytes: 0,
+        }
+    }
+}
+
+impl Pacer {
+    async fn pace(&mut self, chunk: u64, limit: u64) {
+        if limit != self.limit {
+            *self = Self {
+                limit,
+                ..Self::default()
+            };
+        }
+        if limit == 0 {
+            return;
+        }
+        self.bytes += chunk;
+        let due = Duration::from_secs_f64(self.bytes as f64 / limit as f64);
+        let elapsed = self.since.elapsed();
+        if due > elapsed {
+            tokio::time::sleep(due - elapsed).await;
+        }
+    }
+}
+
 /// Rolling window of recent byte-rate samples used to smooth the speed
 /// readout. Samples older than `capacity` entries are evicted.
 struct SpeedWindow {
@@ -325,3 +1285,124 @@ pub fn humanize_eta(seconds: u64) -> String {
         format!("{secs}s")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTHER_KEY: &str = "0123456789ABCDEF0123456789ABCDEF01234567";
+
+    fn validsig(fingerprint: &str) -> String {
+        format!(
+            "[GNUPG:] VALIDSIG {fingerprint} 2024-10-01 1727740800 0 4 0 22 10 00 {fingerprint}\n"
+        )
+    }
+
+    #[test]
+    fn test_newest_match_orders_dates_numerically() {
+        let re = Regex::new(r"archlinux-(?P<date>\d{4}\.\d{1,2}\.\d{1,2})-x86_64\.iso").unwrap();
+        let listing = r#"<a href="archlinux-2024.9.30-x86_64.iso">archlinux-2024.9.30-x86_64.iso</a>
+<a href="archlinux-2024.10.01-x86_64.iso">archlinux-2024.10.01-x86_64.iso</a>
+<a href="archlinux-2023.12.01-x86_64.iso">archlinux-2023.12.01-x86_64.iso</a>"#;
+        assert_eq!(
+            newest_match(&re, listing).as_deref(),
+            Some("archlinux-2024.10.01-x86_64.iso")
+        );
+    }
+
+    #[test]
+    fn test_newest_match_none() {
+        let re = Regex::new(ISO_SOURCES[0].pattern).unwrap();
+        assert_eq!(newest_match(&re, "<a href=\"../\">../</a>"), None);
+    }
+
+    #[test]
+    fn test_find_checksum() {
+        let sums = "ABC123  archlinux-2024.10.01-x86_64.iso\n\
+                    def456 *archlinux-bootstrap-2024.10.01-x86_64.tar.zst\n";
+        assert_eq!(
+            find_checksum(sums, "archlinux-2024.10.01-x86_64.iso", false).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            find_checksum(sums, "archlinux-bootstrap-2024.10.01-x86_64.tar.zst", false).as_deref(),
+            Some("def456")
+        );
+        assert_eq!(
+            find_checksum(sums, "archlinux-2024.9.30-x86_64.iso", false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_checksum_sidecar() {
+        let iso = "EndeavourOS_Mercury-2025.02.08.iso";
+        let named = format!("0a1b2c  {iso}\n");
+        assert_eq!(find_checksum(&named, iso, true).as_deref(), Some("0a1b2c"));
+        assert_eq!(
+            find_checksum("0A1B2C\n", iso, true).as_deref(),
+            Some("0a1b2c")
+        );
+        assert_eq!(find_checksum("0a1b2c  other.iso\n", iso, true), None);
+        assert_eq!(find_checksum("", iso, true), None);
+    }
+
+    #[test]
+    fn test_gpg_status_verified() {
+        let status = format!(
+            "[GNUPG:] NEWSIG\n\
+             [GNUPG:] KEY_CONSIDERED {RELEASE_KEY_FINGERPRINT} 0\n\
+             [GNUPG:] SIG_ID k1PdWcJm3XqF0xq2kJb0uAl3Vbk 2024-10-01 1727740800\n\
+             [GNUPG:] GOODSIG 76A5EF9054449A5C Pierre Schmitz <pierre@archlinux.org>\n\
+             {}\
+             [GNUPG:] TRUST_UNDEFINED 0 pgp\n",
+            validsig(RELEASE_KEY_FINGERPRINT)
+        );
+        assert_eq!(parse_gpg_status(&status), SignatureStatus::Verified);
+    }
+
+    #[test]
+    fn test_gpg_status_other_key() {
+        let status = format!(
+            "[GNUPG:] NEWSIG\n\
+             [GNUPG:] GOODSIG 0123456789ABCDEF Someone Else <someone@example.org>\n\
+             {}",
+            validsig(OTHER_KEY)
+        );
+        assert_eq!(parse_gpg_status(&status), SignatureStatus::Invalid);
+    }
+
+    #[test]
+    fn test_gpg_status_badsig() {
+        let status = "[GNUPG:] NEWSIG\n\
+                      [GNUPG:] BADSIG 76A5EF9054449A5C Pierre Schmitz <pierre@archlinux.org>\n";
+        assert_eq!(parse_gpg_status(status), SignatureStatus::Invalid);
+    }
+
+    #[test]
+    fn test_gpg_status_expired_key() {
+        let status = format!(
+            "[GNUPG:] NEWSIG\n\
+             [GNUPG:] KEYEXPIRED 1700000000\n\
+             [GNUPG:] EXPKEYSIG 76A5EF9054449A5C Pierre Schmitz <pierre@archlinux.org>\n\
+             {}",
+            validsig(RELEASE_KEY_FINGERPRINT)
+        );
+        assert_eq!(parse_gpg_status(&status), SignatureStatus::KeyUnavailable);
+    }
+
+    #[test]
+    fn test_gpg_status_missing_key() {
+        let status = format!(
+            "[GNUPG:] NEWSIG\n\
+             [GNUPG:] ERRSIG 76A5EF9054449A5C 22 10 00 1727740800 9 {RELEASE_KEY_FINGERPRINT}\n\
+             [GNUPG:] NO_PUBKEY 76A5EF9054449A5C\n"
+        );
+        assert_eq!(parse_gpg_status(&status), SignatureStatus::KeyUnavailable);
+    }
+
+    #[test]
+    fn test_gpg_status_empty() {
+        assert_eq!(parse_gpg_status(""), SignatureStatus::KeyUnavailable);
+    }
+}
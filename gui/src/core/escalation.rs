@@ -0,0 +1,120 @@
+//! Privilege escalation tool detection.
+//!
+//! The auth daemon is started once per sequence through whichever tool is
+//! available here; every elevated step after that talks to the daemon. On
+//! minimal installs without polkit we fall back to sudo, then doas.
+
+use log::{debug, warn};
+use std::fmt;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use super::aur::is_executable_in_path;
+use super::settings;
+
+/// Environment variable that forces a specific tool (`pkexec`, `sudo`, `doas`).
+pub const OVERRIDE_ENV: &str = "CYBERXERO_TOOLKIT_ESCALATION";
+
+/// Global storage for the selected tool.
+static TOOL: OnceLock<Option<Tool>> = OnceLock::new();
+
+/// A supported privilege escalation program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Pkexec,
+    Sudo,
+    Doas,
+}
+
+/// Priority order for detection.
+const PRIORITY: [Tool; 3] = [Tool::Pkexec, Tool::Sudo, Tool::Doas];
+
+impl Tool {
+    /// Binary name looked up in `PATH`.
+    pub fn program(self) -> &'static str {
+        match self {
+            Tool::Pkexec => "pkexec",
+            Tool::Sudo => "sudo",
+            Tool::Doas => "doas",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        PRIORITY
+            .into_iter()
+            .find(|t| t.program().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Start a command that will run whatever args are appended as root.
+    ///
+    /// pkexec brings its own graphical prompt. sudo uses `-A` when an askpass
+    /// helper is configured; otherwise sudo and doas prompt on the
+    /// controlling terminal, which only works when launched from one.
+    pub fn command(self) -> Command {
+        let mut cmd = Command::new(self.program());
+        if self == Tool::Sudo && std::env::var_os("SUDO_ASKPASS").is_some() {
+            cmd.arg("-A");
+        }
+        cmd
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.program())
+    }
+}
+
+/// Detect the escalation tool to use.
+///
+/// Honours [`OVERRIDE_ENV`], then the `escalation` setting, if it names an
+/// installed tool, then searches in priority order (pkexec, sudo, doas).
+pub fn detect() -> Option<Tool> {
+    let requested = std::env::var(OVERRIDE_ENV)
+        .ok()
+        .map(|name| (name, OVERRIDE_ENV))
+        .or_else(|| settings::escalation().map(|name| (name, "settings")));
+    if let Some((name, source)) = requested {
+        match Tool::from_name(&name) {
+            Some(tool) if is_executable_in_path(tool.program()) => {
+                debug!("Using escalation override: {}", tool);
+                return Some(tool);
+            }
+            Some(tool) => warn!("{} requested via {} but not installed", tool, source),
+            None => warn!("Unknown escalation tool in {}: {}", source, name),
+        }
+    }
+
+    for tool in PRIORITY {
+        if is_executable_in_path(tool.program()) {
+            debug!("Found escalation tool: {}", tool);
+            return Some(tool);
+        }
+    }
+
+    debug!("No escalation tool found");
+    None
+}
+
+/// Get the selected tool, detecting it on first use.
+pub fn get() -> Option<Tool> {
+    *TOOL.get_or_init(detect)
+}
+
+/// Program to prefix interactive terminal commands with. Falls back to
+/// pkexec so the terminal at least shows a meaningful "not found" error.
+pub fn program() -> &'static str {
+    get().map_or("pkexec", Tool::program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name_matches_known_tools() {
+        assert_eq!(Tool::from_name("sudo"), Some(Tool::Sudo));
+        assert_eq!(Tool::from_name(" DOAS "), Some(Tool::Doas));
+        assert_eq!(Tool::from_name("su"), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! GPU vendor detection from `lspci -nn`.
+//!
+//! Vendors are matched on PCI vendor IDs rather than names, so "Advanced
+//! Micro Devices, Inc. [AMD/ATI]" and rebranded Intel parts can't slip
+//! through a substring check. Every display controller is reported, which
+//! covers hybrid laptops with an integrated and a discrete GPU.
+
+use crate::ui::utils::run_command;
+
+/// GPU vendors the toolkit installs drivers for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl GpuVendor {
+    fn from_pci_id(vendor: &str) -> Option<Self> {
+        match vendor.to_ascii_lowercase().as_str() {
+            "10de" => Some(GpuVendor::Nvidia),
+            "1002" => Some(GpuVendor::Amd),
+            "8086" => Some(GpuVendor::Intel),
+            _ => None,
+        }
+    }
+}
+
+/// Vendors of every GPU in the machine, sorted and without duplicates.
+/// Empty when `lspci` is missing or nothing is recognised.
+pub fn detect_gpu_vendors() -> Vec<GpuVendor> {
+    run_command("lspci", &["-nn"])
+        .map(|out| parse_gpu_vendors(&out))
+        .unwrap_or_default()
+}
+
+/// Vendors of the display controllers (PCI class 03xx) in `lspci -nn` output.
+fn parse_gpu_vendors(lspci: &str) -> Vec<GpuVendor> {
+    let mut vendors: Vec<GpuVendor> = lspci
+        .lines()
+        .filter_map(|line| {
+            let tags = bracketed(line);
+            let is_display = tags
+                .iter()
+                .any(|t| t.len() == 4 && t.starts_with("03") && is_hex(t));
+            if !is_display {
+                return None;
+            }
+            // The `[vendor:device]` pair is the last ID-shaped tag; names
+            // like "[GeForce GTX 1650 Mobile / Max-Q]" never match it.
+            tags.iter()
+                .rev()
+                .find_map(|t| match t.split_once(':') {
+                    Some((v, d)) if v.len() == 4 && d.len() == 4 && is_hex(v) && is_hex(d) => {
+                        Some(v)
+                    }
+                    _ => None,
+                })
+                .and_then(|v| GpuVendor::from_pci_id(v))
+        })
+        .collect();
+    vendors.sort_unstable();
+    vendors.dedup();
+    vendors
+}
+
+/// Contents of each `[...]` group in `line`.
+fn bracketed(line: &str) -> Vec<&str> {
+    line.split('[')
+        .skip(1)
+        .filter_map(|part| part.split_once(']').map(|(inner, _)| inner))
+        .collect()
+}
+
+fn is_hex(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hybrid_laptop() {
+        let out = "\
+00:00.0 Host bridge [0600]: Intel Corporation 8th Gen Core Processor Host Bridge/DRAM Registers [8086:3ec4] (rev 07)
+00:02.0 VGA compatible controller [0300]: Intel Corporation CoffeeLake-H GT2 [UHD Graphics 630] [8086:3e9b]
+01:00.0 3D controller [0302]: NVIDIA Corporation TU117M [GeForce GTX 1650 Mobile / Max-Q] [10de:1f99] (rev a1)
+01:00.1 Audio device [0403]: NVIDIA Corporation Device [10de:10fa] (rev a1)";
+        assert_eq!(
+            parse_gpu_vendors(out),
+            vec![GpuVendor::Nvidia, GpuVendor::Intel]
+        );
+    }
+
+    #[test]
+    fn test_parse_amd_desktop() {
+        let out = "\
+0a:00.0 VGA compatible controller [0300]: Advanced Micro Devices, Inc. [AMD/ATI] Navi 21 [Radeon RX 6800/6800 XT / 6900 XT] [1002:73bf] (rev c1)
+0a:00.1 Audio device [0403]: Advanced Micro Devices, Inc. [AMD/ATI] Navi 21/23 HDMI/DP Audio Controller [1002:ab28]
+0c:00.0 Display controller [0380]: Advanced Micro Devices, Inc. [AMD/ATI] Raphael [1002:164e] (rev c1)";
+        assert_eq!(parse_gpu_vendors(out), vec![GpuVendor::Amd]);
+    }
+
+    #[test]
+    fn test_parse_unknown_or_empty() {
+        let vm = "00:02.0 VGA compatible controller [0300]: Red Hat, Inc. Virtio 1.0 GPU [1af4:1050] (rev 01)";
+        assert!(parse_gpu_vendors(vm).is_empty());
+        assert!(parse_gpu_vendors("").is_empty());
+    }
+}
@@ -3,17 +3,28 @@
 //! This module contains:
 //! - `aur`: AUR helper detection and management
 //! - `daemon`: Daemon management for cyberxero-auth
+//! - `distro`: Distribution detection from os-release
+//! - `escalation`: Privilege escalation tool detection (pkexec/sudo/doas)
 //! - `download`: File download functionality
+//! - `gpu`: GPU vendor detection
 //! - `package`: Package and flatpak checking utilities
-//! - `system_check`: System dependency and distribution validation
+//! - `settings`: User settings persisted between launches
+//! - `system_check`: System dependency and distribution validation, bootloader detection
 
 pub mod aur;
 pub mod autostart;
 pub mod daemon;
+pub mod distro;
 pub mod download;
+pub mod escalation;
+pub mod gpu;
 pub mod package;
+pub mod settings;
 pub mod system_check;
 
 // Re-export commonly used items
 pub use aur::get as aur_helper;
-pub use package::{is_flatpak_installed, is_package_installed, is_package_in_repos};
+pub use package::{
+    is_flatpak_installed, is_multilib_enabled, is_package_installed, is_package_in_repos,
+    FlatpakKind,
+};
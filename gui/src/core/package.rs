@@ -6,9 +6,62 @@
 use super::aur;
 use anyhow::Result;
 use log::debug;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Lock file pacman holds for the duration of a transaction.
+pub const PACMAN_DB_LOCK: &str = "/var/lib/pacman/db.lck";
+
+const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+/// How long a package query result is reused before asking pacman again.
+/// Short enough that changes made from a terminal show up on the next
+/// refresh; the task runner also clears the cache after every run.
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Query {
+    Installed,
+    InRepos,
+}
+
+type QueryCache = HashMap<(Query, String), (bool, Instant)>;
+
+fn cache() -> &'static Mutex<QueryCache> {
+    static CACHE: OnceLock<Mutex<QueryCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Return a fresh cached answer for `(query, package)` or compute and store
+/// one. The lock isn't held while `compute` runs.
+fn cached(query: Query, package: &str, compute: impl FnOnce() -> bool) -> bool {
+    let key = (query, package.to_owned());
+    if let Some(&(value, at)) = cache().lock().unwrap().get(&key) {
+        if at.elapsed() < CACHE_TTL {
+            return value;
+        }
+    }
+
+    let value = compute();
+    cache().lock().unwrap().insert(key, (value, Instant::now()));
+    value
+}
+
+/// Forget all cached package query results. Called whenever a task runner
+/// sequence finishes, since it may have installed or removed packages.
+pub fn invalidate_cache() {
+    debug!("Invalidating package query cache");
+    cache().lock().unwrap().clear();
+}
 
 /// Check if a package is installed using AUR helper or pacman.
 pub fn is_package_installed(package: &str) -> bool {
+    cached(Query::Installed, package, || query_installed(package))
+}
+
+fn query_installed(package: &str) -> bool {
     debug!("Checking if package '{}' is installed", package);
 
     // Use the cached AUR helper if available (avoids re-scanning PATH)
@@ -46,6 +99,10 @@ pub fn is_package_installed(package: &str) -> bool {
 /// Check if a package is available in the configured pacman repositories.
 /// This checks sync databases, not installed packages.
 pub fn is_package_in_repos(package: &str) -> bool {
+    cached(Query::InRepos, package, || query_in_repos(package))
+}
+
+fn query_in_repos(package: &str) -> bool {
     debug!("Checking if package '{}' is available in repos", package);
 
     let available = std::process::Command::new("pacman")
@@ -63,23 +120,84 @@ pub fn is_package_in_repos(package: &str) -> bool {
     available
 }
 
-/// Check if a flatpak package is installed.
-pub fn is_flatpak_installed(package: &str) -> bool {
-    debug!("Checking if Flatpak '{}' is installed", package);
+/// Installed version of `package` (`1:26.1.0-1`), from `pacman -Q`.
+pub fn get_installed_version(package: &str) -> Option<String> {
+    pacman_output(&["-Q", package]).and_then(|out| parse_query_version(&out))
+}
+
+/// Version of `package` in the sync databases, from `pacman -Si`. The first
+/// repository listed wins, as it does for pacman itself.
+pub fn get_repo_version(package: &str) -> Option<String> {
+    pacman_output(&["-Si", package]).and_then(|out| parse_info_version(&out))
+}
+
+/// Whether `candidate` is newer than `current`, by pacman's own `vercmp`
+/// so epochs and pkgrels compare the way upgrades do.
+pub fn is_version_newer(candidate: &str, current: &str) -> bool {
+    std::process::Command::new("vercmp")
+        .args([candidate, current])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse::<i32>().ok())
+        .is_some_and(|order| order > 0)
+}
 
-    // Use --columns=application to get only app IDs, one per line
+/// Stdout of a successful pacman call, with untranslated field names.
+fn pacman_output(args: &[&str]) -> Option<String> {
+    std::process::Command::new("pacman")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+/// `docker 1:26.1.0-1` → `1:26.1.0-1`.
+fn parse_query_version(output: &str) -> Option<String> {
+    output.lines().next()?.split_whitespace().nth(1).map(str::to_owned)
+}
+
+/// The `Version : …` field of the first `pacman -Si` block.
+fn parse_info_version(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Version").then(|| value.trim().to_owned())
+    })
+}
+
+/// Which installed refs [`is_flatpak_installed`] looks through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatpakKind {
+    /// Applications, runtimes and extensions alike.
+    Any,
+    /// Applications only.
+    App,
+    /// Runtimes and extensions only, e.g. OBS plugins.
+    Runtime,
+}
+
+/// Check if a flatpak ref of the given kind is installed.
+///
+/// Matches the full ID, so `com.obsproject.Studio` isn't reported as
+/// installed when only one of its plugins is.
+pub fn is_flatpak_installed(package: &str, kind: FlatpakKind) -> bool {
+    debug!("Checking if Flatpak '{}' ({:?}) is installed", package, kind);
+
+    // Use --columns=application to get only IDs, one per line
+    let mut args = vec!["list", "--columns=application"];
+    match kind {
+        FlatpakKind::Any => {}
+        FlatpakKind::App => args.push("--app"),
+        FlatpakKind::Runtime => args.push("--runtime"),
+    }
     let installed = std::process::Command::new("flatpak")
-        .args(["list", "--columns=application"])
+        .args(&args)
         .output()
         .map(|output| {
-            if output.status.success() {
-                // Check for exact match on any line
-                String::from_utf8_lossy(&output.stdout)
-                    .lines()
-                    .any(|line| line.trim() == package)
-            } else {
-                false
-            }
+            output.status.success()
+                && lists_flatpak(&String::from_utf8_lossy(&output.stdout), package)
         })
         .unwrap_or(false);
 
@@ -92,6 +210,122 @@ pub fn is_flatpak_installed(package: &str) -> bool {
     installed
 }
 
+/// Whether `flatpak list --columns=application` output has `id` on a line
+/// of its own.
+fn lists_flatpak(output: &str, id: &str) -> bool {
+    output.lines().any(|line| line.trim() == id)
+}
+
+/// Check if a flatpak remote with the given name is configured and enabled.
+/// A disabled remote is listed by `flatpak remotes` but can't serve installs.
+pub fn has_flatpak_remote(name: &str) -> bool {
+    debug!("Checking for Flatpak remote '{}'", name);
+
+    std::process::Command::new("flatpak")
+        .args(["remotes", "--show-disabled", "--columns=name,options"])
+        .output()
+        .map(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .any(|line| is_enabled_remote(line, name))
+        })
+        .unwrap_or(false)
+}
+
+/// Whether a `flatpak remotes --columns=name,options` line is `name` and
+/// not marked disabled.
+fn is_enabled_remote(line: &str, name: &str) -> bool {
+    let mut columns = line.split_whitespace();
+    columns.next() == Some(name)
+        && !columns.any(|options| options.split(',').any(|o| o == "disabled"))
+}
+
+/// True when pacman's database lock exists but no pacman process is running,
+/// which is what a transaction killed midway (e.g. a cancelled step) leaves
+/// behind. Every later pacman call fails until the lock is removed.
+pub fn has_stale_pacman_lock() -> bool {
+    Path::new(PACMAN_DB_LOCK).exists() && !is_process_running("pacman")
+}
+
+/// Whether pacman.conf has an uncommented `[multilib]` section, which every
+/// `lib32-*` package comes from.
+pub fn is_multilib_enabled() -> bool {
+    std::fs::read_to_string(PACMAN_CONF)
+        .map(|conf| conf.lines().any(|line| line.trim() == "[multilib]"))
+        .unwrap_or(false)
+}
+
+/// Uncomment the stock `[multilib]` block (`#[multilib]`, `# [multilib]`, …)
+/// together with its `Include`, or append one if the file has none. The
+/// section header is only ever touched once, so nothing ends up duplicated,
+/// and `[multilib-testing]` is left as it is.
+pub const ENABLE_MULTILIB_SCRIPT: &str = r##"set -eu
+conf=/etc/pacman.conf
+if grep -qE '^[[:space:]]*\[multilib\][[:space:]]*$' "$conf"; then
+    echo "[multilib] is already enabled."
+    exit 0
+fi
+tmp="$(mktemp "${conf}.XXXXXX")"
+trap 'rm -f "$tmp"' EXIT
+if grep -qE '^[[:space:]]*#[[:space:]]*\[multilib\][[:space:]]*$' "$conf"; then
+    awk '
+        function close_section() {
+            if (insec && !inc) print "Include = /etc/pacman.d/mirrorlist"
+            insec = 0
+        }
+        !done && /^[[:space:]]*#[[:space:]]*\[multilib\][[:space:]]*$/ {
+            print "[multilib]"; insec = 1; done = 1; next
+        }
+        insec && /^[[:space:]]*#?[[:space:]]*\[/ { close_section() }
+        insec && /^[[:space:]]*#?[[:space:]]*Include[[:space:]]*=/ {
+            sub(/^[[:space:]]*#[[:space:]]*/, ""); print; inc = 1; next
+        }
+        { print }
+        END { close_section() }
+    ' "$conf" > "$tmp"
+else
+    cat -- "$conf" > "$tmp"
+    printf '
+[multilib]
+Include = /etc/pacman.d/mirrorlist
+' >> "$tmp"
+fi
+if ! cp -a -- "$conf" "$conf.bak"; then
+    echo "Could not back up $conf; leaving it untouched." >&2
+    exit 1
+fi
+chmod 0644 "$tmp"
+mv -f -- "$tmp" "$conf"
+trap - EXIT
+echo "Enabled [multilib] in $conf (backup at $conf.bak)"
+"##;
+
+/// Kernel flavour from a release string: `6.12.8-zen1-1-zen` → `zen`.
+pub fn kernel_suffix(release: &str) -> Option<&str> {
+    release
+        .rsplit('-')
+        .next()
+        .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric()))
+}
+
+/// Headers package for a kernel flavour: `zen` → `linux-zen-headers`.
+pub fn kernel_headers_package(suffix: &str) -> String {
+    format!("linux-{}-headers", suffix)
+}
+
+/// Whether any process's `comm` is exactly `name`.
+fn is_process_running(name: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        std::fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim_end() == name)
+            .unwrap_or(false)
+    })
+}
+
 /// Open a URL in the default browser.
 pub fn open_url(url: &str) -> Result<()> {
     debug!("Opening URL: {}", url);
@@ -103,6 +337,41 @@ pub fn open_url(url: &str) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_query_version() {
+        assert_eq!(
+            parse_query_version("docker 1:26.1.0-1\n").as_deref(),
+            Some("1:26.1.0-1")
+        );
+        assert_eq!(parse_query_version(""), None);
+    }
+
+    #[test]
+    fn test_parse_info_version() {
+        let out = "Repository      : extra\n\
+                   Name            : docker\n\
+                   Version         : 1:27.0.0-1\n\
+                   Description     : Pack, ship and run any application as a lightweight container\n";
+        assert_eq!(parse_info_version(out).as_deref(), Some("1:27.0.0-1"));
+        assert_eq!(parse_info_version("Name : docker"), None);
+    }
+
+    #[test]
+    fn test_lists_flatpak() {
+        let output = "com.obsproject.Studio\ncom.obsproject.Studio.Plugin.OBSVkCapture\n";
+        assert!(lists_flatpak(output, "com.obsproject.Studio"));
+        assert!(lists_flatpak(output, "com.obsproject.Studio.Plugin.OBSVkCapture"));
+        assert!(!lists_flatpak(output, "com.obsproject.Studio.Plugin"));
+        assert!(!lists_flatpak("", "com.obsproject.Studio"));
+    }
+
+    #[test]
+    fn test_is_enabled_remote() {
+        assert!(is_enabled_remote("flathub\tsystem", "flathub"));
+        assert!(!is_enabled_remote("flathub\tsystem,disabled", "flathub"));
+        assert!(!is_enabled_remote("flathub-beta\tuser", "flathub"));
+    }
+
     #[test]
     fn test_is_package_installed_nonexistent() {
         // A package that definitely doesn't exist
@@ -0,0 +1,210 @@
+//! User settings persisted between launches.
+//!
+//! Stored as TOML at `~/.config/cyberxero-toolkit/settings.toml`. The file is
+//! read once, on first access, and written back whenever a setter actually
+//! changes something. Keys missing from the file take their defaults, so a
+//! partial or older file keeps working; a missing or malformed one just
+//! means "all defaults". Nothing here is worth failing startup over.
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+use crate::config;
+
+/// Process-wide settings, loaded lazily from disk.
+static SETTINGS: OnceLock<Mutex<Settings>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Id of the sidebar page that was open last.
+    pub last_page: Option<String>,
+    /// Send a desktop notification when a task run finishes unattended.
+    pub notify_on_finish: bool,
+    /// Preferred AUR helper, e.g. `yay`. Used when installed.
+    pub aur_helper: Option<String>,
+    /// Preferred escalation tool: `pkexec`, `sudo` or `doas`.
+    pub escalation: Option<String>,
+    /// Flatpak id of the browser streaming web apps were last made for.
+    pub web_app_browser: Option<String>,
+    /// Days a finished run stays in the History dialog (and its log on disk).
+    pub history_days: u32,
+    /// Zoom of the interactive terminal dialog, 1.0 being the default size.
+    pub terminal_font_scale: f64,
+    /// Proxy for downloads (`http://…` or `socks5://…`). Overrides the
+    /// `HTTPS_PROXY`/`ALL_PROXY` environment variables.
+    pub download_proxy: Option<String>,
+    /// Parallel connections for large downloads from servers that support
+    /// ranges; 1 turns segmenting off.
+    pub download_segments: u32,
+    /// Parse the layouts of unvisited pages in the background after
+    /// startup so their first visit is quicker. Off for low-end machines.
+    pub preload_pages: bool,
+    /// Last confirmed choice of each selection dialog that opts in, by
+    /// dialog id. Kept last: TOML writes tables after plain keys.
+    pub selections: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            last_page: None,
+            notify_on_finish: true,
+            aur_helper: None,
+            escalation: None,
+            web_app_browser: None,
+            history_days: 30,
+            terminal_font_scale: 1.0,
+            download_proxy: None,
+            download_segments: 4,
+            preload_pages: true,
+            selections: BTreeMap::new(),
+        }
+    }
+}
+
+fn settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join(config::app_info::NAME)
+        .join("settings.toml")
+}
+
+fn read() -> Settings {
+    let path = settings_path();
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    toml::from_str(&raw).unwrap_or_else(|e| {
+        warn!("Ignoring malformed {}: {}", path.display(), e);
+        Settings::default()
+    })
+}
+
+/// Write `settings` to disk, logging (not returning) any failure.
+fn write(settings: &Settings) {
+    let path = settings_path();
+    let result = toml::to_string(settings)
+        .map_err(anyhow::Error::from)
+        .and_then(|raw| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, raw)?;
+            Ok(())
+        });
+    match result {
+        Ok(()) => debug!("Saved settings to {}", path.display()),
+        Err(e) => warn!("Failed to save {}: {}", path.display(), e),
+    }
+}
+
+fn lock() -> MutexGuard<'static, Settings> {
+    SETTINGS
+        .get_or_init(|| Mutex::new(read()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Load the settings file now rather than on first use.
+pub fn init() {
+    drop(lock());
+}
+
+/// Apply `change` and save if it altered anything.
+pub fn update(change: impl FnOnce(&mut Settings)) {
+    let mut settings = lock();
+    let before = settings.clone();
+    change(&mut settings);
+    if *settings != before {
+        write(&settings);
+    }
+}
+
+/// Id of the page that was open when the app last closed.
+pub fn last_page() -> Option<String> {
+    lock().last_page.clone()
+}
+
+/// Remember `page_id` as the page to reopen on next launch.
+pub fn set_last_page(page_id: &str) {
+    update(|s| s.last_page = Some(page_id.to_owned()));
+}
+
+pub fn notify_on_finish() -> bool {
+    lock().notify_on_finish
+}
+
+pub fn set_notify_on_finish(enabled: bool) {
+    update(|s| s.notify_on_finish = enabled);
+}
+
+pub fn aur_helper() -> Option<String> {
+    lock().aur_helper.clone()
+}
+
+pub fn escalation() -> Option<String> {
+    lock().escalation.clone()
+}
+
+pub fn web_app_browser() -> Option<String> {
+    lock().web_app_browser.clone()
+}
+
+pub fn set_web_app_browser(app_id: &str) {
+    update(|s| s.web_app_browser = Some(app_id.to_owned()));
+}
+
+pub fn history_days() -> u32 {
+    lock().history_days
+}
+
+pub fn terminal_font_scale() -> f64 {
+    lock().terminal_font_scale
+}
+
+pub fn set_terminal_font_scale(scale: f64) {
+    update(|s| s.terminal_font_scale = scale);
+}
+
+pub fn download_proxy() -> Option<String> {
+    lock().download_proxy.clone()
+}
+
+pub fn download_segments() -> u32 {
+    lock().download_segments
+}
+
+pub fn preload_pages() -> bool {
+    lock().preload_pages
+}
+
+/// Option ids last confirmed in the selection dialog `dialog_id`.
+pub fn last_selection(dialog_id: &str) -> Option<Vec<String>> {
+    lock().selections.get(dialog_id).cloned()
+}
+
+pub fn set_last_selection(dialog_id: &str, ids: &[String]) {
+    update(|s| {
+        s.selections.insert(dialog_id.to_owned(), ids.to_vec());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_file_fills_defaults() {
+        let settings: Settings = toml::from_str("aur_helper = \"yay\"").unwrap();
+        assert_eq!(settings.aur_helper.as_deref(), Some("yay"));
+        assert!(settings.notify_on_finish);
+        assert_eq!(settings.last_page, None);
+        assert_eq!(settings.history_days, 30);
+        assert!(settings.preload_pages);
+    }
+}
@@ -1,4 +1,4 @@
-//! System dependency checks and validation.
+//! System dependency checks, validation and bootloader detection.
 
 use crate::ui::utils::extract_widget;
 use gtk4::prelude::*;
@@ -9,13 +9,12 @@ use log::{error, info, warn};
 #[derive(Debug, Clone)]
 pub struct DependencyCheckResult {
     pub flatpak_missing: bool,
-    pub aur_helper_missing: bool,
 }
 
 impl DependencyCheckResult {
     /// Check if any dependencies are missing.
     pub fn has_missing_dependencies(&self) -> bool {
-        self.flatpak_missing || self.aur_helper_missing
+        self.flatpak_missing
     }
 
     /// Get list of missing dependency names.
@@ -24,9 +23,6 @@ impl DependencyCheckResult {
         if self.flatpak_missing {
             missing.push("flatpak");
         }
-        if self.aur_helper_missing {
-            missing.push("paru or yay");
-        }
         missing
     }
 
@@ -46,9 +42,6 @@ impl DependencyCheckResult {
         if self.flatpak_missing {
             hints.push("Install flatpak: <tt>sudo pacman -S flatpak</tt>");
         }
-        if self.aur_helper_missing {
-            hints.push("AUR Helper repositories:\n• Paru: <a href=\"https://github.com/Morganamilo/paru\">https://github.com/Morganamilo/paru</a>\n• Yay: <a href=\"https://github.com/Jguer/yay\">https://github.com/Jguer/yay</a>");
-        }
 
         if hints.is_empty() {
             return String::new();
@@ -81,40 +74,14 @@ fn check_flatpak() -> bool {
     }
 }
 
-/// Check if an AUR helper (paru or yay) is installed.
-fn check_aur_helper() -> bool {
-    info!("Checking for AUR helper availability");
-
-    if let Ok(output) = std::process::Command::new("paru").arg("--version").output() {
-        if output.status.success() {
-            let version = String::from_utf8_lossy(&output.stdout);
-            info!("paru found: {}", version.trim());
-            return true;
-        }
-    }
-
-    if let Ok(output) = std::process::Command::new("yay").arg("--version").output() {
-        if output.status.success() {
-            let version = String::from_utf8_lossy(&output.stdout);
-            info!("yay found: {}", version.trim());
-            return true;
-        }
-    }
-
-    warn!("No AUR helper (paru or yay) found in PATH");
-    false
-}
-
 /// Perform all dependency checks and return results.
 pub fn check_dependencies() -> DependencyCheckResult {
     info!("Performing system dependency checks");
 
-    let flatpak_missing = !check_flatpak();
-    let aur_helper_missing = !check_aur_helper();
-
+    // A missing AUR helper isn't fatal: the task runner offers to set up
+    // paru the first time something needs it.
     let result = DependencyCheckResult {
-        flatpak_missing,
-        aur_helper_missing,
+        flatpak_missing: !check_flatpak(),
     };
 
     if result.has_missing_dependencies() {
@@ -127,6 +94,31 @@ pub fn check_dependencies() -> DependencyCheckResult {
     result
 }
 
+/// Generated GRUB menu; its presence is how GRUB installs are recognised.
+pub const GRUB_CFG: &str = "/boot/grub/grub.cfg";
+
+/// Bootloaders the toolkit knows how to regenerate entries for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bootloader {
+    Grub,
+    SystemdBoot,
+}
+
+/// Detect the installed bootloader, preferring GRUB when both are present.
+pub fn detect_bootloader() -> Option<Bootloader> {
+    if std::path::Path::new(GRUB_CFG).exists() {
+        return Some(Bootloader::Grub);
+    }
+    let systemd_boot = std::process::Command::new("bootctl")
+        .arg("is-installed")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false);
+    systemd_boot.then_some(Bootloader::SystemdBoot)
+}
+
 /// Show dependency error dialog and prevent app from continuing.
 pub fn show_dependency_error_dialog(
     main_window: &ApplicationWindow,
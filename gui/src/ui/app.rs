@@ -31,6 +31,9 @@ pub fn setup_application_ui(app: &Application) {
         return;
     }
 
+    info!("Loading settings");
+    core::settings::init();
+
     // Extract tabs_container first for stack creation
     let tabs_container = extract_widget(&builder, "tabs_container");
 
@@ -40,9 +43,9 @@ pub fn setup_application_ui(app: &Application) {
     // Set up UI components with the dynamic stack
     let ctx = setup_ui_components(&builder, stack, &window);
 
-    info!("Setting initial view to first page");
-    if let Some(first_page) = navigation::PAGES.first() {
-        ctx.navigate_to_page(first_page.id);
+    info!("Setting initial view to remembered page");
+    if let Some(page_id) = navigation::initial_page() {
+        ctx.navigate_to_page(page_id);
     }
 
     // Apply seasonal effects (snow for December, Halloween for October, etc.)
@@ -59,30 +62,40 @@ pub fn setup_application_ui(app: &Application) {
 
     // Perform system checks off the main thread so they don't block
     // window rendering. Results are sent back via an async channel.
-    let (sender, receiver) = async_channel::bounded::<(core::system_check::DependencyCheckResult, bool)>(1);
+    let (sender, receiver) =
+        async_channel::bounded::<(core::system_check::DependencyCheckResult, bool, bool)>(1);
 
     std::thread::spawn(move || {
         info!("Checking system dependencies (background thread)");
 
         let deps = core::system_check::check_dependencies();
-        let aur_ok = if !deps.has_missing_dependencies() {
-            core::aur::init()
-        } else {
-            false
-        };
+        let aur_ok = core::aur::init();
+        info!("Detected distribution: {}", core::distro::detect_distro().name());
+        let pacman_ok = core::distro::is_pacman_based();
 
-        let _ = sender.send_blocking((deps, aur_ok));
+        let _ = sender.send_blocking((deps, aur_ok, pacman_ok));
     });
 
     let window_clone = window.clone();
     glib::MainContext::default().spawn_local(async move {
-        if let Ok((dep_result, aur_ok)) = receiver.recv().await {
+        if let Ok((dep_result, aur_ok, pacman_ok)) = receiver.recv().await {
+            // Not fatal: the non-pacman tools (web apps, Flatpaks) still work.
+            if !pacman_ok {
+                warn!("pacman not found - this does not look like an Arch-based system");
+                crate::ui::dialogs::error::show_error(
+                    &window_clone,
+                    "CyberXero Toolkit is made for Arch-based distributions, and pacman \
+                     wasn't found on this system. Most tools here will fail.",
+                );
+            }
             if dep_result.has_missing_dependencies() {
                 warn!("Dependency check failed - missing dependencies");
                 core::system_check::show_dependency_error_dialog(&window_clone, &dep_result);
             } else {
                 if aur_ok {
                     info!("AUR helper initialized successfully");
+                } else {
+                    warn!("No AUR helper found; AUR installs will offer to set up paru");
                 }
                 info!("All dependency checks passed");
             }
@@ -142,9 +155,18 @@ fn setup_ui_components(builder: &Builder, stack: Stack, window: &ApplicationWind
     // Set up autostart toggle in sidebar
     setup_autostart_toggle(builder);
 
+    // Set up finish-notification toggle in sidebar
+    setup_notifications_toggle(builder);
+
+    // Set up dry-run toggle in sidebar
+    setup_dry_run_toggle(builder);
+
     // Set up about button
     setup_about_button(builder, window);
 
+    // Set up history button
+    setup_history_button(builder, window);
+
     // Set up seasonal effects toggle
     setup_seasonal_effects_toggle(builder, window);
 
@@ -188,6 +210,31 @@ fn setup_autostart_toggle(builder: &Builder) {
     });
 }
 
+/// Set up the finish-notification toggle switch in the sidebar.
+fn setup_notifications_toggle(builder: &Builder) {
+    let switch = extract_widget::<gtk4::Switch>(builder, "switch_notifications");
+    switch.set_active(core::settings::notify_on_finish());
+
+    switch.connect_state_set(move |_switch, state| {
+        info!("Finish notifications toggle changed to: {}", state);
+        core::settings::set_notify_on_finish(state);
+        glib::Propagation::Proceed
+    });
+}
+
+/// Set up the dry-run toggle switch in the sidebar. Deliberately not
+/// persisted: every launch starts out executing commands for real.
+fn setup_dry_run_toggle(builder: &Builder) {
+    let switch = extract_widget::<gtk4::Switch>(builder, "switch_dry_run");
+    switch.set_active(crate::ui::task_runner::is_dry_run());
+
+    switch.connect_state_set(move |_switch, state| {
+        info!("Dry run toggle changed to: {}", state);
+        crate::ui::task_runner::set_dry_run(state);
+        glib::Propagation::Proceed
+    });
+}
+
 /// Set up the about button in the header bar.
 fn setup_about_button(builder: &Builder, window: &ApplicationWindow) {
     use crate::ui::dialogs::about;
@@ -200,6 +247,18 @@ fn setup_about_button(builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
+/// Set up the run history button in the header bar.
+fn setup_history_button(builder: &Builder, window: &ApplicationWindow) {
+    use crate::ui::dialogs::history;
+
+    let button = extract_widget::<gtk4::Button>(builder, "history_button");
+    let window_clone = window.clone();
+    button.connect_clicked(move |_| {
+        info!("History button clicked");
+        history::show_history_dialog(window_clone.upcast_ref());
+    });
+}
+
 /// Run a background `git ls-remote` check and, if an update is available,
 /// reveal the header-bar notifier and wire its click to the update dialog.
 fn setup_update_notifier(builder: &Builder, window: &ApplicationWindow) {
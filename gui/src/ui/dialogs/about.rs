@@ -1,7 +1,8 @@
-//! About dialog showing project information.
+//! About dialog showing project information and the system details worth
+//! pasting into a bug report.
 
-use crate::core::package;
-use crate::ui::utils::extract_widget;
+use crate::core::{self, package};
+use crate::ui::utils::{extract_widget, run_command};
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{Builder, Button, Label, Window};
@@ -28,6 +29,37 @@ pub fn show_about_dialog(parent: &Window) {
         glib::Propagation::Stop
     });
 
+    // Detection shells out (uname, lspci, flatpak), so keep it off the
+    // main thread and fill the section in when it's ready.
+    let info_label: Label = extract_widget(&builder, "system_info_label");
+    let copy_button: Button = extract_widget(&builder, "copy_info_button");
+    let (tx, rx) = async_channel::bounded::<Vec<(&'static str, String)>>(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking(system_info());
+    });
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(info) = rx.recv().await else {
+            return;
+        };
+        let markup = info
+            .iter()
+            .map(|(key, value)| format!("<b>{}:</b> {}", key, glib::markup_escape_text(value)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        info_label.set_markup(&markup);
+
+        let markdown = info
+            .iter()
+            .map(|(key, value)| format!("- **{}:** {}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        copy_button.set_sensitive(true);
+        copy_button.connect_clicked(move |button| {
+            button.clipboard().set_text(&format!("### System information\n{}\n", markdown));
+            button.set_label("Copied");
+        });
+    });
+
     // Set dialog as transient for parent
     dialog.set_transient_for(Some(parent));
 
@@ -40,3 +72,39 @@ pub fn show_about_dialog(parent: &Window) {
     // Show the dialog
     dialog.present();
 }
+
+/// `(field, value)` pairs shown in the dialog and copied for bug reports.
+fn system_info() -> Vec<(&'static str, String)> {
+    use crate::config::flatpak::FLATHUB;
+
+    let yes_no = |present: bool| String::from(if present { "yes" } else { "no" });
+    let gpus = core::gpu::detect_gpu_vendors()
+        .iter()
+        .map(|vendor| format!("{:?}", vendor))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let flatpak = core::aur::is_executable_in_path("flatpak");
+
+    vec![
+        ("Toolkit version", String::from(crate::config::app_info::VERSION)),
+        ("Distribution", String::from(core::distro::detect_distro().name())),
+        (
+            "Kernel",
+            run_command("uname", &["-r"]).unwrap_or_else(|| String::from("unknown")),
+        ),
+        (
+            "AUR helper",
+            String::from(core::aur_helper().unwrap_or("none")),
+        ),
+        (
+            "GPU",
+            if gpus.is_empty() {
+                String::from("not recognised")
+            } else {
+                gpus
+            },
+        ),
+        ("Flatpak", yes_no(flatpak)),
+        ("Flathub remote", yes_no(flatpak && package::has_flatpak_remote(FLATHUB))),
+    ]
+}
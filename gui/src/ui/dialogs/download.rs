@@ -1,10 +1,11 @@
-//! Two-stage dialog for downloading an Arch Linux ISO: first resolve which
-//! image to pull and where to save it, then run the transfer with live
-//! progress, pause/resume, and cancel.
+//! Two-stage dialog for downloading a distribution ISO (Arch Linux or a
+//! derivative): first resolve which image to pull and where to save it, then
+//! run the transfer with live progress, pause/resume, and cancel.
 //!
 //! Each stage is its own `Rc`-owned struct so the glib signal handlers and
 //! worker-thread callbacks can share state without tangled cloning ladders.
 
+use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::mpsc;
 use std::sync::Mutex;
@@ -12,13 +13,16 @@ use std::time::Duration;
 
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::{Button, Entry, Image, Label, ProgressBar, Window};
-use log::{error, info};
+use gtk4::{Button, CheckButton, DropDown, Entry, Image, Label, ProgressBar, Window};
+use log::{error, info, warn};
 
 use crate::core::download::{
-    humanize_bytes, humanize_eta, humanize_rate, latest_arch_iso, stream_to_file, Progress,
-    TransferFlags,
+    commit_download, humanize_bytes, humanize_eta, humanize_rate, iso_checksum, latest_iso,
+    mirror_urls, part_path, runtime, stream_to_file, verify_checksum, verify_signature,
+    ChecksumMismatch, IncompleteDownload, InsufficientSpace, IsoSource, Progress, SignatureStatus,
+    TransferFlags, ISO_SOURCES,
 };
+use crate::ui::dialogs::warning::show_warning_confirmation;
 use crate::ui::utils::extract_widget;
 
 /// Open the ISO setup dialog. When the user confirms, the transfer dialog
@@ -34,20 +38,28 @@ pub fn show_download_dialog(parent: &Window) {
 
 #[derive(Clone)]
 struct IsoRef {
+    source: &'static IsoSource,
     filename: String,
     url: String,
+    /// Published checksum, if the mirror's checksum file could be read.
+    checksum: Option<String>,
 }
 
 struct SetupStage {
     window: adw::Window,
+    source_dropdown: DropDown,
     version_label: Label,
     path_entry: Entry,
     browse_btn: Button,
     start_btn: Button,
     cancel_btn: Button,
     spinner: Image,
+    verify_sig_check: CheckButton,
     iso: Mutex<Option<IsoRef>>,
     dest: Mutex<Option<String>>,
+    /// Bumped on every lookup so a slow answer for a distribution the user
+    /// has since switched away from is dropped.
+    lookup: Cell<u32>,
 }
 
 impl SetupStage {
@@ -57,43 +69,98 @@ impl SetupStage {
 
         let stage = Rc::new(Self {
             window: extract_widget(&builder, "download_setup_window"),
+            source_dropdown: extract_widget(&builder, "distro_dropdown"),
             version_label: extract_widget(&builder, "version_label"),
             path_entry: extract_widget(&builder, "download_path_entry"),
             browse_btn: extract_widget(&builder, "browse_button"),
             start_btn: extract_widget(&builder, "start_download_button"),
             cancel_btn: extract_widget(&builder, "cancel_button"),
             spinner: extract_widget(&builder, "fetching_spinner"),
+            verify_sig_check: extract_widget(&builder, "verify_signature_check"),
             iso: Mutex::new(None),
             dest: Mutex::new(None),
+            lookup: Cell::new(0),
         });
 
         stage.window.set_transient_for(Some(parent));
 
+        let names: Vec<&str> = ISO_SOURCES.iter().map(|s| s.name).collect();
+        stage
+            .source_dropdown
+            .set_model(Some(&gtk4::StringList::new(&names)));
+        let me = stage.clone();
+        stage
+            .source_dropdown
+            .connect_selected_notify(move |_| me.kick_off_iso_lookup());
+
         stage.kick_off_iso_lookup();
         stage.wire_buttons(parent);
         stage.window.present();
     }
 
+    fn selected_source(&self) -> &'static IsoSource {
+        ISO_SOURCES
+            .get(self.source_dropdown.selected() as usize)
+            .unwrap_or(&ISO_SOURCES[0])
+    }
+
+    /// Reset to the "fetching" state and resolve the selected source.
     fn kick_off_iso_lookup(self: &Rc<Self>) {
+        let source = self.selected_source();
+        let generation = self.lookup.get().wrapping_add(1);
+        self.lookup.set(generation);
+
+        *self.iso.lock().unwrap() = None;
+        *self.dest.lock().unwrap() = None;
+        self.path_entry.set_text("");
+        self.browse_btn.set_sensitive(false);
+        self.start_btn.set_sensitive(false);
+        self.version_label.set_text("Fetching version...");
+        self.version_label.remove_css_class("error");
+        self.version_label.remove_css_class("warning");
+        self.version_label.add_css_class("accent");
+        self.spinner.set_icon_name(Some("circle-noth-symbolic"));
+        self.spinner.add_css_class("spinning");
+        self.spinner.set_visible(true);
+        self.verify_sig_check.set_sensitive(source.arch_signed);
+        if !source.arch_signed {
+            self.verify_sig_check.set_active(false);
+        }
+
         let (tx, rx) = mpsc::channel::<Result<IsoRef, String>>();
 
         std::thread::spawn(move || {
-            let rt = match tokio::runtime::Runtime::new() {
+            let rt = match runtime() {
                 Ok(rt) => rt,
                 Err(e) => {
-                    let _ = tx.send(Err(e.to_string()));
+                    let _ = tx.send(Err(format!("{e:#}")));
                     return;
                 }
             };
             let result = rt
-                .block_on(async { latest_arch_iso().await })
-                .map(|(filename, url)| IsoRef { filename, url })
-                .map_err(|e| e.to_string());
+                .block_on(async {
+                    let (filename, url) = latest_iso(source).await?;
+                    let checksum = match iso_checksum(source, &url).await {
+                        Ok(sum) => Some(sum),
+                        Err(e) => {
+                            warn!("checksum lookup failed, download won't be verified: {e}");
+                            None
+                        }
+                    };
+                    anyhow::Ok(IsoRef {
+                        source,
+                        filename,
+                        url,
+                        checksum,
+                    })
+                })
+                .map_err(|e| format!("{e:#}"));
             let _ = tx.send(result);
         });
 
         let me = self.clone();
         glib::timeout_add_local(Duration::from_millis(50), move || match rx.try_recv() {
+            _ if me.lookup.get() != generation => glib::ControlFlow::Break,
             Ok(Ok(iso)) => {
                 me.on_iso_resolved(iso);
                 glib::ControlFlow::Break
@@ -113,14 +180,19 @@ impl SetupStage {
     fn on_iso_resolved(&self, iso: IsoRef) {
         info!("ISO resolved: {}", iso.filename);
 
-        // Parse `archlinux-YYYY.MM.DD-x86_64.iso` → `Version: YYYY.MM.DD`.
         let version_text = iso
-            .filename
-            .strip_prefix("archlinux-")
-            .and_then(|rest| rest.split('-').next())
+            .source
+            .version(&iso.filename)
             .map(|date| format!("Version: {date}"))
             .unwrap_or_else(|| String::from("Latest Version"));
-        self.version_label.set_text(&version_text);
+        if iso.checksum.is_some() {
+            self.version_label.set_text(&version_text);
+        } else {
+            self.version_label
+                .set_text(&format!("{version_text} · checksum unavailable"));
+            self.version_label.remove_css_class("accent");
+            self.version_label.add_css_class("warning");
+        }
 
         self.spinner.set_visible(false);
 
@@ -158,10 +230,31 @@ impl SetupStage {
         self.start_btn.connect_clicked(move |_| {
             let iso = me.iso.lock().unwrap().clone();
             let dest = me.dest.lock().unwrap().clone();
-            if let (Some(iso), Some(dest)) = (iso, dest) {
+            let (Some(iso), Some(dest)) = (iso, dest) else {
+                return;
+            };
+            let verify_sig = me.verify_sig_check.is_active();
+            let checked = iso.checksum.is_some();
+            let window = me.window.clone();
+            let parent = parent_owned.clone();
+            let start = move || {
                 info!("starting transfer: {} -> {}", iso.filename, dest);
-                me.window.close();
-                TransferStage::spawn(&parent_owned, iso, dest);
+                window.close();
+                TransferStage::spawn(&parent, iso, dest, verify_sig);
+            };
+
+            // Without the published checksum nothing catches a corrupted
+            // download, so make that a deliberate choice.
+            if checked {
+                start();
+            } else {
+                show_warning_confirmation(
+                    me.window.upcast_ref(),
+                    "Checksum Unavailable",
+                    "The published checksum for this ISO could not be fetched, so the \
+                     download can't be checked for corruption.\n\nDownload it unverified?",
+                    start,
+                );
             }
         });
     }
@@ -199,10 +292,27 @@ impl SetupStage {
 
 enum TransferEvent {
     Progress(Progress),
+    Verifying,
     Done,
+    /// Downloaded fine, but the signature couldn't be checked.
+    Unverified,
     Failed(String),
+    /// The transfer was refused for a reason that deserves its own heading
+    /// (corruption, bad signature, no disk space) rather than a generic
+    /// "Download Failed".
+    Rejected {
+        title: &'static str,
+        message: String,
+    },
 }
 
+const KEY_UNAVAILABLE_HINT: &str = "The ISO was downloaded but its signature could not be checked \
+because gpg or the Arch release key is missing. Import the key with:\n\n\
+gpg --auto-key-locate clear,wkd --locate-external-keys pierre@archlinux.org";
+
+/// Choices in the speed limit dropdown, in bytes per second; 0 is unlimited.
+const SPEED_LIMITS: &[u64] = &[0, 1 << 20, 5 << 20, 10 << 20, 25 << 20, 50 << 20];
+
 struct TransferStage {
     window: adw::Window,
     progress_bar: ProgressBar,
@@ -211,15 +321,18 @@ struct TransferStage {
     eta_label: Label,
     pause_btn: Button,
     cancel_btn: Button,
+    speed_limit: DropDown,
     flags: TransferFlags,
 }
 
 impl TransferStage {
-    fn spawn(parent: &Window, iso: IsoRef, dest: String) {
+    fn spawn(parent: &Window, iso: IsoRef, dest: String, verify_sig: bool) {
         let builder = gtk4::Builder::from_resource(crate::config::resources::dialogs::DOWNLOAD);
 
         let filename_label: Label = extract_widget(&builder, "filename_label");
         filename_label.set_text(&iso.filename);
+        let title_label: Label = extract_widget(&builder, "download_title");
+        title_label.set_text(&format!("Downloading {} ISO", iso.source.name));
 
         let stage = Rc::new(Self {
             window: extract_widget(&builder, "download_window"),
@@ -229,6 +342,7 @@ impl TransferStage {
             eta_label: extract_widget(&builder, "time_remaining_label"),
             pause_btn: extract_widget(&builder, "pause_button"),
             cancel_btn: extract_widget(&builder, "cancel_button"),
+            speed_limit: extract_widget(&builder, "speed_limit_dropdown"),
             flags: TransferFlags::new(),
         });
         stage.window.set_transient_for(Some(parent));
@@ -237,7 +351,7 @@ impl TransferStage {
 
         let (tx, rx) = mpsc::channel::<TransferEvent>();
         stage.install_event_pump(parent.clone(), rx);
-        stage.launch_worker(iso.url, dest, tx);
+        stage.launch_worker(iso, dest, verify_sig, tx);
 
         stage.window.present();
     }
@@ -252,6 +366,27 @@ impl TransferStage {
             btn.set_label(if was_paused { "Pause" } else { "Resume" });
         });
 
+        // The speed limit applies live, from the next chunk on.
+        let labels: Vec<String> = SPEED_LIMITS
+            .iter()
+            .map(|&limit| match limit {
+                0 => String::from("Unlimited"),
+                _ => humanize_rate(limit as f64),
+            })
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        self.speed_limit
+            .set_model(Some(&gtk4::StringList::new(&labels)));
+        let flags = self.flags.clone();
+        self.speed_limit.connect_selected_notify(move |dropdown| {
+            let limit = SPEED_LIMITS
+                .get(dropdown.selected() as usize)
+                .copied()
+                .unwrap_or(0);
+            info!("download speed limit: {limit} B/s");
+            flags.set_rate_limit(limit);
+        });
+
         // Cancel flips the flag, then closes the window.
         let flags = self.flags.clone();
         let win = self.window.clone();
@@ -260,11 +395,11 @@ impl TransferStage {
             win.close();
         });
 
-        // If the user closes the window via the titlebar, still mark the
-        // transfer cancelled so the worker cleans up the partial file.
+        // Closing via the titlebar only stops the transfer; the partial
+        // file stays so the next download of the same ISO resumes.
         let flags = self.flags.clone();
         self.window.connect_close_request(move |_| {
-            flags.request_cancel();
+            flags.request_stop();
             glib::Propagation::Proceed
         });
     }
@@ -279,10 +414,16 @@ impl TransferStage {
             while let Ok(evt) = rx.try_recv() {
                 match evt {
                     TransferEvent::Progress(p) => me.render_progress(&p),
+                    TransferEvent::Verifying => me.render_verifying(),
                     TransferEvent::Done => {
                         me.render_done();
                         return glib::ControlFlow::Break;
                     }
+                    TransferEvent::Unverified => {
+                        me.render_done();
+                        alert(&parent, "Signature Not Verified", KEY_UNAVAILABLE_HINT);
+                        return glib::ControlFlow::Break;
+                    }
                     TransferEvent::Failed(e) => {
                         if !e.contains("cancelled") {
                             alert(&parent, "Download Failed", &e);
@@ -290,38 +431,95 @@ impl TransferStage {
                         me.window.close();
                         return glib::ControlFlow::Break;
                     }
+                    TransferEvent::Rejected { title, message } => {
+                        alert(&parent, title, &message);
+                        me.window.close();
+                        return glib::ControlFlow::Break;
+                    }
                 }
             }
             glib::ControlFlow::Continue
         });
     }
 
-    fn launch_worker(&self, url: String, dest: String, tx: mpsc::Sender<TransferEvent>) {
+    fn launch_worker(
+        &self,
+        iso: IsoRef,
+        dest: String,
+        verify_sig: bool,
+        tx: mpsc::Sender<TransferEvent>,
+    ) {
         let flags = self.flags.clone();
         std::thread::spawn(move || {
-            let rt = match tokio::runtime::Runtime::new() {
+            let rt = match runtime() {
                 Ok(rt) => rt,
                 Err(e) => {
-                    let _ = tx.send(TransferEvent::Failed(e.to_string()));
+                    let _ = tx.send(TransferEvent::Failed(format!("{e:#}")));
                     return;
                 }
             };
             let progress_tx = tx.clone();
+            let status_tx = tx.clone();
             let outcome = rt.block_on(async {
                 stream_to_file(
-                    url,
-                    dest,
+                    mirror_urls(iso.source, &iso.url),
+                    dest.clone(),
                     move |p| {
                         let _ = progress_tx.send(TransferEvent::Progress(p));
                     },
                     flags,
                 )
-                .await
+                .await?;
+
+                // Verification runs on the `.part` file; only a download
+                // that passes gets its real name.
+                let part = part_path(&dest);
+                if let Some(sum) = iso.checksum {
+                    let _ = status_tx.send(TransferEvent::Verifying);
+                    verify_checksum(&part, &sum).await?;
+                }
+                let signature = if verify_sig {
+                    let _ = status_tx.send(TransferEvent::Verifying);
+                    Some(verify_signature(&iso.url, &part).await?)
+                } else {
+                    None
+                };
+                if signature != Some(SignatureStatus::Invalid) {
+                    commit_download(&dest).await?;
+                }
+                anyhow::Ok(signature)
             });
-            let _ = match outcome {
-                Ok(()) => tx.send(TransferEvent::Done),
-                Err(e) => tx.send(TransferEvent::Failed(e.to_string())),
+            let event = match outcome {
+                Ok(None | Some(SignatureStatus::Verified)) => TransferEvent::Done,
+                Ok(Some(SignatureStatus::KeyUnavailable)) => TransferEvent::Unverified,
+                Ok(Some(SignatureStatus::Invalid)) => TransferEvent::Rejected {
+                    title: "Signature Invalid",
+                    message: String::from(
+                        "The ISO is not signed by the Arch Linux release key and may have \
+                         been tampered with. The file was deleted.",
+                    ),
+                },
+                Err(e) if e.downcast_ref::<ChecksumMismatch>().is_some() => {
+                    TransferEvent::Rejected {
+                        title: "Download Corrupted",
+                        message: e.to_string(),
+                    }
+                }
+                Err(e) if e.downcast_ref::<IncompleteDownload>().is_some() => {
+                    TransferEvent::Rejected {
+                        title: "Download Incomplete",
+                        message: e.to_string(),
+                    }
+                }
+                Err(e) if e.downcast_ref::<InsufficientSpace>().is_some() => {
+                    TransferEvent::Rejected {
+                        title: "Not Enough Disk Space",
+                        message: e.to_string(),
+                    }
+                }
+                Err(e) => TransferEvent::Failed(format!("{e:#}")),
             };
+            let _ = tx.send(event);
         });
     }
 
@@ -358,6 +556,12 @@ impl TransferStage {
         }
     }
 
+    fn render_verifying(&self) {
+        self.progress_bar.set_text(Some("Verifying…"));
+        self.eta_label.set_text("Verifying");
+        self.pause_btn.set_sensitive(false);
+    }
+
     fn render_done(&self) {
         info!("transfer complete");
         self.progress_bar.set_fraction(1.0);
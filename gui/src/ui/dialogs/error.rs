@@ -3,6 +3,7 @@
 use adw::prelude::*;
 use adw::AlertDialog;
 use gtk4::ApplicationWindow;
+use log::error;
 
 /// Show an error message dialog transient for the provided window.
 pub fn show_error(window: &ApplicationWindow, message: &str) {
@@ -13,3 +14,32 @@ pub fn show_error(window: &ApplicationWindow, message: &str) {
 
     dialog.present(Some(window));
 }
+
+/// Report an unexpected but recoverable failure — the toolkit's own bug or
+/// a broken install rather than something the user did. `message` is the
+/// friendly one-liner; `details` (the underlying error) is logged and can be
+/// copied from the dialog for a bug report.
+pub fn report_failure(window: &ApplicationWindow, message: &str, details: &str) {
+    error!("{}: {}", message, details);
+
+    let dialog = AlertDialog::builder()
+        .heading("Something Went Wrong")
+        .body(format!(
+            "{}\n\nThe rest of the toolkit keeps working. If this keeps happening, \
+             please report it with the copied details.",
+            message
+        ))
+        .build();
+    dialog.add_response("copy", "Copy Details");
+    dialog.add_response("close", "Close");
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+
+    let window_for_copy = window.clone();
+    let details = format!("{}\n{}", message, details);
+    dialog.connect_response(Some("copy"), move |_, _| {
+        window_for_copy.clipboard().set_text(&details);
+    });
+
+    dialog.present(Some(window));
+}
@@ -0,0 +1,108 @@
+//! History dialog listing previous task-runner runs.
+
+use crate::core::package;
+use crate::ui::task_runner::history::{self, HistoryEntry, Outcome};
+use adw::prelude::*;
+use gtk4::glib;
+use gtk4::{Box as GtkBox, Button, Image, Label, ListBox, Orientation, ScrolledWindow, Window};
+
+/// Show the run history, newest first.
+pub fn show_history_dialog(parent: &Window) {
+    let dialog = adw::Window::new();
+    dialog.set_title(Some("History"));
+    dialog.set_default_size(560, 480);
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(parent));
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&adw::HeaderBar::new());
+
+    let entries = history::load();
+    if entries.is_empty() {
+        let empty = adw::StatusPage::new();
+        empty.set_icon_name(Some("document-open-recent-symbolic"));
+        empty.set_title("No Runs Yet");
+        empty.set_description(Some("Finished installs and fixes will be listed here."));
+        toolbar.set_content(Some(&empty));
+    } else {
+        let list = ListBox::new();
+        list.set_selection_mode(gtk4::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        for entry in &entries {
+            list.append(&build_row(entry));
+        }
+
+        let content = GtkBox::new(Orientation::Vertical, 0);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+        content.append(&list);
+
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&content));
+        toolbar.set_content(Some(&scrolled));
+    }
+
+    dialog.set_content(Some(&toolbar));
+    dialog.present();
+}
+
+fn build_row(entry: &HistoryEntry) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+    row.set_margin_top(8);
+    row.set_margin_bottom(8);
+    row.set_margin_start(12);
+    row.set_margin_end(12);
+
+    let icon = match entry.outcome {
+        Outcome::Succeeded => "circle-check",
+        Outcome::Failed => "circle-xmark",
+        Outcome::Cancelled => "circle-stop",
+    };
+    let image = Image::from_icon_name(icon);
+    image.set_pixel_size(24);
+    row.append(&image);
+
+    let text = GtkBox::new(Orientation::Vertical, 2);
+    text.set_hexpand(true);
+    let title = Label::new(Some(&entry.title));
+    title.set_xalign(0.0);
+    let detail = Label::new(Some(&format!(
+        "{} — {}",
+        format_timestamp(entry.finished),
+        entry.message
+    )));
+    detail.set_xalign(0.0);
+    detail.set_wrap(true);
+    detail.add_css_class("dim-label");
+    detail.add_css_class("caption");
+    text.append(&title);
+    text.append(&detail);
+    row.append(&text);
+
+    if let Some(path) = entry.log.as_ref().filter(|p| p.exists()) {
+        let open = Button::with_label("Open Log");
+        open.set_valign(gtk4::Align::Center);
+        let path = path.display().to_string();
+        open.connect_clicked(move |_| {
+            if let Err(e) = package::open_url(&path) {
+                log::error!("Failed to open log {}: {}", path, e);
+            }
+        });
+        row.append(&open);
+    }
+
+    row
+}
+
+/// Local `YYYY-MM-DD HH:MM` for a unix timestamp.
+fn format_timestamp(secs: u64) -> String {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|s| glib::DateTime::from_unix_local(s).ok())
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M").ok())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| String::from("unknown time"))
+}
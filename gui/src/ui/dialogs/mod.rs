@@ -3,6 +3,7 @@
 //! This module contains all dialog-related UI components:
 //! - `about`: About dialog with creator information
 //! - `error`: Simple error message dialogs
+//! - `history`: List of previous task runs and their logs
 //! - `selection`: Multi-choice selection dialogs
 //! - `download`: ISO download dialogs
 //! - `terminal`: Interactive terminal dialogs
@@ -10,6 +11,7 @@
 pub mod about;
 pub mod download;
 pub mod error;
+pub mod history;
 pub mod selection;
 pub mod terminal;
 pub mod warning;
@@ -8,22 +8,50 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, Builder, Button, CheckButton, Label, Separator, Window};
+use gtk4::{
+    Align, Box as GtkBox, Builder, Button, CheckButton, Expander, Label, MenuButton, Popover,
+    Separator, Window,
+};
 use log::info;
 
+use crate::core::settings;
 use crate::ui::utils::extract_widget;
 
 /// One row in the picker.
 ///
 /// `installed` rows are rendered as pre-checked and non-interactive — they
 /// communicate "already in place, no action needed" rather than being
-/// available for selection.
+/// available for selection — unless the dialog
+/// [allows removal](SelectionDialogConfig::allow_removal).
+///
+/// Options sharing a `group` are shown together under a collapsible
+/// header; ungrouped options render as plain rows. An `unavailable` option
+/// is shown disabled, with the reason as its tooltip. A `preselected` option
+/// starts checked but stays interactive, for suggested defaults.
+///
+/// `details` is optional longer text — what an option pulls in, or what it
+/// changes on the system — behind an info button on the row. Options
+/// without it (or without a description) simply render less.
+///
+/// `installs` lists the concrete package or Flatpak ids behind an option;
+/// the dialog sums them up for whatever is checked, live.
+///
+/// In a multi-select dialog, options sharing an `exclusive` key behave like
+/// radio buttons among themselves — checking one unchecks the others — while
+/// every other option stays freely selectable. That lets one dialog mix
+/// "pick one backend" with "pick any plugins".
 #[derive(Clone, Debug)]
 pub struct SelectionOption {
     pub id: String,
     pub label: String,
     pub description: String,
     pub installed: bool,
+    pub group: Option<String>,
+    pub unavailable: Option<String>,
+    pub preselected: bool,
+    pub exclusive: Option<String>,
+    pub details: Option<String>,
+    pub installs: Vec<String>,
 }
 
 impl SelectionOption {
@@ -33,8 +61,52 @@ impl SelectionOption {
             label: label.to_owned(),
             description: description.to_owned(),
             installed,
+            group: None,
+            unavailable: None,
+            preselected: false,
+            exclusive: None,
+            details: None,
+            installs: Vec::new(),
         }
     }
+
+    /// Place the option under a named section header.
+    pub fn group(mut self, name: &str) -> Self {
+        self.group = Some(name.to_owned());
+        self
+    }
+
+    /// Start the option checked without locking it.
+    pub fn preselected(mut self, preselected: bool) -> Self {
+        self.preselected = preselected;
+        self
+    }
+
+    /// Make the option mutually exclusive with every other option given the
+    /// same `key`.
+    pub fn exclusive(mut self, key: &str) -> Self {
+        self.exclusive = Some(key.to_owned());
+        self
+    }
+
+    /// Longer explanation, shown on hover and in a popover from the row's
+    /// info button.
+    pub fn details(mut self, text: &str) -> Self {
+        self.details = Some(text.to_owned());
+        self
+    }
+
+    /// Package or Flatpak ids this option installs, for the summary.
+    pub fn installs(mut self, ids: &[&str]) -> Self {
+        self.installs = ids.iter().map(|&id| id.to_owned()).collect();
+        self
+    }
+
+    /// Disable the option, explaining why in its tooltip.
+    pub fn unavailable(mut self, reason: &str) -> Self {
+        self.unavailable = Some(reason.to_owned());
+        self
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -49,7 +121,16 @@ pub struct SelectionDialogConfig {
     pub options: Vec<SelectionOption>,
     pub confirm_label: String,
     pub selection_type: SelectionType,
-    pub selection_required: bool,
+    /// Fewest options that must be checked before confirm is allowed.
+    pub min_selection: usize,
+    /// Most options that may be checked; `None` is unbounded.
+    pub max_selection: Option<usize>,
+    /// What the options are, for the hint under the list ("plugin").
+    pub item_noun: String,
+    pub allow_removal: bool,
+    pub extra: Option<gtk4::Widget>,
+    /// Settings key the confirmed choice is saved under and restored from.
+    pub remember: Option<String>,
 }
 
 impl SelectionDialogConfig {
@@ -60,7 +141,12 @@ impl SelectionDialogConfig {
             options: Vec::new(),
             confirm_label: String::from("Install"),
             selection_type: SelectionType::Multi,
-            selection_required: true,
+            min_selection: 1,
+            max_selection: None,
+            item_noun: String::from("option"),
+            allow_removal: false,
+            extra: None,
+            remember: None,
         }
     }
 
@@ -79,28 +165,119 @@ impl SelectionDialogConfig {
         self
     }
 
+    /// Shorthand for a minimum of one (`true`) or zero (`false`).
     pub fn selection_required(mut self, required: bool) -> Self {
-        self.selection_required = required;
+        self.min_selection = usize::from(required);
+        self
+    }
+
+    pub fn min_selection(mut self, min: usize) -> Self {
+        self.min_selection = min;
+        self
+    }
+
+    pub fn max_selection(mut self, max: usize) -> Self {
+        self.max_selection = Some(max);
+        self
+    }
+
+    /// Name the options in the selection hint, e.g. "plugin" for
+    /// "Select at least one plugin".
+    pub fn item_noun(mut self, noun: &str) -> Self {
+        self.item_noun = noun.to_owned();
+        self
+    }
+
+    /// Keep installed rows interactive so they can be unchecked. The
+    /// callback then receives every checked id, installed or not, and the
+    /// caller diffs it against what was installed.
+    pub fn allow_removal(mut self, allow: bool) -> Self {
+        self.allow_removal = allow;
+        self
+    }
+
+    /// Remember what the user confirmed under `dialog_id` and check the
+    /// same options the next time, in place of the `preselected` defaults.
+    /// Installed options show as checked either way.
+    pub fn remember_as(mut self, dialog_id: &str) -> Self {
+        self.remember = Some(dialog_id.to_owned());
+        self
+    }
+
+    /// Show `widget` below the options. The caller keeps its own handles
+    /// to whatever inputs it contains and reads them in the callback.
+    pub fn extra_widget(mut self, widget: &impl IsA<gtk4::Widget>) -> Self {
+        self.extra = Some(widget.clone().upcast());
         self
     }
 }
 
+/// How many rows may be checked, and what to call them when telling the
+/// user they're outside that range.
+#[derive(Clone)]
+struct Bounds {
+    min: usize,
+    max: Option<usize>,
+    noun: String,
+}
+
+impl Bounds {
+    fn from_config(config: &SelectionDialogConfig) -> Self {
+        let max = match config.selection_type {
+            SelectionType::Single => Some(config.max_selection.unwrap_or(1).min(1)),
+            SelectionType::Multi => config.max_selection,
+        };
+        Self {
+            min: config.min_selection,
+            max,
+            noun: config.item_noun.clone(),
+        }
+    }
+
+    /// `None` when `count` is acceptable, otherwise what to tell the user.
+    fn hint(&self, count: usize) -> Option<String> {
+        let amount = |n: usize| match n {
+            1 => format!("one {}", self.noun),
+            n => format!("{} {}s", n, self.noun),
+        };
+        if Some(self.min) == self.max && count != self.min {
+            return Some(format!("Select exactly {}", amount(self.min)));
+        }
+        if count < self.min {
+            return Some(format!("Select at least {}", amount(self.min)));
+        }
+        match self.max {
+            Some(max) if count > max => Some(format!("Select at most {}", amount(max))),
+            _ => None,
+        }
+    }
+}
+
 /// Internal bookkeeping for a single row. The toggle is whatever widget
 /// we created (radio or checkbox, both `CheckButton` in GTK4) paired with
-/// the option's caller-facing ID.
+/// the option's caller-facing ID and the row widget it lives in.
 struct RowHandle {
     id: String,
     toggle: CheckButton,
+    container: GtkBox,
+    exclusive: Option<String>,
+    installs: Vec<String>,
 }
 
 /// Open the dialog, wire up the toggle logic, and fire `on_confirm` with
 /// the selected IDs when the user hits confirm.
-pub fn show_selection_dialog<F>(parent: &Window, config: SelectionDialogConfig, on_confirm: F)
+pub fn show_selection_dialog<F>(parent: &Window, mut config: SelectionDialogConfig, on_confirm: F)
 where
     F: Fn(Vec<String>) + 'static,
 {
     info!("opening selection dialog: {}", config.title);
 
+    if let Some(saved) = config.remember.as_deref().and_then(settings::last_selection) {
+        for option in &mut config.options {
+            option.preselected = saved.contains(&option.id);
+        }
+    }
+
     let builder = Builder::from_resource(crate::config::resources::dialogs::SELECTION);
 
     let dialog: Window = extract_widget(&builder, "selection_dialog");
@@ -109,6 +286,12 @@ where
     let options_container: GtkBox = extract_widget(&builder, "options_container");
     let cancel_button: Button = extract_widget(&builder, "cancel_button");
     let confirm_button: Button = extract_widget(&builder, "confirm_button");
+    let bulk_actions: GtkBox = extract_widget(&builder, "bulk_actions_box");
+    let select_all_button: Button = extract_widget(&builder, "select_all_button");
+    let deselect_all_button: Button = extract_widget(&builder, "deselect_all_button");
+    let hint_label: Label = extract_widget(&builder, "selection_hint");
+    let summary: Expander = extract_widget(&builder, "install_summary");
+    let summary_label: Label = extract_widget(&builder, "install_summary_label");
 
     dialog.set_transient_for(Some(parent));
     title_label.set_label(&config.title);
@@ -116,56 +299,122 @@ where
     confirm_button.set_label(&config.confirm_label);
 
     let selection_type = config.selection_type;
-    let selection_required = config.selection_required;
+    let bounds = Bounds::from_config(&config);
 
     let rows = Rc::new(RefCell::new(populate_options(
         &options_container,
         &config.options,
         selection_type,
+        config.allow_removal,
     )));
+    if let Some(extra) = &config.extra {
+        options_container.append(&Separator::new(gtk4::Orientation::Horizontal));
+        options_container.append(extra);
+    }
 
-    apply_confirm_sensitivity(&confirm_button, &rows.borrow(), selection_required);
-    wire_sync_on_toggle(&confirm_button, &rows, selection_required);
+    apply_confirm_sensitivity(&confirm_button, &hint_label, &rows.borrow(), &bounds);
+    wire_sync_on_toggle(&confirm_button, &hint_label, &rows, &bounds);
+    wire_exclusive(&rows);
+    if config.options.iter().any(|o| !o.installs.is_empty()) {
+        wire_install_summary(&summary, &summary_label, &rows);
+    }
+    if selection_type == SelectionType::Multi {
+        bulk_actions.set_visible(true);
+        wire_bulk_toggle(&select_all_button, &rows, true);
+        wire_bulk_toggle(&deselect_all_button, &rows, false);
+    }
     wire_cancel(&cancel_button, &dialog);
-    wire_confirm(&confirm_button, &dialog, &rows, on_confirm);
+    wire_confirm(&confirm_button, &dialog, &rows, bounds, config.remember, on_confirm);
 
     dialog.present();
 }
 
+/// Render every option, bucketing grouped ones under an expander per group
+/// (in order of first appearance). The returned handles keep the original
+/// option order so confirm reports ids exactly as before grouping existed.
 fn populate_options(
     container: &GtkBox,
     options: &[SelectionOption],
     kind: SelectionType,
+    allow_removal: bool,
 ) -> Vec<RowHandle> {
-    let mut rows = Vec::with_capacity(options.len());
-    let mut group_anchor: Option<CheckButton> = None;
-
-    for (i, option) in options.iter().enumerate() {
-        let toggle = CheckButton::new();
-        if matches!(kind, SelectionType::Single) {
-            match group_anchor.as_ref() {
-                Some(anchor) => toggle.set_group(Some(anchor)),
-                None => group_anchor = Some(toggle.clone()),
-            }
+    let mut sections: Vec<(Option<&str>, Vec<(usize, &SelectionOption)>)> = Vec::new();
+    for (index, option) in options.iter().enumerate() {
+        let key = option.group.as_deref();
+        match sections.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push((index, option)),
+            None => sections.push((key, vec![(index, option)])),
         }
-        toggle.set_active(option.installed);
-        toggle.set_sensitive(!option.installed);
+    }
+
+    let mut rows: Vec<(usize, RowHandle)> = Vec::with_capacity(options.len());
+    let mut group_anchor: Option<CheckButton> = None;
 
-        container.append(&build_row(&toggle, option));
-        if i + 1 < options.len() {
+    for (s, (group, members)) in sections.iter().enumerate() {
+        if s > 0 {
             container.append(&Separator::new(gtk4::Orientation::Horizontal));
         }
+        let target = match group {
+            Some(name) => build_section(container, name),
+            None => container.clone(),
+        };
+
+        for (i, &(index, option)) in members.iter().enumerate() {
+            let toggle = CheckButton::new();
+            if matches!(kind, SelectionType::Single) {
+                match group_anchor.as_ref() {
+                    Some(anchor) => toggle.set_group(Some(anchor)),
+                    None => group_anchor = Some(toggle.clone()),
+                }
+            }
+            let locked = (option.installed && !allow_removal) || option.unavailable.is_some();
+            toggle.set_active(
+                (option.installed || option.preselected) && option.unavailable.is_none(),
+            );
+            toggle.set_sensitive(!locked);
+
+            let row = build_row(&toggle, option, locked);
+            row.set_tooltip_text(option.unavailable.as_deref());
+            target.append(&row);
+            if i + 1 < members.len() {
+                target.append(&Separator::new(gtk4::Orientation::Horizontal));
+            }
 
-        rows.push(RowHandle {
-            id: option.id.clone(),
-            toggle,
-        });
+            rows.push((
+                index,
+                RowHandle {
+                    id: option.id.clone(),
+                    toggle,
+                    container: row,
+                    exclusive: option.exclusive.clone(),
+                    installs: option.installs.clone(),
+                },
+            ));
+        }
     }
 
-    rows
+    rows.sort_by_key(|(index, _)| *index);
+    rows.into_iter().map(|(_, row)| row).collect()
 }
 
-fn build_row(toggle: &CheckButton, option: &SelectionOption) -> GtkBox {
+/// Append a collapsible section and return the box its rows go into.
+fn build_section(container: &GtkBox, name: &str) -> GtkBox {
+    let header = Label::new(Some(name));
+    header.set_css_classes(&["heading"]);
+
+    let body = GtkBox::new(gtk4::Orientation::Vertical, 12);
+    body.set_margin_top(8);
+
+    let expander = Expander::new(None);
+    expander.set_label_widget(Some(&header));
+    expander.set_expanded(true);
+    expander.set_child(Some(&body));
+    container.append(&expander);
+
+    body
+}
+
+fn build_row(toggle: &CheckButton, option: &SelectionOption, locked: bool) -> GtkBox {
     let row = GtkBox::new(gtk4::Orientation::Horizontal, 12);
     row.set_margin_start(12);
     row.set_margin_end(12);
@@ -178,40 +427,75 @@ fn build_row(toggle: &CheckButton, option: &SelectionOption) -> GtkBox {
     let title = Label::new(Some(&option.label));
     title.set_halign(Align::Start);
     title.set_wrap(true);
-    if option.installed {
+    if locked {
         title.set_css_classes(&["dim"]);
     }
 
-    let caption = Label::new(Some(&option.description));
-    caption.set_css_classes(&["dim", "caption"]);
-    caption.set_halign(Align::Start);
-    caption.set_wrap(true);
-
     text_column.append(&title);
-    text_column.append(&caption);
+    if !option.description.is_empty() {
+        let caption = Label::new(Some(&option.description));
+        caption.set_css_classes(&["dim", "caption"]);
+        caption.set_halign(Align::Start);
+        caption.set_wrap(true);
+        text_column.append(&caption);
+    }
 
     row.append(toggle);
     row.append(&text_column);
+    if let Some(details) = option.details.as_deref().filter(|d| !d.is_empty()) {
+        row.append(&build_details_button(details));
+    }
     row
 }
 
-fn any_active(rows: &[RowHandle]) -> bool {
-    rows.iter().any(|r| r.toggle.is_active())
+/// "ⓘ" button revealing `details` in a popover; hovering shows it too.
+fn build_details_button(details: &str) -> MenuButton {
+    let text = Label::new(Some(details));
+    text.set_wrap(true);
+    text.set_max_width_chars(48);
+    text.set_xalign(0.0);
+    text.set_margin_top(6);
+    text.set_margin_bottom(6);
+    text.set_margin_start(6);
+    text.set_margin_end(6);
+
+    let popover = Popover::new();
+    popover.set_child(Some(&text));
+
+    let button = MenuButton::new();
+    button.set_icon_name("info-circle-symbolic");
+    button.set_valign(Align::Center);
+    button.set_css_classes(&["flat", "circular"]);
+    button.set_tooltip_text(Some(details));
+    button.set_popover(Some(&popover));
+    button
+}
+
+/// Rows confirm would report: checked and not locked.
+fn checked_rows(rows: &[RowHandle]) -> impl Iterator<Item = &RowHandle> {
+    rows.iter()
+        .filter(|r| r.toggle.is_active() && r.toggle.is_sensitive())
 }
 
-fn apply_confirm_sensitivity(button: &Button, rows: &[RowHandle], required: bool) {
-    button.set_sensitive(!required || any_active(rows));
+fn apply_confirm_sensitivity(button: &Button, hint: &Label, rows: &[RowHandle], bounds: &Bounds) {
+    let problem = bounds.hint(checked_rows(rows).count());
+    button.set_sensitive(problem.is_none());
+    hint.set_label(problem.as_deref().unwrap_or_default());
+    hint.set_visible(problem.is_some());
 }
 
 fn wire_sync_on_toggle(
     button: &Button,
+    hint: &Label,
     rows: &Rc<RefCell<Vec<RowHandle>>>,
-    required: bool,
+    bounds: &Bounds,
 ) {
     let button = button.clone();
+    let hint = hint.clone();
+    let bounds = bounds.clone();
     let rows_for_sync = rows.clone();
     let sync: Rc<dyn Fn()> = Rc::new(move || {
-        button.set_sensitive(!required || any_active(&rows_for_sync.borrow()));
+        apply_confirm_sensitivity(&button, &hint, &rows_for_sync.borrow(), &bounds);
     });
 
     for row in rows.borrow().iter() {
@@ -220,6 +504,82 @@ fn wire_sync_on_toggle(
     }
 }
 
+/// Checking a row unchecks the other rows of its exclusivity group. Turning
+/// the others off re-enters this handler with `is_active() == false`, which
+/// is a no-op, so there's no feedback loop.
+fn wire_exclusive(rows: &Rc<RefCell<Vec<RowHandle>>>) {
+    for row in rows.borrow().iter() {
+        let Some(key) = row.exclusive.clone() else {
+            continue;
+        };
+        let rows = Rc::downgrade(rows);
+        let own_id = row.id.clone();
+        row.toggle.connect_toggled(move |toggle| {
+            if !toggle.is_active() {
+                return;
+            }
+            let Some(rows) = rows.upgrade() else {
+                return;
+            };
+            for other in rows.borrow().iter() {
+                if other.id != own_id
+                    && other.exclusive.as_deref() == Some(key.as_str())
+                    && other.toggle.is_sensitive()
+                {
+                    other.toggle.set_active(false);
+                }
+            }
+        });
+    }
+}
+
+/// Show the ids behind the rows confirm would report (so not locked,
+/// already-installed ones), and keep the list current as rows are toggled.
+fn wire_install_summary(summary: &Expander, label: &Label, rows: &Rc<RefCell<Vec<RowHandle>>>) {
+    let summary = summary.clone();
+    let label = label.clone();
+    let rows_for_sync = Rc::downgrade(rows);
+    let sync: Rc<dyn Fn()> = Rc::new(move || {
+        let Some(rows) = rows_for_sync.upgrade() else {
+            return;
+        };
+        let mut ids: Vec<String> = Vec::new();
+        for row in checked_rows(&rows.borrow()) {
+            for id in &row.installs {
+                if !ids.contains(id) {
+                    ids.push(id.clone());
+                }
+            }
+        }
+        summary.set_visible(true);
+        if ids.is_empty() {
+            label.set_label("Nothing selected.");
+        } else {
+            label.set_label(&ids.join("\n"));
+        }
+    });
+
+    sync();
+    for row in rows.borrow().iter() {
+        let sync = sync.clone();
+        row.toggle.connect_toggled(move |_| sync());
+    }
+}
+
+/// Check or uncheck every row the user could toggle by hand. Installed
+/// rows are locked and hidden rows are out of the user's view, so both
+/// are left alone.
+fn wire_bulk_toggle(button: &Button, rows: &Rc<RefCell<Vec<RowHandle>>>, active: bool) {
+    let rows = rows.clone();
+    button.connect_clicked(move |_| {
+        for row in rows.borrow().iter() {
+            if row.toggle.is_sensitive() && row.container.is_visible() {
+                row.toggle.set_active(active);
+            }
+        }
+    });
+}
+
 fn wire_cancel(button: &Button, dialog: &Window) {
     let dialog = dialog.clone();
     button.connect_clicked(move |_| {
@@ -232,6 +592,8 @@ fn wire_confirm<F>(
     button: &Button,
     dialog: &Window,
     rows: &Rc<RefCell<Vec<RowHandle>>>,
+    bounds: Bounds,
+    remember: Option<String>,
     on_confirm: F,
 ) where
     F: Fn(Vec<String>) + 'static,
@@ -239,12 +601,21 @@ fn wire_confirm<F>(
     let dialog = dialog.clone();
     let rows = rows.clone();
     button.connect_clicked(move |_| {
-        let selected: Vec<String> = rows
-            .borrow()
-            .iter()
-            .filter(|r| r.toggle.is_active() && r.toggle.is_sensitive())
-            .map(|r| r.id.clone())
-            .collect();
+        let selected: Vec<String> = checked_rows(&rows.borrow()).map(|r| r.id.clone()).collect();
+        if let Some(problem) = bounds.hint(selected.len()) {
+            info!("selection rejected: {}", problem);
+            return;
+        }
+        if let Some(dialog_id) = &remember {
+            // Installed rows count too: they are part of the set the user wants.
+            let intent: Vec<String> = rows
+                .borrow()
+                .iter()
+                .filter(|r| r.toggle.is_active())
+                .map(|r| r.id.clone())
+                .collect();
+            settings::set_last_selection(dialog_id, &intent);
+        }
         info!("selection confirmed ({} item(s))", selected.len());
         on_confirm(selected);
         dialog.close();
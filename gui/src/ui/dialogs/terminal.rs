@@ -1,9 +1,20 @@
 //! Interactive terminal dialog for running shell commands.
+//!
+//! The header bar has a save button that writes the scrollback to a file.
+//! VTE keeps rendered cells rather than the raw byte stream, so the saved
+//! transcript is plain text: line breaks are preserved and colour/cursor
+//! escape codes are already gone.
+//!
+//! Ctrl + `+`/`-`/`0` (or Ctrl + scroll) zoom the text; the zoom level is
+//! remembered in the settings for the next terminal. Ctrl + F opens a
+//! search bar over the scrollback with next/previous navigation that wraps
+//! around, case-insensitive unless "Aa" is toggled.
 
-use crate::ui::utils::extract_widget;
-use gtk4::gdk::RGBA;
+use crate::core::settings;
+use crate::ui::utils::{extract_widget, file_stem};
+use gtk4::gdk::{self, RGBA};
 use gtk4::prelude::*;
-use gtk4::{Builder, Button, Window};
+use gtk4::{gio, glib, Builder, Button, SearchBar, SearchEntry, ToggleButton, Window};
 use log::{error, info};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -11,6 +22,14 @@ use std::str::FromStr;
 use vte4::prelude::*;
 use vte4::Terminal;
 
+const FONT_SCALE_STEP: f64 = 0.1;
+const FONT_SCALE_MIN: f64 = 0.5;
+const FONT_SCALE_MAX: f64 = 3.0;
+
+/// PCRE2 compile flags VTE search regexes use. VTE requires multiline.
+const PCRE2_CASELESS: u32 = 0x0000_0008;
+const PCRE2_MULTILINE: u32 = 0x0000_0400;
+
 fn update_terminal_style(terminal: &Terminal) {
     let style_manager = adw::StyleManager::default();
     let is_dark = style_manager.is_dark();
@@ -52,6 +71,7 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
     let window: adw::Window = extract_widget(&builder, "terminal_window");
     let terminal: Terminal = extract_widget(&builder, "terminal");
     let close_button: Button = extract_widget(&builder, "close_button");
+    let save_button: Button = extract_widget(&builder, "save_button");
 
     window.set_transient_for(Some(parent));
     window.set_title(Some(title));
@@ -62,6 +82,9 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
 
     // Setup theming
     update_terminal_style(&terminal);
+    terminal.set_font_scale(settings::terminal_font_scale());
+    setup_zoom(&terminal);
+    setup_search(&builder, &terminal);
 
     let terminal_weak = terminal.downgrade();
     let style_manager = adw::StyleManager::default();
@@ -86,6 +109,14 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
         window_clone.close();
     });
 
+    // Setup save button
+    let terminal_for_save = terminal.clone();
+    let window_for_save = window.clone();
+    let initial_name = format!("{}.log", file_stem(title, "terminal-output"));
+    save_button.connect_clicked(move |_| {
+        save_output(&terminal_for_save, window_for_save.upcast_ref(), &initial_name);
+    });
+
     // Spawn the command
     let mut argv = vec![command.to_string()];
     argv.extend(args.iter().map(|s| s.to_string()));
@@ -137,3 +168,214 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
 
     window.present();
 }
+
+/// Set the zoom, clamped to a readable range, and remember it.
+fn set_zoom(terminal: &Terminal, scale: f64) {
+    let scale = (scale.clamp(FONT_SCALE_MIN, FONT_SCALE_MAX) * 100.0).round() / 100.0;
+    terminal.set_font_scale(scale);
+    settings::set_terminal_font_scale(scale);
+}
+
+/// Ctrl + `+`/`=`, `-` and `0` zoom in, out and reset; Ctrl + scroll zooms
+/// too. Both controllers run in the capture phase so the shortcuts never
+/// reach the child process.
+fn setup_zoom(terminal: &Terminal) {
+    let keys = gtk4::EventControllerKey::new();
+    keys.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let term = terminal.clone();
+    keys.connect_key_pressed(move |_, key, _, state| {
+        if !state.contains(gdk::ModifierType::CONTROL_MASK) {
+            return glib::Propagation::Proceed;
+        }
+        let scale = term.font_scale();
+        match key {
+            gdk::Key::plus | gdk::Key::equal | gdk::Key::KP_Add => {
+                set_zoom(&term, scale + FONT_SCALE_STEP)
+            }
+            gdk::Key::minus | gdk::Key::KP_Subtract => set_zoom(&term, scale - FONT_SCALE_STEP),
+            gdk::Key::_0 | gdk::Key::KP_0 => set_zoom(&term, 1.0),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    });
+    terminal.add_controller(keys);
+
+    let scroll = gtk4::EventControllerScroll::new(gtk4::EventControllerScrollFlags::VERTICAL);
+    scroll.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let term = terminal.clone();
+    scroll.connect_scroll(move |controller, _, dy| {
+        if !controller
+            .current_event_state()
+            .contains(gdk::ModifierType::CONTROL_MASK)
+            || dy == 0.0
+        {
+            return glib::Propagation::Proceed;
+        }
+        let step = if dy < 0.0 { FONT_SCALE_STEP } else { -FONT_SCALE_STEP };
+        set_zoom(&term, term.font_scale() + step);
+        glib::Propagation::Stop
+    });
+    terminal.add_controller(scroll);
+}
+
+/// Wire the search bar: typing searches from the current position, Enter /
+/// Shift+Enter and the arrow buttons step through matches.
+fn setup_search(builder: &Builder, terminal: &Terminal) {
+    let bar: SearchBar = extract_widget(builder, "search_bar");
+    let entry: SearchEntry = extract_widget(builder, "search_entry");
+    let toggle: ToggleButton = extract_widget(builder, "search_button");
+    let case_button: ToggleButton = extract_widget(builder, "search_case_button");
+    let prev_button: Button = extract_widget(builder, "search_prev_button");
+    let next_button: Button = extract_widget(builder, "search_next_button");
+
+    bar.connect_entry(&entry);
+    toggle
+        .bind_property("active", &bar, "search-mode-enabled")
+        .bidirectional()
+        .sync_create()
+        .build();
+    terminal.search_set_wrap_around(true);
+
+    let apply = {
+        let terminal = terminal.clone();
+        let entry = entry.clone();
+        let case_button = case_button.clone();
+        move || {
+            set_search_pattern(&terminal, &entry.text(), case_button.is_active());
+            terminal.search_find_next();
+        }
+    };
+    let apply_on_change = apply.clone();
+    entry.connect_search_changed(move |_| apply_on_change());
+    case_button.connect_toggled(move |_| apply());
+
+    let term = terminal.clone();
+    entry.connect_activate(move |_| {
+        term.search_find_next();
+    });
+    let term = terminal.clone();
+    entry.connect_next_match(move |_| {
+        term.search_find_next();
+    });
+    let term = terminal.clone();
+    entry.connect_previous_match(move |_| {
+        term.search_find_previous();
+    });
+    let term = terminal.clone();
+    next_button.connect_clicked(move |_| {
+        term.search_find_next();
+    });
+    let term = terminal.clone();
+    prev_button.connect_clicked(move |_| {
+        term.search_find_previous();
+    });
+
+    // Shift+Enter goes back, like most search bars.
+    let keys = gtk4::EventControllerKey::new();
+    let term = terminal.clone();
+    keys.connect_key_pressed(move |_, key, _, state| {
+        if state.contains(gdk::ModifierType::SHIFT_MASK)
+            && matches!(key, gdk::Key::Return | gdk::Key::KP_Enter)
+        {
+            term.search_find_previous();
+            return glib::Propagation::Stop;
+        }
+        glib::Propagation::Proceed
+    });
+    entry.add_controller(keys);
+
+    // Closing the bar drops the highlight and hands focus back.
+    let term = terminal.clone();
+    bar.connect_search_mode_enabled_notify(move |bar| {
+        if !bar.is_search_mode() {
+            term.search_set_regex(None, 0);
+            term.unselect_all();
+            term.grab_focus();
+        }
+    });
+
+    // Ctrl+F from inside the terminal, captured before the child sees it.
+    let keys = gtk4::EventControllerKey::new();
+    keys.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    keys.connect_key_pressed(move |_, key, _, state| {
+        if state.contains(gdk::ModifierType::CONTROL_MASK)
+            && matches!(key, gdk::Key::f | gdk::Key::F)
+        {
+            bar.set_search_mode(true);
+            entry.grab_focus();
+            return glib::Propagation::Stop;
+        }
+        glib::Propagation::Proceed
+    });
+    terminal.add_controller(keys);
+}
+
+/// Search for `text` literally. An empty or unusable pattern clears it.
+fn set_search_pattern(terminal: &Terminal, text: &str, match_case: bool) {
+    if text.is_empty() {
+        terminal.search_set_regex(None, 0);
+        return;
+    }
+    let mut flags = PCRE2_MULTILINE;
+    if !match_case {
+        flags |= PCRE2_CASELESS;
+    }
+    match vte4::Regex::for_search(&escape_regex(text), flags) {
+        Ok(regex) => terminal.search_set_regex(Some(&regex), 0),
+        Err(e) => {
+            error!("Invalid terminal search pattern {:?}: {}", text, e);
+            terminal.search_set_regex(None, 0);
+        }
+    }
+}
+
+/// Escape PCRE metacharacters so the entry text is matched as typed.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Ask for a file and write the terminal's scrollback to it as plain text.
+fn save_output(terminal: &Terminal, parent: &Window, initial_name: &str) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title("Save Output");
+    dialog.set_initial_name(Some(initial_name));
+
+    let terminal = terminal.clone();
+    let parent = parent.clone();
+    glib::spawn_future_local(async move {
+        let Ok(file) = dialog.save_future(Some(&parent)).await else {
+            return;
+        };
+        let result = file
+            .replace(
+                None,
+                false,
+                gio::FileCreateFlags::REPLACE_DESTINATION,
+                None::<&gio::Cancellable>,
+            )
+            .and_then(|stream| {
+                terminal.write_contents_sync(
+                    &stream,
+                    vte4::WriteFlags::Default,
+                    None::<&gio::Cancellable>,
+                )?;
+                stream.close(None::<&gio::Cancellable>)
+            });
+        let path = file.parse_name();
+        match result {
+            Ok(()) => info!("Terminal: saved output to {}", path),
+            Err(e) => {
+                error!("Failed to save terminal output to {}: {}", path, e);
+                let message = format!("\r\n[ERROR] Could not save output: {}\r\n", e);
+                terminal.feed(message.as_bytes());
+            }
+        }
+    });
+}
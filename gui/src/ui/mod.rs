@@ -7,6 +7,7 @@
 //! - `dialogs`: Dialog windows (error, selection, download)
 //! - `task_runner`: Command execution with progress UI
 //! - `pages`: Page-specific button handlers
+//! - `tool_search`: Sidebar search across every page's tools
 
 pub mod app;
 pub mod context;
@@ -15,6 +16,7 @@ pub mod navigation;
 pub mod pages;
 pub mod seasonal;
 pub mod task_runner;
+pub mod tool_search;
 pub mod utils;
 
 // Re-export the main entry point
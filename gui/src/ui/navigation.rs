@@ -6,17 +6,33 @@
 //!    defers the actual XML parse + handler setup to the next idle cycle.
 //! 3. A `loaded_pages` / `loading_pages` guard prevents duplicate loads.
 //!
-//! Only the first page is loaded eagerly (synchronously at startup) so the user
-//! sees real content the moment the window appears.
+//! Only the remembered page (the last one opened, or the first in [`PAGES`]) is
+//! loaded eagerly at startup so the user sees real content the moment the
+//! window appears.
+//!
+//! Shortly after startup the remaining pages' UI XML is parsed one page per
+//! low-priority idle cycle (unless the `preload_pages` setting is off). Setup
+//! handlers are *not* run then — they spawn subprocesses — so a preloaded
+//! page's first visit only has to wire itself up. Everything happens on the
+//! main loop, so a tab click and a preload step never interleave: whichever
+//! runs first decides, and the other sees the page as taken.
 
 use crate::ui::pages;
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::{ApplicationWindow, Box as GtkBox, Builder, Button, Image, Label, Orientation, Stack};
+use gtk4::{
+    ApplicationWindow, Box as GtkBox, Builder, Button, CallbackAction, Image, Label, Orientation,
+    Shortcut, ShortcutController, ShortcutTrigger, Stack,
+};
 use log::{info, warn};
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Duration;
+
+/// Delay after startup before unvisited pages start being preloaded, so the
+/// initial page and the window get the main loop to themselves first.
+const PRELOAD_DELAY: Duration = Duration::from_secs(2);
 
 /// Configuration for a single page in the application.
 pub struct PageConfig {
@@ -25,6 +41,16 @@ pub struct PageConfig {
     pub icon: &'static str,
     pub ui_resource: &'static str,
     pub setup_handler: Option<fn(&Builder, &Builder, &ApplicationWindow)>,
+    pub tools: &'static [Tool],
+}
+
+/// A tool offered on a page, as listed in the sidebar search.
+pub struct Tool {
+    pub name: &'static str,
+    /// Extra space-separated search terms (package names, aliases).
+    pub keywords: &'static str,
+    /// Builder id of the button that launches the tool.
+    pub button: &'static str,
 }
 
 /// Central list of all pages in the application.
@@ -36,6 +62,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "house-symbolic",
         ui_resource: crate::config::resources::tabs::MAIN_PAGE,
         setup_handler: Some(pages::main_page::setup_handlers),
+        tools: pages::main_page::TOOLS,
     },
     PageConfig {
         id: "drivers",
@@ -43,6 +70,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "gear-symbolic",
         ui_resource: crate::config::resources::tabs::DRIVERS,
         setup_handler: Some(pages::drivers::setup_handlers),
+        tools: pages::drivers::TOOLS,
     },
     PageConfig {
         id: "customization",
@@ -50,6 +78,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "brush-symbolic",
         ui_resource: crate::config::resources::tabs::CUSTOMIZATION,
         setup_handler: Some(pages::customization::setup_handlers),
+        tools: pages::customization::TOOLS,
     },
     PageConfig {
         id: "gaming_tools",
@@ -57,6 +86,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "gamepad-symbolic",
         ui_resource: crate::config::resources::tabs::GAMING_TOOLS,
         setup_handler: Some(pages::gaming_tools::setup_handlers),
+        tools: pages::gaming_tools::TOOLS,
     },
     PageConfig {
         id: "emulators",
@@ -64,6 +94,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "gamepad-symbolic",
         ui_resource: crate::config::resources::tabs::EMULATORS,
         setup_handler: Some(pages::emulators::setup_handlers),
+        tools: pages::emulators::TOOLS,
     },
     PageConfig {
         id: "gamescope",
@@ -71,6 +102,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "steam-symbolic",
         ui_resource: crate::config::resources::tabs::GAMESCOPE,
         setup_handler: Some(pages::gamescope::setup_handlers),
+        tools: pages::gamescope::TOOLS,
     },
     PageConfig {
         id: "containers_vms",
@@ -78,6 +110,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "box-symbolic",
         ui_resource: crate::config::resources::tabs::CONTAINERS_VMS,
         setup_handler: Some(pages::containers_vms::setup_handlers),
+        tools: pages::containers_vms::TOOLS,
     },
     PageConfig {
         id: "multimedia_tools",
@@ -85,6 +118,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "play-symbolic",
         ui_resource: crate::config::resources::tabs::MULTIMEDIA_TOOLS,
         setup_handler: Some(pages::multimedia_tools::setup_handlers),
+        tools: pages::multimedia_tools::TOOLS,
     },
     PageConfig {
         id: "kernel_schedulers",
@@ -92,6 +126,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "hammer-symbolic",
         ui_resource: crate::config::resources::tabs::KERNEL_SCHEDULERS,
         setup_handler: Some(pages::kernel_schedulers::setup_handlers),
+        tools: pages::kernel_schedulers::TOOLS,
     },
     PageConfig {
         id: "servicing_system_tweaks",
@@ -99,6 +134,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "toolbox-symbolic",
         ui_resource: crate::config::resources::tabs::SERVICING_SYSTEM_TWEAKS,
         setup_handler: Some(pages::servicing::setup_handlers),
+        tools: pages::servicing::TOOLS,
     },
     PageConfig {
         id: "biometrics",
@@ -106,6 +142,7 @@ pub const PAGES: &[PageConfig] = &[
         icon: "xfprintd-gui",
         ui_resource: crate::config::resources::tabs::BIOMETRICS,
         setup_handler: Some(pages::biometrics::setup_handlers),
+        tools: pages::biometrics::TOOLS,
     },
 ];
 
@@ -116,8 +153,10 @@ pub const PAGES: &[PageConfig] = &[
 /// Tracks which pages have been loaded or are currently loading, and performs
 /// the async load when a page is visited for the first time.
 pub struct LazyPageLoader {
-    loaded_pages: RefCell<HashSet<String>>,
-    loading_pages: RefCell<HashSet<String>>,
+    loaded_pages: Rc<RefCell<HashSet<String>>>,
+    loading_pages: Rc<RefCell<HashSet<String>>>,
+    /// Builders parsed ahead of time by [`Self::preload`], not yet set up.
+    preparsed: Rc<RefCell<HashMap<&'static str, Builder>>>,
     main_builder: Builder,
     window: ApplicationWindow,
 }
@@ -125,8 +164,9 @@ pub struct LazyPageLoader {
 impl LazyPageLoader {
     fn new(main_builder: Builder, window: ApplicationWindow) -> Self {
         Self {
-            loaded_pages: RefCell::new(HashSet::new()),
-            loading_pages: RefCell::new(HashSet::new()),
+            loaded_pages: Rc::new(RefCell::new(HashSet::new())),
+            loading_pages: Rc::new(RefCell::new(HashSet::new())),
+            preparsed: Rc::new(RefCell::new(HashMap::new())),
             main_builder,
             window,
         }
@@ -180,16 +220,32 @@ impl LazyPageLoader {
         let page_id_str = page_id.to_string();
         let ui_resource = config.ui_resource;
         let setup_handler = config.setup_handler;
+        let tools = config.tools;
         let title = config.title;
         let main_builder = self.main_builder.clone();
         let window = self.window.clone();
         let container = container.clone();
-        let loaded_pages = self.loaded_pages.clone();
-        let loading_pages = self.loading_pages.clone();
+        let loaded_pages = Rc::clone(&self.loaded_pages);
+        let loading_pages = Rc::clone(&self.loading_pages);
+        let preparsed = Rc::clone(&self.preparsed);
 
         // Defer the heavy work — UI will repaint (showing the spinner) first.
         glib::idle_add_local_once(move || {
-            match load_page_content(&page_id_str, ui_resource, setup_handler, &main_builder, &window) {
+            let preloaded = preparsed.borrow_mut().remove(page_id_str.as_str());
+            let page_builder = match preloaded {
+                Some(builder) => Ok(builder),
+                None => parse_page(ui_resource),
+            };
+            match page_builder.and_then(|page_builder| {
+                load_page_content(
+                    &page_id_str,
+                    &page_builder,
+                    setup_handler,
+                    tools,
+                    &main_builder,
+                    &window,
+                )
+            }) {
                 Ok(page_widget) => {
                     // Swap out the placeholder for the real page.
                     while let Some(child) = container.first_child() {
@@ -203,7 +259,11 @@ impl LazyPageLoader {
                     info!("Successfully lazy-loaded page '{}'", page_id_str);
                 }
                 Err(e) => {
-                    warn!("Failed to lazy-load page '{}': {}", page_id_str, e);
+                    crate::ui::dialogs::error::report_failure(
+                        &window,
+                        &format!("The {} page could not be loaded.", title),
+                        &format!("page '{}': {:#}", page_id_str, e),
+                    );
 
                     // Update placeholder to show an error state.
                     if let Some(spinner) = find_child_by_name::<Image>(&container, "loading_spinner") {
@@ -220,6 +280,37 @@ impl LazyPageLoader {
             }
         });
     }
+
+    /// Parse the UI of every page that hasn't been visited yet, one page per
+    /// low-priority idle cycle, starting [`PRELOAD_DELAY`] from now.
+    fn preload(self: &Rc<Self>) {
+        let loader = Rc::clone(self);
+        glib::timeout_add_local_once(PRELOAD_DELAY, move || {
+            let mut pending = PAGES.iter();
+            glib::idle_add_local_full(glib::Priority::LOW, move || {
+                let Some(config) = pending.find(|p| !loader.is_taken(p.id)) else {
+                    info!("Finished preloading pages");
+                    return glib::ControlFlow::Break;
+                };
+                // A page that fails here is reported when it's visited.
+                match parse_page(config.ui_resource) {
+                    Ok(page_builder) => {
+                        loader.preparsed.borrow_mut().insert(config.id, page_builder);
+                        info!("Preloaded UI for page '{}'", config.id);
+                    }
+                    Err(e) => warn!("Failed to preload page '{}': {:#}", config.id, e),
+                }
+                glib::ControlFlow::Continue
+            });
+        });
+    }
+
+    /// Already loaded, loading, or parsed and waiting for its first visit.
+    fn is_taken(&self, page_id: &str) -> bool {
+        self.is_loaded(page_id)
+            || self.is_loading(page_id)
+            || self.preparsed.borrow().contains_key(page_id)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -227,7 +318,7 @@ impl LazyPageLoader {
 // ---------------------------------------------------------------------------
 
 /// Recursively search the widget tree for a child with the given name.
-fn find_child_by_name<T>(parent: &impl IsA<gtk4::Widget>, name: &str) -> Option<T>
+pub(crate) fn find_child_by_name<T>(parent: &impl IsA<gtk4::Widget>, name: &str) -> Option<T>
 where
     T: IsA<gtk4::Widget> + IsA<glib::Object>,
 {
@@ -247,28 +338,47 @@ where
     None
 }
 
-/// Parse a page's UI resource and run its setup handler.
+/// Parse a page's UI resource. Unlike `Builder::from_resource`, which
+/// aborts the process, a missing or malformed resource comes back as an
+/// error the caller can show.
+fn parse_page(ui_resource: &str) -> anyhow::Result<Builder> {
+    let builder = Builder::new();
+    builder
+        .add_from_resource(ui_resource)
+        .map_err(|e| anyhow::anyhow!("Could not parse UI resource '{}': {}", ui_resource, e))?;
+    Ok(builder)
+}
+
+/// Pick the page widget out of its parsed UI resource and run its setup
+/// handler.
+///
+/// Tool buttons get their builder id as widget name so the sidebar search can
+/// find them again with [`find_child_by_name`].
 fn load_page_content(
     page_id: &str,
-    ui_resource: &str,
+    page_builder: &Builder,
     setup_handler: Option<fn(&Builder, &Builder, &ApplicationWindow)>,
+    tools: &[Tool],
     main_builder: &Builder,
     window: &ApplicationWindow,
 ) -> anyhow::Result<gtk4::Widget> {
-    let page_builder = Builder::from_resource(ui_resource);
-
     let page_widget: gtk4::Widget = page_builder
         .object(format!("page_{}", page_id))
         .ok_or_else(|| {
             anyhow::anyhow!(
-                "Could not find widget 'page_{}' in resource '{}'",
-                page_id,
-                ui_resource
+                "Could not find widget 'page_{}' in its UI resource",
+                page_id
             )
         })?;
 
+    for tool in tools {
+        if let Some(button) = page_builder.object::<gtk4::Widget>(tool.button) {
+            button.set_widget_name(tool.button);
+        }
+    }
+
     if let Some(setup_fn) = setup_handler {
-        setup_fn(&page_builder, main_builder, window);
+        setup_fn(page_builder, main_builder, window);
     }
 
     Ok(page_widget)
@@ -366,6 +476,7 @@ impl Tab {
 
             stack_clone.set_visible_child_name(&page_name);
             update_active_tab(&tabs_clone, &button_clone);
+            crate::core::settings::set_last_page(&page_name);
         });
     }
 }
@@ -376,16 +487,17 @@ impl Tab {
 
 /// Build the page stack and sidebar tabs, then return the configured stack.
 ///
-/// All pages except the first get placeholder containers; the first page is
-/// loaded eagerly so the user sees real content from the moment the window
-/// opens.
+/// Every page gets a placeholder container; the page remembered from the
+/// last session (or the first page, if none is remembered or it no longer
+/// exists) is loaded eagerly and shown so the user sees real content from
+/// the moment the window opens. The caller shows it via [`initial_page`].
 pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) -> Stack {
     info!("Creating dynamic stack with async lazy loading");
 
     let window: ApplicationWindow =
         crate::ui::utils::extract_widget(main_builder, "app_window");
 
-    let loader = Rc::new(LazyPageLoader::new(main_builder.clone(), window));
+    let loader = Rc::new(LazyPageLoader::new(main_builder.clone(), window.clone()));
 
     // Build stack — one placeholder container per page.
     let stack = Stack::new();
@@ -408,33 +520,103 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
         PAGES.len()
     );
 
-    // Build sidebar tabs.
-    let mut first_button: Option<Button> = None;
+    let initial = initial_page();
 
+    // Build sidebar tabs.
+    let mut buttons = Vec::with_capacity(PAGES.len());
     for page_config in PAGES {
         let tab = Tab::new(page_config.title, page_config.id, page_config.icon);
         tab.connect(&stack, tabs_container, &loader);
+        buttons.push(tab.button.clone());
 
-        if first_button.is_none() {
-            first_button = Some(tab.button.clone());
+        if Some(page_config.id) == initial {
+            tab.button.add_css_class("active");
         }
 
         tabs_container.append(&tab.button);
     }
 
-    if let Some(btn) = first_button {
-        btn.add_css_class("active");
+    // Eagerly load the initial page so it's ready when the window opens.
+    if let Some(id) = initial {
+        info!("Eagerly loading initial page '{}'", id);
+        loader.ensure_page_loaded(&stack, id);
     }
 
-    // Eagerly load the first page so it's ready when the window opens.
-    if let Some(first) = PAGES.first() {
-        info!("Eagerly loading first page '{}'", first.id);
-        loader.ensure_page_loaded(&stack, first.id);
+    if crate::core::settings::preload_pages() {
+        loader.preload();
+    } else {
+        info!("Page preloading disabled in settings");
     }
 
+    let buttons = Rc::new(buttons);
+    register_shortcuts(&window, &stack, Rc::clone(&buttons));
+    crate::ui::tool_search::setup(main_builder, &stack, buttons);
+
     stack
 }
 
+/// Ctrl+1 … Ctrl+9 and Ctrl+0 jump to the first ten pages; Ctrl+Tab and
+/// Ctrl+Shift+Tab cycle through all of them. Each shortcut clicks the tab
+/// button, so lazy loading and the active-tab highlight behave exactly as
+/// they do for the mouse.
+fn register_shortcuts(window: &ApplicationWindow, stack: &Stack, buttons: Rc<Vec<Button>>) {
+    let controller = ShortcutController::new();
+    controller.set_scope(gtk4::ShortcutScope::Global);
+    // Capture so Ctrl+Tab wins over focus navigation inside the pages.
+    controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+
+    for (i, button) in buttons.iter().take(10).enumerate() {
+        let button = button.clone();
+        let accel = format!("<Control>{}", (i + 1) % 10);
+        add_shortcut(&controller, &accel, move || button.emit_clicked());
+    }
+
+    for (accel, step) in [
+        ("<Control>Tab", 1),
+        ("<Control><Shift>Tab", -1),
+        ("<Control><Shift>ISO_Left_Tab", -1),
+    ] {
+        let stack = stack.clone();
+        let buttons = Rc::clone(&buttons);
+        add_shortcut(&controller, accel, move || cycle_tabs(&stack, &buttons, step));
+    }
+
+    window.add_controller(controller);
+}
+
+fn add_shortcut(controller: &ShortcutController, accel: &str, activate: impl Fn() + 'static) {
+    let Some(trigger) = ShortcutTrigger::parse_string(accel) else {
+        warn!("Invalid accelerator: {}", accel);
+        return;
+    };
+    let action = CallbackAction::new(move |_, _| {
+        activate();
+        glib::Propagation::Stop
+    });
+    controller.add_shortcut(Shortcut::new(Some(trigger), Some(action)));
+}
+
+/// Click the tab `step` places away from the visible page, wrapping around.
+fn cycle_tabs(stack: &Stack, buttons: &[Button], step: isize) {
+    let visible = stack.visible_child_name();
+    let current = PAGES
+        .iter()
+        .position(|p| visible.as_deref() == Some(p.id))
+        .unwrap_or(0);
+    let next = (current as isize + step).rem_euclid(buttons.len() as isize) as usize;
+    if let Some(button) = buttons.get(next) {
+        button.emit_clicked();
+    }
+}
+
+/// The remembered page if it's still in [`PAGES`], otherwise the first page.
+pub fn initial_page() -> Option<&'static str> {
+    crate::core::settings::last_page()
+        .and_then(|id| PAGES.iter().find(|p| p.id == id))
+        .or_else(|| PAGES.first())
+        .map(|p| p.id)
+}
+
 /// Highlight `clicked_button` and clear the active class from all others.
 fn update_active_tab(tabs_container: &GtkBox, clicked_button: &Button) {
     let mut child = tabs_container.first_child();
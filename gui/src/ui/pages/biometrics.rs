@@ -2,19 +2,53 @@
 //!
 //! Handles:
 //! - Fingerprint reader setup (xfprintd-gui - jailbroken edition from source)
+//! - Fingerprint enrollment (fprintd-enroll in the interactive terminal)
+//! - Fingerprint authentication (pam_fprintd in /etc/pam.d/system-auth)
 //! - Howdy facial recognition setup (xero-howdy-qt - build from source)
 
 use crate::core;
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::dialogs::terminal;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::{extract_widget, on_refocus};
 use gtk4::prelude::*;
-use gtk4::{ApplicationWindow, Builder};
+use gtk4::{glib, ApplicationWindow, Builder};
 use log::{error, info};
 use std::process::{Command as StdCommand, Stdio};
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Fingerprint Login",
+        keywords: "fprintd xfprintd",
+        button: "btn_fingerprint_setup",
+    },
+    Tool {
+        name: "Enroll Fingerprint",
+        keywords: "fprintd-enroll finger scan register",
+        button: "btn_fingerprint_enroll",
+    },
+    Tool {
+        name: "Fingerprint Authentication",
+        keywords: "pam pam_fprintd sudo login unlock",
+        button: "btn_fingerprint_pam",
+    },
+    Tool {
+        name: "Howdy Face Unlock",
+        keywords: "facial recognition ir camera",
+        button: "btn_howdy_setup",
+    },
+];
+
 /// Set up all button handlers for the biometrics page
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_fingerprint(page_builder, window);
+    setup_fingerprint_enroll(page_builder, window);
+    setup_fingerprint_pam(page_builder, window);
     setup_howdy(page_builder, window);
 }
 
@@ -53,11 +87,9 @@ fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
     // Update on window focus (e.g. after installation completes)
     let btn_setup_clone = btn_fingerprint_setup.clone();
     let btn_uninstall_clone = btn_fingerprint_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xfprintd-gui").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    on_refocus(window, move || {
+        let is_installed = std::path::Path::new("/usr/bin/xfprintd-gui").exists();
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     // Setup/Launch button handler
@@ -183,6 +215,228 @@ fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
+/// Fingers `fprintd-enroll -f` accepts: (id, label).
+const FINGERS: &[(&str, &str)] = &[
+    ("right-index-finger", "Right Index"),
+    ("right-thumb", "Right Thumb"),
+    ("right-middle-finger", "Right Middle"),
+    ("left-index-finger", "Left Index"),
+    ("left-thumb", "Left Thumb"),
+    ("left-middle-finger", "Left Middle"),
+];
+
+/// Whether fprintd sees a reader. `fprintd-list` prints "Device at …" per
+/// reader and "No devices available" otherwise; its exit code doesn't say.
+fn has_fingerprint_reader() -> bool {
+    let user = crate::config::env::get().user.clone();
+    StdCommand::new("fprintd-list")
+        .arg(&user)
+        .stdin(Stdio::null())
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains("Device at"))
+        .unwrap_or(false)
+}
+
+fn setup_fingerprint_enroll(page_builder: &Builder, window: &ApplicationWindow) {
+    let btn_enroll = extract_widget::<gtk4::Button>(page_builder, "btn_fingerprint_enroll");
+
+    let window = window.clone();
+    btn_enroll.connect_clicked(move |_| {
+        info!("Biometrics: Fingerprint enroll button clicked");
+
+        if core::is_package_installed("fprintd") {
+            check_reader_then_enroll(&window);
+            return;
+        }
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "fprintd"])
+                    .description("Installing fprintd...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, "Install fprintd");
+
+        // Carry on once the install finishes, if it succeeded.
+        let window = window.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+            if task_runner::is_running() {
+                return glib::ControlFlow::Continue;
+            }
+            if core::is_package_installed("fprintd") {
+                check_reader_then_enroll(&window);
+            }
+            glib::ControlFlow::Break
+        });
+    });
+}
+
+/// Probe for a reader off the main thread; enrolling without one would sit
+/// waiting for a touch that never comes.
+fn check_reader_then_enroll(window: &ApplicationWindow) {
+    let (tx, rx) = async_channel::bounded::<bool>(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking(has_fingerprint_reader());
+    });
+
+    let window = window.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(found) = rx.recv().await else {
+            return;
+        };
+        if found {
+            choose_finger(&window);
+        } else {
+            show_error(
+                &window,
+                "No fingerprint reader was found.\n\nMake sure the reader is enabled in your \
+                 firmware settings. Some readers also need a vendor driver from the AUR \
+                 (for example libfprint-goodixtls or python-validity).",
+            );
+        }
+    });
+}
+
+fn choose_finger(window: &ApplicationWindow) {
+    let mut config = SelectionDialogConfig::new(
+        "Enroll Fingerprint",
+        "Choose a finger, then touch the reader repeatedly when asked until \
+         enrollment completes.",
+    )
+    .selection_type(SelectionType::Single)
+    .confirm_label("Enroll");
+    for (i, (id, label)) in FINGERS.iter().enumerate() {
+        config = config.add_option(SelectionOption::new(id, label, "", false).preselected(i == 0));
+    }
+
+    let window_inner = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let Some(finger) = selected.first() else {
+            return;
+        };
+        terminal::show_terminal_dialog(
+            window_inner.upcast_ref(),
+            "Enroll Fingerprint",
+            "fprintd-enroll",
+            &["-f", finger.as_str()],
+        );
+    });
+}
+
+const PAM_SYSTEM_AUTH: &str = "/etc/pam.d/system-auth";
+
+/// Adds (`$1` = enable) or removes (`$1` = disable) the pam_fprintd line in
+/// system-auth. The edit is made on a copy and only installed if the stack
+/// still has exactly the expected fprintd lines and its pam_unix fallback,
+/// so a failed sed can never leave a half-written PAM file behind. A
+/// one-time backup of the untouched original is kept next to it.
+const PAM_FPRINTD_SCRIPT: &str = r#"
+set -e
+f=/etc/pam.d/system-auth
+line='auth       sufficient                  pam_fprintd.so'
+[ -f "$f.cyberxero.bak" ] || cp -p "$f" "$f.cyberxero.bak"
+tmp=$(mktemp)
+trap 'rm -f "$tmp"' EXIT
+cp -p "$f" "$tmp"
+if [ "$1" = enable ]; then
+    grep -Eq '^[[:space:]]*auth[[:space:]].*pam_fprintd\.so' "$tmp" && exit 0
+    sed -i "0,/^[[:space:]]*auth[[:space:]]/s//$line\n&/" "$tmp"
+    want=1
+else
+    sed -i -E '/^[[:space:]]*auth[[:space:]].*pam_fprintd\.so/d' "$tmp"
+    want=0
+fi
+if [ "$(grep -Ec '^[[:space:]]*auth[[:space:]].*pam_fprintd\.so' "$tmp")" != "$want" ] \
+    || ! grep -Eq '^[[:space:]]*auth[[:space:]].*pam_unix\.so' "$tmp"; then
+    echo "Refusing to write $f: the edited stack failed validation." >&2
+    exit 1
+fi
+install -m644 "$tmp" "$f"
+"#;
+
+/// Whether an active (uncommented) pam_fprintd auth line is in system-auth.
+fn is_fingerprint_pam_enabled() -> bool {
+    std::fs::read_to_string(PAM_SYSTEM_AUTH)
+        .map(|content| {
+            content.lines().any(|line| {
+                let line = line.trim_start();
+                line.starts_with("auth") && line.contains("pam_fprintd.so")
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn update_pam_button(button: &gtk4::Button) {
+    // pam_fprintd.so ships with fprintd; without it the line would only
+    // make PAM log a missing-module error on every login.
+    let has_fprintd = core::is_package_installed("fprintd");
+    button.set_sensitive(has_fprintd);
+    button.set_tooltip_text((!has_fprintd).then_some("Install fprintd and enroll a finger first"));
+    if is_fingerprint_pam_enabled() {
+        button.set_label("Disable Auth");
+        button.add_css_class("destructive-action");
+    } else {
+        button.set_label("Enable Auth");
+        button.remove_css_class("destructive-action");
+    }
+}
+
+fn setup_fingerprint_pam(page_builder: &Builder, window: &ApplicationWindow) {
+    let btn_pam = extract_widget::<gtk4::Button>(page_builder, "btn_fingerprint_pam");
+    update_pam_button(&btn_pam);
+
+    let btn_clone = btn_pam.clone();
+    on_refocus(window, move || {
+        update_pam_button(&btn_clone);
+    });
+
+    let window = window.clone();
+    btn_pam.connect_clicked(move |button| {
+        let enable = !is_fingerprint_pam_enabled();
+        info!(
+            "Biometrics: {} fingerprint authentication",
+            if enable { "Enabling" } else { "Disabling" }
+        );
+
+        let (action, description, title) = if enable {
+            (
+                "enable",
+                "Adding pam_fprintd to system-auth...",
+                "Enable Fingerprint Authentication",
+            )
+        } else {
+            (
+                "disable",
+                "Removing pam_fprintd from system-auth...",
+                "Disable Fingerprint Authentication",
+            )
+        };
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", PAM_FPRINTD_SCRIPT, "sh", action])
+                    .description(description)
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, title);
+
+        let button = button.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+            if task_runner::is_running() {
+                return glib::ControlFlow::Continue;
+            }
+            update_pam_button(&button);
+            glib::ControlFlow::Break
+        });
+    });
+}
+
 fn setup_howdy(page_builder: &Builder, window: &ApplicationWindow) {
     let btn_howdy_setup = extract_widget::<gtk4::Button>(page_builder, "btn_howdy_setup");
     let btn_howdy_uninstall = extract_widget::<gtk4::Button>(page_builder, "btn_howdy_uninstall");
@@ -194,11 +448,9 @@ fn setup_howdy(page_builder: &Builder, window: &ApplicationWindow) {
     // Update on window focus (e.g. after installation completes)
     let btn_setup_clone = btn_howdy_setup.clone();
     let btn_uninstall_clone = btn_howdy_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xero-howdy-qt").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    on_refocus(window, move || {
+        let is_installed = std::path::Path::new("/usr/bin/xero-howdy-qt").exists();
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     // Setup/Launch button handler
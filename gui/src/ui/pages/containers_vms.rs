@@ -5,50 +5,31 @@
 //! with any Arch-based distribution.
 //!
 //! Handles install + uninstall for:
-//! - Docker
+//! - Docker (rootful or rootless)
 //! - Podman (with optional Podman Desktop flatpak)
-//! - VirtualBox (kernel-aware host modules / dkms)
+//! - VirtualBox (kernel-aware host modules / dkms, optional Extension Pack)
 //! - DistroBox (with BoxBuddy flatpak)
 //! - KVM / QEMU / virt-manager (with conflict resolution & nested virt)
 //! - iOS iPA Sideloader (Plume Impactor flatpak)
+//! - Waydroid (Android container, binder-aware)
+//! - Incus (system containers)
 
 use crate::core;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::{
+    extract_widget, on_refocus, refresh_versioned_button_states, run_command, InstallCheck,
+};
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
-use log::info;
+use log::{info, warn};
 
 // ─── Shared helpers ─────────────────────────────────────────────────────────
 
-/// Update install / uninstall button pair based on installation status.
-///
-/// Installed  → install button greyed with "✓", uninstall visible.
-/// Not installed → install button active, uninstall hidden.
-fn update_button_state(
-    install_button: &Button,
-    uninstall_button: &Button,
-    is_installed: bool,
-    default_label: &str,
-) {
-    if is_installed {
-        install_button.set_label(&format!("{} ✓", default_label));
-        install_button.set_sensitive(false);
-        install_button.remove_css_class("suggested-action");
-        install_button.add_css_class("dim-label");
-        uninstall_button.set_visible(true);
-    } else {
-        install_button.set_label(default_label);
-        install_button.set_sensitive(true);
-        install_button.add_css_class("suggested-action");
-        install_button.remove_css_class("dim-label");
-        uninstall_button.set_visible(false);
-    }
-}
-
 /// Build a `-Rns` argument list that only includes packages actually installed.
 /// Prevents pacman from erroring on packages that were already removed or
 /// never installed in the first place.
@@ -60,44 +41,78 @@ fn removable_packages(candidates: &[&str]) -> Vec<String> {
         .collect()
 }
 
-/// Check all install states off the main thread, then update all button pairs
-/// at once. Called on initial page load and on window refocus — never blocks
-/// the GTK main loop.
+/// Check all install states (and versions, for the install button tooltips)
+/// off the main thread, then update all button pairs. Called on initial page
+/// load and on window refocus — never blocks the GTK main loop.
 fn async_refresh_states(
-    docker: (Button, Button),
-    podman: (Button, Button),
-    vbox: (Button, Button),
-    distrobox: (Button, Button),
-    kvm: (Button, Button),
-    ipa: (Button, Button),
+    docker: &(Button, Button),
+    podman: &(Button, Button),
+    vbox: &(Button, Button),
+    distrobox: &(Button, Button),
+    kvm: &(Button, Button),
+    ipa: &(Button, Button),
+    waydroid: &(Button, Button),
+    incus: &(Button, Button),
 ) {
-    let (tx, rx) = async_channel::bounded::<(bool, bool, bool, bool, bool, bool)>(1);
-
-    std::thread::spawn(move || {
-        let _ = tx.send_blocking((
-            core::is_package_installed("docker"),
-            core::is_package_installed("podman"),
-            core::is_package_installed("virtualbox"),
-            core::is_package_installed("distrobox"),
-            core::is_package_installed("virt-manager"),
-            core::is_flatpak_installed("dev.khcrysalis.PlumeImpactor"),
-        ));
-    });
-
-    gtk4::glib::MainContext::default().spawn_local(async move {
-        if let Ok((d, p, v, db, k, ipa_ok)) = rx.recv().await {
-            update_button_state(&docker.0, &docker.1, d, "Docker");
-            update_button_state(&podman.0, &podman.1, p, "Podman");
-            update_button_state(&vbox.0, &vbox.1, v, "Virtual Box");
-            update_button_state(&distrobox.0, &distrobox.1, db, "DistroBox");
-            update_button_state(&kvm.0, &kvm.1, k, "Qemu Virtual Manager");
-            update_button_state(&ipa.0, &ipa.1, ipa_ok, "iOS iPA Sideloader");
-        }
-    });
+    let pair = |btns: &(Button, Button), check, label| (btns.0.clone(), btns.1.clone(), check, label);
+    refresh_versioned_button_states(vec![
+        pair(docker, InstallCheck::PackageAndService("docker", "docker.service"), "Docker"),
+        pair(podman, InstallCheck::Package("podman"), "Podman"),
+        pair(vbox, InstallCheck::Package("virtualbox"), "Virtual Box"),
+        pair(distrobox, InstallCheck::Package("distrobox"), "DistroBox"),
+        pair(kvm, InstallCheck::Package("virt-manager"), "Qemu Virtual Manager"),
+        pair(ipa, InstallCheck::Flatpak("dev.khcrysalis.PlumeImpactor"), "iOS iPA Sideloader"),
+        pair(waydroid, InstallCheck::Package("waydroid"), "Waydroid"),
+        pair(incus, InstallCheck::Package("incus"), "Incus"),
+    ]);
 }
 
 // ─── Page entry point ───────────────────────────────────────────────────────
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Docker",
+        keywords: "containers compose",
+        button: "btn_docker",
+    },
+    Tool {
+        name: "Podman",
+        keywords: "containers podman desktop",
+        button: "btn_podman",
+    },
+    Tool {
+        name: "VirtualBox",
+        keywords: "virtual machine vm",
+        button: "btn_vbox",
+    },
+    Tool {
+        name: "DistroBox",
+        keywords: "containers boxbuddy",
+        button: "btn_distrobox",
+    },
+    Tool {
+        name: "QEMU / Virt-Manager",
+        keywords: "kvm libvirt swtpm virtual machine vm",
+        button: "btn_kvm",
+    },
+    Tool {
+        name: "iOS IPA Sideloader",
+        keywords: "plume impactor iphone",
+        button: "btn_ipa_sideloader",
+    },
+    Tool {
+        name: "Waydroid",
+        keywords: "android apps container",
+        button: "btn_waydroid",
+    },
+    Tool {
+        name: "Incus",
+        keywords: "lxd lxc system containers",
+        button: "btn_incus",
+    },
+];
+
 /// Set up all button handlers for the containers/VMs page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     let docker_btns = setup_docker(page_builder, window);
@@ -106,29 +121,33 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
     let distrobox_btns = setup_distrobox(page_builder, window);
     let kvm_btns = setup_kvm(page_builder, window);
     let ipa_btns = setup_ipa_sideloader(page_builder, window);
+    let waydroid_btns = setup_waydroid(page_builder, window);
+    let incus_btns = setup_incus(page_builder, window);
 
     // Single async pass to set initial button states — no main-thread blocking.
     async_refresh_states(
-        docker_btns.clone(),
-        podman_btns.clone(),
-        vbox_btns.clone(),
-        distrobox_btns.clone(),
-        kvm_btns.clone(),
-        ipa_btns.clone(),
+        &docker_btns,
+        &podman_btns,
+        &vbox_btns,
+        &distrobox_btns,
+        &kvm_btns,
+        &ipa_btns,
+        &waydroid_btns,
+        &incus_btns,
     );
 
     // Refresh states whenever the user returns focus to the window.
-    window.connect_is_active_notify(move |w| {
-        if w.is_active() {
-            async_refresh_states(
-                docker_btns.clone(),
-                podman_btns.clone(),
-                vbox_btns.clone(),
-                distrobox_btns.clone(),
-                kvm_btns.clone(),
-                ipa_btns.clone(),
-            );
-        }
+    on_refocus(window, move || {
+        async_refresh_states(
+            &docker_btns,
+            &podman_btns,
+            &vbox_btns,
+            &distrobox_btns,
+            &kvm_btns,
+            &ipa_btns,
+            &waydroid_btns,
+            &incus_btns,
+        );
     });
 }
 
@@ -136,8 +155,101 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
 //  Docker
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Core packages for a working Docker setup.
-const DOCKER_PACKAGES: &[&str] = &["docker", "docker-compose", "docker-buildx"];
+/// Core packages for a working Docker setup, plus the rootless extras.
+const DOCKER_PACKAGES: &[&str] = &[
+    "docker",
+    "docker-compose",
+    "docker-buildx",
+    "docker-rootless-extras",
+];
+
+/// System-wide daemon; the user gets access through the docker group.
+fn docker_rootful_plan(user: &str) -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S", "--noconfirm", "--needed",
+                    "docker", "docker-compose", "docker-buildx",
+                ])
+                .description("Installing Docker engine and tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "docker.service"])
+                .description("Enabling Docker service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("groupadd")
+                .args(&["-f", "docker"])
+                .description("Ensuring docker group exists...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "docker", user])
+                .description("Adding your user to docker group...")
+                .build(),
+        )
+        .build()
+}
+
+/// Per-user daemon set up by `dockerd-rootless-setuptool.sh`. Needs
+/// subordinate UID/GID ranges for the user and no docker group at all.
+fn docker_rootless_plan(user: &str) -> CommandSequence {
+    let subid_cmd = format!(
+        "grep -q '^{0}:' /etc/subuid || usermod --add-subuids 100000-165535 {0}; \
+         grep -q '^{0}:' /etc/subgid || usermod --add-subgids 100000-165535 {0}",
+        user
+    );
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S", "--noconfirm", "--needed",
+                    "docker", "docker-compose", "docker-buildx",
+                    "docker-rootless-extras", "fuse-overlayfs", "slirp4netns",
+                ])
+                .description("Installing Docker engine and rootless tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &subid_cmd])
+                .description("Allocating subordinate UIDs/GIDs for your user...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("dockerd-rootless-setuptool.sh")
+                .args(&["install"])
+                .description("Setting up rootless Docker daemon...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("systemctl")
+                .args(&["--user", "enable", "--now", "docker.service"])
+                .description("Enabling rootless Docker user service...")
+                .build(),
+        )
+        .build()
+}
 
 fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
     let btn_install = extract_widget::<Button>(builder, "btn_docker");
@@ -148,46 +260,51 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
     btn_install.connect_clicked(move |_| {
         info!("Docker install button clicked");
 
-        let user = crate::config::env::get().user.clone();
-
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .aur()
-                    .args(&[
-                        "-S", "--noconfirm", "--needed",
-                        "docker", "docker-compose", "docker-buildx",
-                    ])
-                    .description("Installing Docker engine and tools...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["enable", "--now", "docker.service"])
-                    .description("Enabling Docker service...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("groupadd")
-                    .args(&["-f", "docker"])
-                    .description("Ensuring docker group exists...")
-                    .build(),
+        let config = SelectionDialogConfig::new(
+            "Docker Installation",
+            "Choose how the Docker daemon should run.",
+        )
+        .selection_type(SelectionType::Single)
+        .add_option(
+            SelectionOption::new(
+                "rootful",
+                "Rootful (default)",
+                "System daemon; your user joins the docker group, which is root-equivalent",
+                false,
             )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("usermod")
-                    .args(&["-aG", "docker", &user])
-                    .description("Adding your user to docker group...")
-                    .build(),
+            .details(
+                "Members of the docker group can start a container that mounts / \
+                 and edit any file on the system, so anything running as your user \
+                 effectively has root without a password prompt. Takes effect after \
+                 you log out and back in.",
+            ),
+        )
+        .add_option(
+            SelectionOption::new(
+                "rootless",
+                "Rootless",
+                "Per-user daemon running without root privileges; no docker group",
+                false,
             )
-            .build();
+            .details(
+                "Containers run inside a user namespace and can't touch more than \
+                 your user can. Binding ports below 1024 and some networking and \
+                 storage drivers are unavailable, and the daemon only runs while \
+                 you're logged in.",
+            ),
+        )
+        .confirm_label("Install");
 
-        task_runner::run(window_clone.upcast_ref(), commands, "Docker Setup");
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let user = crate::config::env::get().user.clone();
+            let commands = if selected.iter().any(|s| s == "rootless") {
+                docker_rootless_plan(&user)
+            } else {
+                docker_rootful_plan(&user)
+            };
+            task_runner::run(window_for_closure.upcast_ref(), commands, "Docker Setup");
+        });
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -199,12 +316,22 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
         let pkgs = removable_packages(DOCKER_PACKAGES);
 
         let mut commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("systemctl")
+                    .args(&["--user", "disable", "--now", "docker.service"])
+                    .description("Stopping rootless Docker user service...")
+                    .allow_failure(true)
+                    .build(),
+            )
             .then(
                 Command::builder()
                     .privileged()
                     .program("systemctl")
                     .args(&["stop", "docker.service", "docker.socket"])
                     .description("Stopping Docker services...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
@@ -213,6 +340,7 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                     .program("systemctl")
                     .args(&["disable", "docker.service", "docker.socket"])
                     .description("Disabling Docker services...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
@@ -221,6 +349,7 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                     .program("gpasswd")
                     .args(&["-d", &user, "docker"])
                     .description("Removing your user from docker group...")
+                    .allow_failure(true)
                     .build(),
             );
 
@@ -269,7 +398,7 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
             "podman_desktop",
             "Podman Desktop",
             "Graphical interface for managing containers",
-            core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK),
+            core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK, core::FlatpakKind::App),
         ))
         .confirm_label("Install");
 
@@ -295,9 +424,8 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
             if selected.iter().any(|s| s == "podman_desktop") {
                 commands = commands.then(
                     Command::builder()
-                        .normal()
-                        .program("flatpak")
-                        .args(&["install", "-y", "flathub", PODMAN_DESKTOP_FLATPAK])
+                        .flatpak_install()
+                        .args(&[PODMAN_DESKTOP_FLATPAK])
                         .description("Installing Podman Desktop GUI...")
                         .build(),
                 );
@@ -325,6 +453,7 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                     .program("systemctl")
                     .args(&["stop", "podman.socket"])
                     .description("Stopping Podman socket...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
@@ -333,15 +462,15 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                     .program("systemctl")
                     .args(&["disable", "podman.socket"])
                     .description("Disabling Podman socket...")
+                    .allow_failure(true)
                     .build(),
             );
 
-        if core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK) {
+        if core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK, core::FlatpakKind::App) {
             commands = commands.then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["uninstall", "-y", PODMAN_DESKTOP_FLATPAK])
+                    .flatpak_uninstall()
+                    .args(&[PODMAN_DESKTOP_FLATPAK])
                     .description("Removing Podman Desktop GUI...")
                     .build(),
             );
@@ -383,6 +512,18 @@ const VBOX_HOST_VARIANTS: &[&str] = &[
     "virtualbox-host-dkms",
 ];
 
+/// AUR package shipping Oracle's Extension Pack (USB 2.0/3.0, RDP, disk
+/// encryption) with its PUEL license pre-accepted.
+const VBOX_EXTPACK_PACKAGE: &str = "virtualbox-ext-oracle";
+
+/// Whether the Extension Pack is present, installed by package or by hand
+/// through `VBoxManage extpack install`.
+fn is_vbox_extpack_installed() -> bool {
+    core::is_package_installed(VBOX_EXTPACK_PACKAGE)
+        || run_command("VBoxManage", &["list", "extpacks"])
+            .is_some_and(|out| out.contains("Oracle VirtualBox Extension Pack"))
+}
+
 /// Detect which host modules packages are needed for VirtualBox based on
 /// the running kernel (`uname -r`):
 ///
@@ -411,14 +552,12 @@ fn detect_vbox_host_packages() -> Vec<String> {
         // Custom kernel (zen, cachyos, hardened, etc.) — needs dkms + headers.
         let mut pkgs = vec!["virtualbox-host-dkms".to_string()];
 
-        if let Some(suffix) = uname.rsplit('-').next() {
-            if !suffix.is_empty() && suffix.chars().all(|c| c.is_alphanumeric()) {
-                let headers = format!("linux-{}-headers", suffix);
-                if core::is_package_in_repos(&headers)
-                    || core::is_package_installed(&format!("linux-{}", suffix))
-                {
-                    pkgs.push(headers);
-                }
+        if let Some(suffix) = core::package::kernel_suffix(&uname) {
+            let headers = core::package::kernel_headers_package(suffix);
+            if core::is_package_in_repos(&headers)
+                || core::is_package_installed(&format!("linux-{}", suffix))
+            {
+                pkgs.push(headers);
             }
         }
 
@@ -449,18 +588,51 @@ fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
         ];
         let host_refs: Vec<&str> = host_pkgs.iter().map(|s| s.as_str()).collect();
         install_args.extend_from_slice(&host_refs);
+        let install_args: Vec<String> = install_args.into_iter().map(str::to_owned).collect();
 
-        let commands = CommandSequence::new()
-            .then(
+        let config = SelectionDialogConfig::new(
+            "VirtualBox Installation",
+            "VirtualBox will be installed. Optionally include Oracle's Extension Pack.",
+        )
+        .selection_type(SelectionType::Single)
+        .selection_required(false)
+        .add_option(SelectionOption::new(
+            "extpack",
+            "Extension Pack",
+            "USB 2.0/3.0 passthrough, VirtualBox RDP and disk encryption. \
+             Proprietary (Oracle PUEL): free for personal and educational use only, \
+             installing it accepts that license.",
+            is_vbox_extpack_installed(),
+        ))
+        .confirm_label("Install");
+
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let refs: Vec<&str> = install_args.iter().map(String::as_str).collect();
+            let mut commands = CommandSequence::new().then(
                 Command::builder()
                     .aur()
-                    .args(&install_args)
+                    .args(&refs)
                     .description("Installing VirtualBox...")
                     .build(),
-            )
-            .build();
+            );
+
+            if selected.iter().any(|s| s == "extpack") && !is_vbox_extpack_installed() {
+                commands = commands.then(
+                    Command::builder()
+                        .aur()
+                        .args(&["-S", "--noconfirm", "--needed", VBOX_EXTPACK_PACKAGE])
+                        .description("Installing VirtualBox Extension Pack...")
+                        .build(),
+                );
+            }
 
-        task_runner::run(window_clone.upcast_ref(), commands, "VirtualBox Setup");
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                commands.build(),
+                "VirtualBox Setup",
+            );
+        });
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -471,7 +643,8 @@ fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
     btn_uninstall.connect_clicked(move |_| {
         info!("VirtualBox uninstall button clicked");
 
-        let mut candidates: Vec<&str> = vec!["virtualbox", "virtualbox-guest-iso"];
+        let mut candidates: Vec<&str> =
+            vec![VBOX_EXTPACK_PACKAGE, "virtualbox", "virtualbox-guest-iso"];
         candidates.extend_from_slice(VBOX_HOST_VARIANTS);
 
         let pkgs = removable_packages(&candidates);
@@ -528,9 +701,8 @@ fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> (Button, Bu
             )
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["install", "-y", BOXBUDDY_FLATPAK])
+                    .flatpak_install()
+                    .args(&[BOXBUDDY_FLATPAK])
                     .description("Installing BoxBuddy GUI...")
                     .build(),
             )
@@ -546,12 +718,11 @@ fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> (Button, Bu
 
         let mut commands = CommandSequence::new();
 
-        if core::is_flatpak_installed(BOXBUDDY_FLATPAK) {
+        if core::is_flatpak_installed(BOXBUDDY_FLATPAK, core::FlatpakKind::App) {
             commands = commands.then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["uninstall", "-y", BOXBUDDY_FLATPAK])
+                    .flatpak_uninstall()
+                    .args(&[BOXBUDDY_FLATPAK])
                     .description("Removing BoxBuddy GUI...")
                     .build(),
             );
@@ -607,6 +778,26 @@ const KVM_PACKAGES: &[&str] = &[
     "swtpm",
 ];
 
+/// Define (from the template libvirt ships), start and autostart the
+/// `default` NAT network so new VMs get networking out of the box. Each step
+/// is skipped when already done, so re-running setup is harmless.
+const LIBVIRT_DEFAULT_NET_SCRIPT: &str = r#"
+set -e
+virsh="virsh --connect qemu:///system"
+$virsh net-info default >/dev/null 2>&1 \
+    || $virsh net-define /usr/share/libvirt/networks/default.xml
+$virsh net-info default | grep -Eq '^Active:[[:space:]]+yes' \
+    || $virsh net-start default
+$virsh net-autostart default
+"#;
+
+/// Modprobe drop-in holding our nested-virtualisation option. A file of our
+/// own, so an existing `kvm-intel.conf`/`kvm-amd.conf` the user maintains is
+/// never overwritten, and uninstall removes only what we wrote.
+fn kvm_dropin_path(kvm_module: &str) -> String {
+    format!("/etc/modprobe.d/{}-cyberxero.conf", kvm_module)
+}
+
 /// Detect CPU vendor and return the correct modprobe option for nested
 /// virtualisation. Intel → `kvm-intel`, AMD → `kvm-amd`.
 fn detect_kvm_nested_conf() -> (&'static str, &'static str) {
@@ -631,30 +822,41 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
 
         let user = crate::config::env::get().user.clone();
         let (kvm_module, kvm_option) = detect_kvm_nested_conf();
-        let conf_path = format!("/etc/modprobe.d/{}.conf", kvm_module);
+        let conf_path = kvm_dropin_path(kvm_module);
         let write_cmd = format!("echo '{}' > {}", kvm_option, conf_path);
 
         let mut commands = CommandSequence::new();
 
-        // Resolve iptables / netcat conflicts safely.
-        // iptables (legacy) conflicts with iptables-nft; gnu-netcat conflicts
-        // with openbsd-netcat. Only act when the conflicting variant is present,
-        // exit 0 regardless so the sequence continues.
-        commands = commands.then(
-            Command::builder()
-                .privileged()
-                .program("sh")
-                .args(&[
-                    "-c",
-                    "pacman -Qi iptables &>/dev/null && \
-                     ! pacman -Qi iptables-nft &>/dev/null && \
-                     pacman -Rdd --noconfirm iptables || true; \
-                     pacman -Qi gnu-netcat &>/dev/null && \
-                     pacman -Rdd --noconfirm gnu-netcat || true",
-                ])
-                .description("Resolving package conflicts if needed...")
-                .build(),
-        );
+        // iptables (legacy) conflicts with iptables-nft and gnu-netcat with
+        // openbsd-netcat; --noconfirm would decline the swap, so drop the
+        // old variant first. -dd because libvirt's deps come right back.
+        commands = commands
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Rdd", "--noconfirm", "iptables"])
+                    .description("Removing legacy iptables...")
+                    .run_if(|| {
+                        core::is_package_installed("iptables")
+                            && !core::is_package_installed("iptables-nft")
+                    })
+                    .run_if_shell(
+                        "pacman -Qq iptables >/dev/null 2>&1 \
+                         && ! pacman -Qq iptables-nft >/dev/null 2>&1",
+                    )
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Rdd", "--noconfirm", "gnu-netcat"])
+                    .description("Removing gnu-netcat...")
+                    .run_if(|| core::is_package_installed("gnu-netcat"))
+                    .run_if_shell("pacman -Qq gnu-netcat >/dev/null 2>&1")
+                    .build(),
+            );
 
         // Install all packages explicitly (no meta-package).
         commands = commands.then(
@@ -709,6 +911,14 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
                     .args(&["restart", "libvirtd.service"])
                     .description("Restarting libvirtd service...")
                     .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", LIBVIRT_DEFAULT_NET_SCRIPT])
+                    .description("Activating the default virtual network...")
+                    .build(),
             );
 
         task_runner::run(window_clone.upcast_ref(), commands.build(), "KVM / QEMU Setup");
@@ -721,6 +931,8 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
 
         let user = crate::config::env::get().user.clone();
         let pkgs = removable_packages(KVM_PACKAGES);
+        let intel_dropin = kvm_dropin_path("kvm-intel");
+        let amd_dropin = kvm_dropin_path("kvm-amd");
 
         let mut commands = CommandSequence::new()
             .then(
@@ -729,6 +941,7 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
                     .program("systemctl")
                     .args(&["stop", "libvirtd.service", "libvirtd.socket", "libvirtd-ro.socket"])
                     .description("Stopping libvirtd services...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
@@ -737,6 +950,7 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
                     .program("systemctl")
                     .args(&["disable", "libvirtd.service", "libvirtd.socket", "libvirtd-ro.socket"])
                     .description("Disabling libvirtd services...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
@@ -745,17 +959,14 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
                     .program("gpasswd")
                     .args(&["-d", &user, "libvirt"])
                     .description("Removing your user from libvirt group...")
+                    .allow_failure(true)
                     .build(),
             )
             .then(
                 Command::builder()
                     .privileged()
                     .program("rm")
-                    .args(&[
-                        "-f",
-                        "/etc/modprobe.d/kvm-intel.conf",
-                        "/etc/modprobe.d/kvm-amd.conf",
-                    ])
+                    .args(&["-f", &intel_dropin, &amd_dropin])
                     .description("Removing nested virtualization config...")
                     .build(),
             );
@@ -801,9 +1012,8 @@ fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Butto
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["install", "-y", "flathub", PLUME_FLATPAK])
+                    .flatpak_install()
+                    .args(&[PLUME_FLATPAK])
                     .description("Installing Plume Impactor from Flathub...")
                     .build(),
             )
@@ -820,9 +1030,8 @@ fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Butto
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["uninstall", "-y", PLUME_FLATPAK])
+                    .flatpak_uninstall()
+                    .args(&[PLUME_FLATPAK])
                     .description("Removing Plume Impactor...")
                     .build(),
             )
@@ -837,3 +1046,265 @@ fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Butto
 
     (btn_install, btn_uninstall)
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Waydroid
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const WAYDROID_WARNING: &str = "The running kernel has no <b>binder</b> support, which Waydroid \
+needs to start its Android container.\n\n\
+Kernels such as linux-zen ship it built in; on others install \
+<b>binder_linux-dkms</b> from the AUR first.\n\n\
+Waydroid will be installed anyway, but it won't run until binder is available.";
+
+/// Whether the running kernel provides the binder IPC Waydroid needs:
+/// binderfs built in (zen and most gaming kernels) or a loadable
+/// `binder_linux` module (e.g. from `binder_linux-dkms`).
+fn has_binder_support() -> bool {
+    let filesystems = std::fs::read_to_string("/proc/filesystems").unwrap_or_default();
+    if filesystems.contains("binder") {
+        return true;
+    }
+
+    std::process::Command::new("modinfo")
+        .args(["-n", "binder_linux"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Install Waydroid (official repos first, AUR otherwise), download its
+/// Android image and start the container service.
+fn waydroid_install_plan() -> CommandSequence {
+    let install_cmd = if core::is_package_in_repos("waydroid") {
+        info!("waydroid found in official repos – installing via pacman");
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-S", "--noconfirm", "--needed", "waydroid"])
+            .description("Installing Waydroid from official repos...")
+            .build()
+    } else {
+        info!("waydroid not in official repos – installing via AUR");
+        Command::builder()
+            .aur()
+            .args(&["-S", "--noconfirm", "--needed", "waydroid"])
+            .description("Installing Waydroid from AUR...")
+            .build()
+    };
+
+    CommandSequence::new()
+        .then(install_cmd)
+        .then(
+            Command::builder()
+                .privileged()
+                .program("waydroid")
+                .args(&["init"])
+                .description("Downloading and initializing the Android image...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "waydroid-container.service"])
+                .description("Enabling Waydroid container service...")
+                .build(),
+        )
+        .build()
+}
+
+fn setup_waydroid(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, "btn_waydroid");
+    let btn_uninstall = extract_widget::<Button>(builder, "btn_waydroid_uninstall");
+
+    // ── Install ──────────────────────────────────────────────────────────
+    //
+    // Without binder the container can't start, so warn before installing
+    // rather than leave the user with a service that fails silently.
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Waydroid install button clicked");
+
+        if has_binder_support() {
+            task_runner::run(
+                window_clone.upcast_ref(),
+                waydroid_install_plan(),
+                "Waydroid Setup",
+            );
+            return;
+        }
+
+        warn!("No binder support in the running kernel");
+        let window_inner = window_clone.clone();
+        show_warning_confirmation(
+            window_clone.upcast_ref(),
+            "Binder Support Missing",
+            WAYDROID_WARNING,
+            move || {
+                task_runner::run(
+                    window_inner.upcast_ref(),
+                    waydroid_install_plan(),
+                    "Waydroid Setup",
+                );
+            },
+        );
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Waydroid uninstall button clicked");
+
+        let mut commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["stop", "waydroid-container.service"])
+                    .description("Stopping Waydroid container service...")
+                    .allow_failure(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["disable", "waydroid-container.service"])
+                    .description("Disabling Waydroid container service...")
+                    .allow_failure(true)
+                    .build(),
+            );
+
+        if !removable_packages(&["waydroid"]).is_empty() {
+            commands = commands.then(
+                Command::builder()
+                    .aur()
+                    .args(&["-Rns", "--noconfirm", "waydroid"])
+                    .description("Removing Waydroid and dependencies...")
+                    .build(),
+            );
+        }
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands.build(),
+            "Waydroid Uninstall",
+        );
+    });
+
+    (btn_install, btn_uninstall)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Incus
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_incus(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, "btn_incus");
+    let btn_uninstall = extract_widget::<Button>(builder, "btn_incus_uninstall");
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Incus install button clicked");
+
+        let user = crate::config::env::get().user.clone();
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "incus"])
+                    .description("Installing Incus...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["enable", "--now", "incus.service"])
+                    .description("Enabling Incus service...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("usermod")
+                    .args(&["-aG", "incus-admin", &user])
+                    .description("Adding your user to incus-admin group...")
+                    .build(),
+            )
+            .then(
+                // `init` refuses a server that already has storage, e.g. a
+                // reinstall over existing data, so only run it on a blank one.
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        "incus storage list --format csv | grep -q . || incus admin init --minimal",
+                    ])
+                    .description("Creating default storage pool and network...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "Incus Setup");
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Incus uninstall button clicked");
+
+        let user = crate::config::env::get().user.clone();
+
+        let mut commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["stop", "incus.service", "incus.socket"])
+                    .description("Stopping Incus services...")
+                    .allow_failure(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["disable", "incus.service", "incus.socket"])
+                    .description("Disabling Incus services...")
+                    .allow_failure(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("gpasswd")
+                    .args(&["-d", &user, "incus-admin"])
+                    .description("Removing your user from incus-admin group...")
+                    .allow_failure(true)
+                    .build(),
+            );
+
+        if !removable_packages(&["incus"]).is_empty() {
+            commands = commands.then(
+                Command::builder()
+                    .aur()
+                    .args(&["-Rns", "--noconfirm", "incus"])
+                    .description("Removing Incus and dependencies...")
+                    .build(),
+            );
+        }
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands.build(),
+            "Incus Uninstall",
+        );
+    });
+
+    (btn_install, btn_uninstall)
+}
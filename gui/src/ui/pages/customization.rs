@@ -8,19 +8,95 @@
 //! - Nova-Choom Theme installation
 //! - ZSH All-in-One setup
 //! - Save Desktop tool
-//! - GRUB theme installation
+//! - GRUB theme selection (GRUB only; disabled on systemd-boot)
 //! - Plymouth Manager
+//! - Icon/cursor/GTK theme bundles (per-user, via gsettings)
 //! - Update Layan Theme
 //! - Decky Loader management (install/update/uninstall/wipe)
 //! - Config/Rice reset
 
+use crate::core;
+use crate::core::system_check::{detect_bootloader, Bootloader, GRUB_CFG};
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
 use crate::ui::dialogs::terminal;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::{extract_widget, run_command};
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "CyberXero Theme",
+        keywords: "rice kde",
+        button: "btn_cyberxero_theme",
+    },
+    Tool {
+        name: "PS4 Theme",
+        keywords: "rice kde",
+        button: "btn_ps4_theme",
+    },
+    Tool {
+        name: "Lunar Glass Theme",
+        keywords: "rice kde",
+        button: "btn_lunar_glass",
+    },
+    Tool {
+        name: "Carnage Theme",
+        keywords: "rice kde",
+        button: "btn_carnage_theme",
+    },
+    Tool {
+        name: "Nova-Choom Theme",
+        keywords: "rice kde",
+        button: "btn_nova_choom_theme",
+    },
+    Tool {
+        name: "ZSH AiO",
+        keywords: "shell oh my zsh",
+        button: "btn_zsh_aio",
+    },
+    Tool {
+        name: "Save Desktop",
+        keywords: "backup flatpak",
+        button: "btn_save_desktop",
+    },
+    Tool {
+        name: "GRUB Theme",
+        keywords: "bootloader breeze vimix starfield",
+        button: "btn_grub_theme",
+    },
+    Tool {
+        name: "Plymouth Manager",
+        keywords: "boot splash",
+        button: "btn_plymouth_manager",
+    },
+    Tool {
+        name: "Theme Bundles",
+        keywords: "icons cursor gtk papirus bibata adw-gtk3 gsettings",
+        button: "btn_theme_bundles",
+    },
+    Tool {
+        name: "Layan Theme",
+        keywords: "kde",
+        button: "btn_layan_patch",
+    },
+    Tool {
+        name: "Decky Loader",
+        keywords: "steam plugins",
+        button: "btn_decky_loader",
+    },
+    Tool {
+        name: "Config/Rice Reset",
+        keywords: "restore defaults",
+        button: "btn_config_reset",
+    },
+];
+
 /// Set up all button handlers for the customization page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_cyberxero_theme(page_builder, window);
@@ -32,6 +108,7 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
     setup_save_desktop(page_builder, window);
     setup_grub_theme(page_builder, window);
     setup_plymouth_manager(page_builder, window);
+    setup_theme_bundles(page_builder, window);
     setup_layan_patch(page_builder, window);
     setup_decky_loader(page_builder, window);
     setup_config_reset(page_builder, window);
@@ -322,9 +399,8 @@ fn setup_save_desktop(builder: &Builder, window: &ApplicationWindow) {
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["install", "-y", "io.github.vikdevelop.SaveDesktop"])
+                    .flatpak_install()
+                    .args(&["io.github.vikdevelop.SaveDesktop"])
                     .description("Installing Save Desktop tool from Flathub...")
                     .build(),
             )
@@ -338,31 +414,212 @@ fn setup_save_desktop(builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
+/// Curated GRUB themes: (id, label, description, package, installed theme dir).
+const GRUB_THEMES: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "breeze",
+        "Breeze",
+        "KDE Plasma's boot menu, matches the Breeze desktop",
+        "breeze-grub",
+        "/usr/share/grub/themes/breeze",
+    ),
+    (
+        "vimix",
+        "Vimix",
+        "Clean dark theme with distro icons (AUR)",
+        "grub-theme-vimix",
+        "/usr/share/grub/themes/Vimix",
+    ),
+    (
+        "starfield",
+        "Starfield",
+        "GRUB's own sample theme, always available",
+        "grub",
+        "/usr/share/grub/themes/starfield",
+    ),
+];
+
+/// Copies `$1` to `/boot/grub/themes/$2` and points `GRUB_THEME` at it.
+/// `/etc/default/grub` is backed up once, before the first edit, and the
+/// edit replaces any existing (or commented) `GRUB_THEME=` line, so running
+/// it again only swaps the path.
+const GRUB_THEME_APPLY_SCRIPT: &str = r#"set -e
+conf=/etc/default/grub
+[ -f "$conf.cyberxero.bak" ] || cp "$conf" "$conf.cyberxero.bak"
+install -d /boot/grub/themes
+rm -rf "/boot/grub/themes/$2"
+cp -r "$1" "/boot/grub/themes/$2"
+line="GRUB_THEME=\"/boot/grub/themes/$2/theme.txt\""
+if grep -qE '^#?GRUB_THEME=' "$conf"; then
+    sed -i -E "s|^#?GRUB_THEME=.*|$line|" "$conf"
+else
+    printf '%s\n' "$line" >> "$conf"
+fi"#;
+
+/// Comments `GRUB_THEME=` back out, returning to GRUB's text menu. The
+/// copied theme stays under `/boot/grub/themes` and is harmless there.
+const GRUB_THEME_RESET_SCRIPT: &str = r#"set -e
+conf=/etc/default/grub
+[ -f "$conf.cyberxero.bak" ] || cp "$conf" "$conf.cyberxero.bak"
+sed -i -E 's|^GRUB_THEME=|#GRUB_THEME=|' "$conf""#;
+
+/// Puts back the `GRUB_THEME` line saved in the backup, e.g. the distro's
+/// own theme, in place of the current one. If the backup had none there was
+/// nothing to restore, so the current line is just commented out.
+const GRUB_THEME_RESTORE_SCRIPT: &str = r#"set -e
+conf=/etc/default/grub
+orig=$(grep -E '^GRUB_THEME=' "$conf.cyberxero.bak" | tail -n 1 || true)
+if [ -z "$orig" ]; then
+    sed -i -E 's|^GRUB_THEME=|#GRUB_THEME=|' "$conf"
+    exit 0
+fi
+orig="$orig" awk '
+    /^#?GRUB_THEME=/ && !done { print ENVIRON["orig"]; done = 1; next }
+    { print }
+    END { if (!done) print ENVIRON["orig"] }
+' "$conf" > "$conf.cyberxero.tmp"
+cat "$conf.cyberxero.tmp" > "$conf"
+rm -f "$conf.cyberxero.tmp""#;
+
+/// Written by the apply and reset scripts before their first edit.
+const GRUB_BACKUP: &str = "/etc/default/grub.cyberxero.bak";
+
 fn setup_grub_theme(builder: &Builder, window: &ApplicationWindow) {
     let button = extract_widget::<Button>(builder, "btn_grub_theme");
-    let window = window.clone();
 
+    match detect_bootloader() {
+        Some(Bootloader::Grub) => {}
+        Some(Bootloader::SystemdBoot) => {
+            button.set_sensitive(false);
+            button.set_tooltip_text(Some(
+                "This system boots with systemd-boot, which has no theme support",
+            ));
+            return;
+        }
+        None => {
+            button.set_sensitive(false);
+            button.set_tooltip_text(Some("No GRUB installation found"));
+            return;
+        }
+    }
+
+    let window = window.clone();
     button.connect_clicked(move |_| {
         info!("GRUB Theme button clicked");
 
-        let home = crate::config::env::get().home.clone();
-        let repo_path = format!("{}/xero-grubs", home);
-
-        // Run everything in terminal - clone if needed, then run interactive installation script
-        let install_command = format!(
-            "if [ ! -d \"{}\" ]; then git clone --depth 1 https://github.com/xerolinux/xero-grubs \"{}\"; fi && pkexec sh -c 'cd \"{}\" && ./install.sh'",
-            repo_path, repo_path, repo_path
-        );
+        let mut config = SelectionDialogConfig::new(
+            "GRUB Theme",
+            "The theme is copied to /boot/grub/themes and set in /etc/default/grub \
+             (a backup is kept at /etc/default/grub.cyberxero.bak), then the boot menu \
+             is regenerated.",
+        )
+        .selection_type(SelectionType::Single)
+        .confirm_label("Apply");
+        for (id, label, description, _, _) in GRUB_THEMES {
+            config = config.add_option(SelectionOption::new(id, label, description, false));
+        }
+        config = config.add_option(SelectionOption::new(
+            "xero",
+            "XeroLinux Themes",
+            "Interactive installer with the XeroLinux collection",
+            false,
+        ));
+        if std::path::Path::new(GRUB_BACKUP).exists() {
+            config = config.add_option(SelectionOption::new(
+                "restore",
+                "Original Theme",
+                "Put back the theme setting from before the toolkit changed it",
+                false,
+            ));
+        }
+        config = config.add_option(SelectionOption::new(
+            "none",
+            "No Theme",
+            "Go back to GRUB's plain text menu",
+            false,
+        ));
 
-        terminal::show_terminal_dialog(
-            window.upcast_ref(),
-            "XeroLinux GRUB Theme Installation",
-            "sh",
-            &["-c", &install_command],
-        );
+        let window_inner = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let Some(choice) = selected.first() else {
+                return;
+            };
+            if choice == "xero" {
+                run_xero_grub_installer(&window_inner);
+                return;
+            }
+            let edit = match GRUB_THEMES.iter().find(|(id, ..)| *id == choice.as_str()) {
+                Some(&(_, label, _, package, source)) => CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .aur()
+                            .args(&["-S", "--noconfirm", "--needed", package])
+                            .description(&format!("Installing the {label} theme..."))
+                            .build(),
+                    )
+                    .then(
+                        Command::builder()
+                            .privileged()
+                            .program("sh")
+                            .args(&["-c", GRUB_THEME_APPLY_SCRIPT, "sh", source, theme_dir_name(source)])
+                            .description("Setting GRUB_THEME...")
+                            .build(),
+                    ),
+                None if choice == "restore" => CommandSequence::new().then(
+                    Command::builder()
+                        .privileged()
+                        .program("sh")
+                        .args(&["-c", GRUB_THEME_RESTORE_SCRIPT])
+                        .description("Restoring the original GRUB_THEME...")
+                        .build(),
+                ),
+                None => CommandSequence::new().then(
+                    Command::builder()
+                        .privileged()
+                        .program("sh")
+                        .args(&["-c", GRUB_THEME_RESET_SCRIPT])
+                        .description("Removing GRUB_THEME...")
+                        .build(),
+                ),
+            };
+            let commands = edit
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("grub-mkconfig")
+                        .args(&["-o", GRUB_CFG])
+                        .description("Regenerating the GRUB menu...")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window_inner.upcast_ref(), commands, "GRUB Theme");
+        });
     });
 }
 
+/// Theme directory name, taken from its installed path.
+fn theme_dir_name(source: &str) -> &str {
+    source.rsplit('/').next().unwrap_or(source)
+}
+
+fn run_xero_grub_installer(window: &ApplicationWindow) {
+    let home = crate::config::env::get().home.clone();
+    let repo_path = format!("{}/xero-grubs", home);
+
+    // Run everything in terminal - clone if needed, then run interactive installation script
+    let install_command = format!(
+        "if [ ! -d \"{}\" ]; then git clone --depth 1 https://github.com/xerolinux/xero-grubs \"{}\"; fi && pkexec sh -c 'cd \"{}\" && ./install.sh'",
+        repo_path, repo_path, repo_path
+    );
+
+    terminal::show_terminal_dialog(
+        window.upcast_ref(),
+        "XeroLinux GRUB Theme Installation",
+        "sh",
+        &["-c", &install_command],
+    );
+}
+
 fn setup_plymouth_manager(builder: &Builder, window: &ApplicationWindow) {
     let button = extract_widget::<Button>(builder, "btn_plymouth_manager");
     let window = window.clone();
@@ -379,6 +636,164 @@ fn setup_plymouth_manager(builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
+/// An icon, cursor or GTK theme the bundle installer can set up.
+struct ThemeBundle {
+    id: &'static str,
+    label: &'static str,
+    description: &'static str,
+    package: &'static str,
+    /// Key under `org.gnome.desktop.interface` that selects it.
+    key: &'static str,
+    value: &'static str,
+}
+
+const INTERFACE_SCHEMA: &str = "org.gnome.desktop.interface";
+
+const THEME_BUNDLES: &[ThemeBundle] = &[
+    ThemeBundle {
+        id: "papirus",
+        label: "Papirus Icons",
+        description: "Flat, colourful icon set with wide app coverage",
+        package: "papirus-icon-theme",
+        key: "icon-theme",
+        value: "Papirus-Dark",
+    },
+    ThemeBundle {
+        id: "bibata",
+        label: "Bibata Cursors",
+        description: "Smooth, rounded cursor theme",
+        package: "bibata-cursor-theme-bin",
+        key: "cursor-theme",
+        value: "Bibata-Modern-Classic",
+    },
+    ThemeBundle {
+        id: "adw-gtk3",
+        label: "adw-gtk3",
+        description: "Libadwaita look for GTK3 apps, dark variant",
+        package: "adw-gtk-theme",
+        key: "gtk-theme",
+        value: "adw-gtk3-dark",
+    },
+];
+
+/// Current value of an interface key, without gsettings' quotes.
+fn interface_setting(key: &str) -> Option<String> {
+    run_command("gsettings", &["get", INTERFACE_SCHEMA, key])
+        .map(|v| v.trim_matches('\'').to_string())
+}
+
+fn setup_theme_bundles(builder: &Builder, window: &ApplicationWindow) {
+    let button = extract_widget::<Button>(builder, "btn_theme_bundles");
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Theme Bundles button clicked");
+
+        let mut config = SelectionDialogConfig::new(
+            "Theme Bundles",
+            "Selected themes are installed and applied for your user through gsettings, \
+             which GTK apps and GNOME-based desktops follow.",
+        )
+        .selection_type(SelectionType::Multi)
+        .confirm_label("Apply");
+        for bundle in THEME_BUNDLES {
+            let installed = core::is_package_installed(bundle.package);
+            let applied = installed
+                && interface_setting(bundle.key).as_deref() == Some(bundle.value);
+            let description = if installed && !applied {
+                format!("{} — installed, not applied", bundle.description)
+            } else {
+                bundle.description.to_string()
+            };
+            config = config.add_option(
+                SelectionOption::new(bundle.id, bundle.label, &description, applied)
+                    .group("Themes"),
+            );
+        }
+        config = config.add_option(
+            SelectionOption::new(
+                "revert",
+                "Revert to Default",
+                "Reset the icon, cursor and GTK theme settings first",
+                false,
+            )
+            .group("Reset"),
+        );
+
+        let window_inner = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let commands = theme_bundle_plan(&selected);
+            if !commands.is_empty() {
+                task_runner::run(window_inner.upcast_ref(), commands.build(), "Theme Bundles");
+            }
+        });
+    });
+}
+
+/// Reset (if asked), install the picked themes repo-first with the AUR as
+/// fallback, then point the interface keys at them. Everything after the
+/// install step is per-user, so it runs unprivileged.
+fn theme_bundle_plan(selected: &[String]) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    if selected.iter().any(|s| s == "revert") {
+        for bundle in THEME_BUNDLES {
+            commands = commands.then(
+                Command::builder()
+                    .normal()
+                    .program("gsettings")
+                    .args(&["reset", INTERFACE_SCHEMA, bundle.key])
+                    .description(&format!("Resetting {}...", bundle.key))
+                    .build(),
+            );
+        }
+    }
+
+    let picked: Vec<&ThemeBundle> = THEME_BUNDLES
+        .iter()
+        .filter(|b| selected.iter().any(|s| s == b.id))
+        .collect();
+    let (repo, aur): (Vec<&str>, Vec<&str>) = picked
+        .iter()
+        .map(|b| b.package)
+        .filter(|p| !core::is_package_installed(p))
+        .partition(|p| core::is_package_in_repos(p));
+    if !repo.is_empty() {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(&repo);
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing themes from official repos...")
+                .build(),
+        );
+    }
+    if !aur.is_empty() {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(&aur);
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&args)
+                .description("Installing themes from AUR...")
+                .build(),
+        );
+    }
+
+    for bundle in picked {
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("gsettings")
+                .args(&["set", INTERFACE_SCHEMA, bundle.key, bundle.value])
+                .description(&format!("Applying {}...", bundle.label))
+                .build(),
+        );
+    }
+    commands
+}
+
 fn setup_layan_patch(builder: &Builder, window: &ApplicationWindow) {
     let button = extract_widget::<Button>(builder, "btn_layan_patch");
     let window = window.clone();
@@ -589,6 +1004,7 @@ fn setup_decky_loader(builder: &Builder, window: &ApplicationWindow) {
                                             )])
                                             .description("Disabling CEF remote debugging...")
                                             .build())
+                                        .confirm(false)
                                         .build();
 
                                     task_runner::run(
@@ -652,6 +1068,7 @@ fn setup_decky_loader(builder: &Builder, window: &ApplicationWindow) {
                                             )])
                                             .description("Disabling CEF remote debugging...")
                                             .build())
+                                        .confirm(false)
                                         .build();
 
                                     task_runner::run(
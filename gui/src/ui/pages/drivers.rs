@@ -1,22 +1,79 @@
 //! Drivers & hardware tools page.
 //!
-//! Five of the eight installers are a straight "click → run this AUR
+//! Five of the ten installers are a straight "click → run this AUR
 //! sequence" — those are driven off a single [`SimpleSpec`] table to
-//! eliminate copy-pasted boilerplate. The remaining three (OpenRazer,
-//! NVIDIA CUDA, NVIDIA Legacy) open a dialog first, so they're wired up
-//! explicitly.
+//! eliminate copy-pasted boilerplate. The remaining five (OpenRazer,
+//! Printers, NVIDIA Drivers, NVIDIA CUDA, NVIDIA Legacy) open a dialog
+//! first, so they're wired up explicitly. The NVIDIA buttons are only enabled when
+//! `lspci` reports an NVIDIA GPU.
 
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
 use crate::core;
+use crate::core::gpu::GpuVendor;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
 use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::{extract_widget, is_service_enabled, on_refocus, run_command};
+
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "NVIDIA Drivers",
+        keywords: "gpu nvidia-open dkms proprietary geforce rtx",
+        button: "btn_nvidia_drivers",
+    },
+    Tool {
+        name: "NVIDIA Legacy Drivers",
+        keywords: "gpu 470xx 390xx",
+        button: "btn_nvidia_legacy",
+    },
+    Tool {
+        name: "AMD ROCm",
+        keywords: "gpu compute hip opencl",
+        button: "btn_rocm",
+    },
+    Tool {
+        name: "NVIDIA CUDA",
+        keywords: "gpu compute",
+        button: "btn_cuda",
+    },
+    Tool {
+        name: "Tailscale VPN",
+        keywords: "network wireguard",
+        button: "btn_tailscale",
+    },
+    Tool {
+        name: "ASUS ROG Tools",
+        keywords: "asusctl supergfxctl laptop",
+        button: "btn_asus_rog",
+    },
+    Tool {
+        name: "OpenRazer Drivers",
+        keywords: "razer peripherals polychromatic",
+        button: "btn_openrazer",
+    },
+    Tool {
+        name: "Zenergy Driver",
+        keywords: "amd power sensors",
+        button: "btn_zenergy",
+    },
+    Tool {
+        name: "Printer Support",
+        keywords: "cups printing hplip brother epson canon scanner",
+        button: "btn_printer",
+    },
+    Tool {
+        name: "Cooler Control",
+        keywords: "fans cooling",
+        button: "btn_cooler_control",
+    },
+];
 
 pub fn setup_handlers(
     page_builder: &Builder,
@@ -25,8 +82,11 @@ pub fn setup_handlers(
 ) {
     wire_simple_handlers(page_builder, window);
     wire_openrazer(page_builder, window);
+    wire_printer(page_builder, window);
+    wire_nvidia_drivers(page_builder, window);
     wire_nvidia_legacy(page_builder, window);
     wire_cuda(page_builder, window);
+    gate_nvidia_buttons(page_builder);
 }
 
 // ---------------------------------------------------------------------------
@@ -215,6 +275,271 @@ fn openrazer_plan(extras: &[String]) -> CommandSequence {
     seq.build()
 }
 
+// ---------------------------------------------------------------------------
+// Printers — CUPS, generic drivers, optional vendor drivers
+// ---------------------------------------------------------------------------
+
+const PRINTER_LABEL: &str = "Printer Support";
+
+const PRINTER_PACKAGES: &[&str] = &[
+    "cups",
+    "cups-pdf",
+    "system-config-printer",
+    "gutenprint",
+    "foomatic-db",
+    "foomatic-db-engine",
+];
+
+/// Optional vendor drivers: (id, label, description, packages).
+const PRINTER_VENDOR_DRIVERS: &[(&str, &str, &str, &[&str])] = &[
+    ("hplip", "HP", "HPLIP drivers and tools for HP printers and scanners", &["hplip"]),
+    ("brlaser", "Brother", "Open driver for Brother monochrome laser printers", &["brlaser"]),
+    (
+        "epson",
+        "Epson",
+        "ESC/P-R drivers for Epson inkjets",
+        &["epson-inkjet-printer-escpr", "epson-inkjet-printer-escpr2"],
+    ),
+    ("canon", "Canon", "Canon PIXMA / MAXIFY inkjet driver (AUR)", &["cnijfilter2"]),
+];
+
+fn is_printing_set_up() -> bool {
+    core::is_package_installed("cups") && is_service_enabled("cups.service")
+}
+
+/// Mark the button once CUPS is installed and enabled. It stays clickable
+/// so vendor drivers can be added later.
+fn update_printer_button(button: &Button) {
+    if is_printing_set_up() {
+        button.set_label(&format!("{PRINTER_LABEL} ✓"));
+        button.set_tooltip_text(Some("CUPS is set up — click to add vendor drivers"));
+    } else {
+        button.set_label(PRINTER_LABEL);
+        button.set_tooltip_text(None);
+    }
+}
+
+fn wire_printer(builder: &Builder, window: &ApplicationWindow) {
+    let btn = extract_widget::<Button>(builder, "btn_printer");
+    update_printer_button(&btn);
+
+    let btn_for_focus = btn.clone();
+    on_refocus(window, move || {
+        update_printer_button(&btn_for_focus);
+    });
+
+    let window = window.clone();
+    btn.connect_clicked(move |_| {
+        info!("Printer button clicked");
+        let window_inner = window.clone();
+        let mut config = SelectionDialogConfig::new(
+            "Printer Support",
+            "CUPS, a PDF printer and the Gutenprint and Foomatic drivers will be installed, \
+             and the print service enabled. Optionally add drivers for your printer's brand.",
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .confirm_label("Install");
+        for (id, label, description, packages) in PRINTER_VENDOR_DRIVERS {
+            config = config.add_option(SelectionOption::new(
+                id,
+                label,
+                description,
+                packages.iter().all(|p| core::is_package_installed(p)),
+            ));
+        }
+
+        show_selection_dialog(window.upcast_ref(), config, move |picked| {
+            task_runner::run(
+                window_inner.upcast_ref(),
+                printer_plan(&picked),
+                "Install Printer Support",
+            );
+        });
+    });
+}
+
+fn printer_plan(vendors: &[String]) -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+    let mut seq = CommandSequence::new()
+        .then(aur_install(
+            PRINTER_PACKAGES,
+            "Installing CUPS and printer drivers...",
+        ))
+        .then(priv_cmd(
+            "systemctl",
+            &["enable", "--now", "cups.service"],
+            "Enabling the CUPS print service...",
+        ))
+        // CUPS lets members of `sys` add and manage printers.
+        .then(priv_cmd(
+            "usermod",
+            &["-aG", "sys", &user],
+            "Adding user to sys group for printer administration...",
+        ));
+    for (id, label, _, packages) in PRINTER_VENDOR_DRIVERS {
+        if vendors.iter().any(|v| v == id) {
+            seq = seq.then(aur_install(
+                packages,
+                &format!("Installing {label} printer drivers..."),
+            ));
+        }
+    }
+    seq.build()
+}
+
+// ---------------------------------------------------------------------------
+// NVIDIA Drivers — pick open or proprietary modules for the running kernel
+// ---------------------------------------------------------------------------
+
+const NVIDIA_BUTTONS: &[&str] = &["btn_nvidia_drivers", "btn_nvidia_legacy", "btn_cuda"];
+
+/// Loads the DRM module with kernel modesetting, the same effect as the
+/// `nvidia-drm.modeset=1` boot parameter but independent of the bootloader.
+/// mkinitcpio's `modconf` hook copies it into the initramfs.
+const NVIDIA_MODESET_DROPIN: &str = "/etc/modprobe.d/nvidia-drm-modeset.conf";
+
+fn has_nvidia_gpu() -> bool {
+    core::gpu::detect_gpu_vendors().contains(&GpuVendor::Nvidia)
+}
+
+fn gate_nvidia_buttons(builder: &Builder) {
+    if has_nvidia_gpu() {
+        return;
+    }
+    info!("No NVIDIA GPU found; disabling NVIDIA installers");
+    for id in NVIDIA_BUTTONS {
+        let btn = extract_widget::<Button>(builder, id);
+        btn.set_sensitive(false);
+        btn.set_tooltip_text(Some("No NVIDIA GPU detected"));
+    }
+}
+
+/// Kernel module package for the running kernel: the prebuilt one for
+/// `linux` and `linux-lts`, otherwise the DKMS build plus the kernel's
+/// headers (same split as the VirtualBox host modules).
+fn nvidia_module_packages(open: bool) -> Vec<String> {
+    let base = if open { "nvidia-open" } else { "nvidia" };
+    let uname = run_command("uname", &["-r"]).unwrap_or_default();
+
+    if uname.contains("-arch") {
+        vec![base.to_string()]
+    } else if uname.contains("-lts") {
+        vec![format!("{base}-lts")]
+    } else {
+        let mut pkgs = vec![format!("{base}-dkms")];
+        if let Some(suffix) = core::package::kernel_suffix(&uname) {
+            let headers = core::package::kernel_headers_package(suffix);
+            if core::is_package_in_repos(&headers)
+                || core::is_package_installed(&format!("linux-{}", suffix))
+            {
+                pkgs.push(headers);
+            }
+        }
+        pkgs
+    }
+}
+
+fn wire_nvidia_drivers(builder: &Builder, window: &ApplicationWindow) {
+    let btn = extract_widget::<Button>(builder, "btn_nvidia_drivers");
+    let window = window.clone();
+    btn.connect_clicked(move |_| {
+        info!("NVIDIA Drivers button clicked");
+        let window_inner = window.clone();
+        let open = nvidia_module_packages(true);
+        let proprietary = nvidia_module_packages(false);
+
+        let mut closed_option = SelectionOption::new(
+            "proprietary",
+            "Proprietary Modules",
+            &format!(
+                "Closed kernel modules for Maxwell through Ada GPUs ({})",
+                proprietary.join(", ")
+            ),
+            core::is_package_installed(&proprietary[0]),
+        );
+        if !core::is_package_in_repos(&proprietary[0]) {
+            closed_option = closed_option
+                .unavailable("No longer in the repos — use Nvidia Legacy Drivers for GTX 900/1000");
+        }
+        let lib32 = core::is_multilib_enabled();
+
+        let config = SelectionDialogConfig::new(
+            "NVIDIA Drivers",
+            &format!(
+                "Pick the kernel modules for your GPU. nvidia-utils{} is installed alongside, \
+                 kernel modesetting is turned on and the initramfs is rebuilt. Reboot afterwards.",
+                if lib32 { " and lib32-nvidia-utils" } else { "" }
+            ),
+        )
+        .selection_type(SelectionType::Single)
+        .add_option(
+            SelectionOption::new(
+                "open",
+                "Open Modules (Recommended)",
+                &format!(
+                    "NVIDIA's open kernel modules for Turing (RTX 20/GTX 16) and newer ({})",
+                    open.join(", ")
+                ),
+                core::is_package_installed(&open[0]),
+            )
+            .preselected(true),
+        )
+        .add_option(closed_option)
+        .confirm_label("Install");
+
+        show_selection_dialog(window.upcast_ref(), config, move |picked| {
+            let Some(choice) = picked.first() else { return };
+            let modules = if choice == "open" { &open } else { &proprietary };
+            task_runner::run(
+                window_inner.upcast_ref(),
+                nvidia_drivers_plan(modules, lib32),
+                "Install NVIDIA Drivers (Reboot Required)",
+            );
+        });
+    });
+}
+
+fn nvidia_drivers_plan(modules: &[String], lib32: bool) -> CommandSequence {
+    let mut packages: Vec<&str> = modules.iter().map(String::as_str).collect();
+    packages.push("nvidia-utils");
+    if lib32 {
+        packages.push("lib32-nvidia-utils");
+    }
+    let mkinitcpio = crate::config::paths::scripts()
+        .join("nvidia_mkinitcpio.sh")
+        .to_string_lossy()
+        .into_owned();
+    let dropin = format!(
+        "printf 'options nvidia_drm modeset=1\\n' > {}",
+        NVIDIA_MODESET_DROPIN
+    );
+
+    CommandSequence::new()
+        .then(aur_install(&packages, "Installing NVIDIA drivers..."))
+        .then(priv_cmd(
+            "sh",
+            &["-c", &dropin],
+            "Enabling kernel modesetting (nvidia-drm.modeset=1)...",
+        ))
+        .then(priv_cmd(
+            "bash",
+            &[&mkinitcpio],
+            "Configuring mkinitcpio modules...",
+        ))
+        .then(priv_cmd(
+            "systemctl",
+            NVIDIA_LEGACY_SERVICES,
+            "Enabling Nvidia power management services...",
+        ))
+        .then(priv_cmd(
+            "mkinitcpio",
+            &["-P"],
+            "Rebuilding initramfs...",
+        ))
+        .build()
+}
+
 // ---------------------------------------------------------------------------
 // NVIDIA Legacy (GTX 900/1000 series, 580xx branch)
 // ---------------------------------------------------------------------------
@@ -352,6 +677,7 @@ fn aur_install(packages: &[&str], description: &str) -> Command {
         .aur()
         .args(&args)
         .description(description)
+        .capture_output(true)
         .build()
 }
 
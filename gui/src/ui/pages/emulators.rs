@@ -8,6 +8,7 @@ use crate::core;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
 use crate::ui::utils::extract_widget;
 use gtk4::prelude::*;
@@ -16,6 +17,85 @@ use log::info;
 
 // ── Public setup ─────────────────────────────────────────────────────────────
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "RetroArch",
+        keywords: "libretro cores",
+        button: "btn_emu_retroarch",
+    },
+    Tool {
+        name: "DuckStation",
+        keywords: "ps1 playstation",
+        button: "btn_emu_ps1",
+    },
+    Tool {
+        name: "PCSX2",
+        keywords: "ps2 playstation",
+        button: "btn_emu_ps2",
+    },
+    Tool {
+        name: "RPCS3",
+        keywords: "ps3 playstation",
+        button: "btn_emu_ps3",
+    },
+    Tool {
+        name: "ShadPS4",
+        keywords: "ps4 playstation",
+        button: "btn_emu_ps4",
+    },
+    Tool {
+        name: "PPSSPP",
+        keywords: "psp playstation",
+        button: "btn_emu_psp",
+    },
+    Tool {
+        name: "Vita3K",
+        keywords: "ps vita playstation",
+        button: "btn_emu_vita",
+    },
+    Tool {
+        name: "mGBA",
+        keywords: "gba game boy advance",
+        button: "btn_emu_gba",
+    },
+    Tool {
+        name: "melonDS",
+        keywords: "ds nintendo",
+        button: "btn_emu_nds",
+    },
+    Tool {
+        name: "Dolphin",
+        keywords: "gamecube wii nintendo",
+        button: "btn_emu_dolphin",
+    },
+    Tool {
+        name: "Cemu",
+        keywords: "wii u nintendo",
+        button: "btn_emu_wiiu",
+    },
+    Tool {
+        name: "Ryujinx",
+        keywords: "switch nintendo",
+        button: "btn_emu_switch",
+    },
+    Tool {
+        name: "xemu",
+        keywords: "xbox",
+        button: "btn_emu_xbox",
+    },
+    Tool {
+        name: "Flycast",
+        keywords: "dreamcast sega",
+        button: "btn_emu_dreamcast",
+    },
+    Tool {
+        name: "MAME",
+        keywords: "arcade",
+        button: "btn_emu_mame",
+    },
+];
+
 /// Set up all button handlers for the emulators page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_retroarch(page_builder, window);
@@ -4,29 +4,58 @@
 //! or combo row paired with the gamescope flag it emits. The form listens
 //! for changes on every bound widget, re-renders the command, and copies
 //! it to the clipboard on demand.
+//!
+//! Below the form, a session generator writes a wrapper script and an app
+//! menu entry that start Steam Big Picture inside gamescope, and optionally
+//! a Wayland session so it can be picked at the login screen. Its settings
+//! are kept in `~/.config/cyberxero-toolkit/gamescope-session.toml` so the
+//! dialog can reopen them for editing.
 
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use adw::prelude::*;
 use adw::{ComboRow, EntryRow};
 use gtk4::{ApplicationWindow, Builder, Button, StringObject, Switch};
-use log::info;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 
-use crate::ui::utils::extract_widget;
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{show_selection_dialog, SelectionDialogConfig, SelectionOption};
+use crate::ui::navigation::Tool;
+use crate::ui::task_runner::{self, shell_quote, Command, CommandSequence};
+use crate::ui::utils::{desktop_exec_quote, extract_widget};
 
 /// Fixed prefix and suffix wrapping the generated command.
 const CMD_HEAD: &str = "gamescope";
 const CMD_TAIL: &[&str] = &["--", "%command%"];
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Gamescope Command Generator",
+        keywords: "launch options steam",
+        button: "btn_copy_command",
+    },
+    Tool {
+        name: "Gamescope Session",
+        keywords: "big picture steam deck couch session login hdr fsr",
+        button: "btn_gamescope_session",
+    },
+];
+
 pub fn setup_handlers(
     page_builder: &Builder,
     _main_builder: &Builder,
-    _window: &ApplicationWindow,
+    window: &ApplicationWindow,
 ) {
     let form = Rc::new(GamescopeForm::load(page_builder));
     form.wire_change_watchers();
     form.bind_copy_button(page_builder);
     form.refresh();
+    setup_session(page_builder, window);
 }
 
 struct GamescopeForm {
@@ -227,3 +256,323 @@ fn combo_value(combo: &ComboRow) -> Option<String> {
         .selected_item()
         .and_then(|item| item.downcast_ref::<StringObject>().map(|s| s.string().to_string()))
 }
+
+// ---------------------------------------------------------------------------
+// Gamescope session generator
+// ---------------------------------------------------------------------------
+
+const SESSION_NAME: &str = "cyberxero-gamescope-session";
+
+/// Display-manager session entry; needs root, so it's staged and installed.
+const WAYLAND_SESSION_PATH: &str = "/usr/share/wayland-sessions/cyberxero-gamescope.desktop";
+
+const RESOLUTIONS: &[(&str, Option<(u32, u32)>)] = &[
+    ("Native", None),
+    ("1280 × 800", Some((1280, 800))),
+    ("1920 × 1080", Some((1920, 1080))),
+    ("2560 × 1440", Some((2560, 1440))),
+    ("3840 × 2160", Some((3840, 2160))),
+];
+
+const REFRESH_RATES: &[(&str, Option<u32>)] = &[
+    ("Default", None),
+    ("60 Hz", Some(60)),
+    ("120 Hz", Some(120)),
+    ("144 Hz", Some(144)),
+    ("165 Hz", Some(165)),
+    ("240 Hz", Some(240)),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Upscaler {
+    #[default]
+    None,
+    Fsr,
+    Integer,
+}
+
+const UPSCALERS: &[(&str, Upscaler)] = &[
+    ("None", Upscaler::None),
+    ("AMD FSR", Upscaler::Fsr),
+    ("Integer", Upscaler::Integer),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionConfig {
+    resolution: Option<(u32, u32)>,
+    refresh: Option<u32>,
+    hdr: bool,
+    upscaler: Upscaler,
+}
+
+impl SessionConfig {
+    fn path() -> PathBuf {
+        config_home()
+            .join(crate::config::app_info::NAME)
+            .join("gamescope-session.toml")
+    }
+
+    fn load() -> Option<Self> {
+        let raw = fs::read_to_string(Self::path()).ok()?;
+        toml::from_str(&raw)
+            .map_err(|e| warn!("Ignoring malformed gamescope session config: {}", e))
+            .ok()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        write_atomic(&Self::path(), &toml::to_string(self)?, 0o644)?;
+        Ok(())
+    }
+
+    /// Arguments placed between `gamescope` and `--`.
+    fn flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if let Some((w, h)) = self.resolution {
+            flags.extend(["-W".into(), w.to_string(), "-H".into(), h.to_string()]);
+        }
+        if let Some(hz) = self.refresh {
+            flags.extend(["-r".into(), hz.to_string()]);
+        }
+        if self.hdr {
+            flags.push("--hdr-enabled".into());
+        }
+        match self.upscaler {
+            Upscaler::None => {}
+            Upscaler::Fsr => flags.extend(["-F".into(), "fsr".into()]),
+            Upscaler::Integer => flags.extend(["-S".into(), "integer".into()]),
+        }
+        // Steam integration: lets Big Picture drive focus and frame limits.
+        flags.push("-e".into());
+        flags
+    }
+
+    fn summary(&self) -> String {
+        let mut parts = vec![match self.resolution {
+            Some((w, h)) => format!("{w}×{h}"),
+            None => String::from("Native resolution"),
+        }];
+        if let Some(hz) = self.refresh {
+            parts.push(format!("{hz} Hz"));
+        }
+        if self.hdr {
+            parts.push(String::from("HDR"));
+        }
+        match self.upscaler {
+            Upscaler::None => {}
+            Upscaler::Fsr => parts.push(String::from("FSR")),
+            Upscaler::Integer => parts.push(String::from("Integer scaling")),
+        }
+        parts.join(", ")
+    }
+}
+
+fn config_home() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"))
+}
+
+fn wrapper_path() -> PathBuf {
+    dirs::executable_dir()
+        .unwrap_or_else(|| PathBuf::from(crate::config::env::get().home.clone()).join(".local/bin"))
+        .join(SESSION_NAME)
+}
+
+fn desktop_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
+        .join("applications")
+        .join(format!("{SESSION_NAME}.desktop"))
+}
+
+/// Write `contents` next to `path` and rename it into place, so a crash
+/// never leaves a half-written launcher behind.
+fn write_atomic(path: &Path, contents: &str, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let staging = path.with_extension("partial");
+    fs::write(&staging, contents)?;
+    fs::set_permissions(&staging, fs::Permissions::from_mode(mode))?;
+    fs::rename(&staging, path)
+}
+
+fn wrapper_script(config: &SessionConfig) -> String {
+    let flags = config
+        .flags()
+        .iter()
+        .map(|f| shell_quote(f))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "#!/bin/sh\n\
+         # Generated by CyberXero Toolkit; edit it from the Gamescope page.\n\
+         exec gamescope {flags} -- steam -gamepadui \"$@\"\n"
+    )
+}
+
+fn desktop_entry(name: &str, comment: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Name={name}\n\
+         Comment={comment}\n\
+         Exec={}\n\
+         Icon=steam\n\
+         Type=Application\n\
+         Categories=Game;\n",
+        desktop_exec_quote(&wrapper_path().to_string_lossy())
+    )
+}
+
+/// Write the user-owned files: settings, wrapper and app menu entry.
+fn write_session_files(config: &SessionConfig) -> anyhow::Result<()> {
+    config.save()?;
+    write_atomic(&wrapper_path(), &wrapper_script(config), 0o755)?;
+    write_atomic(
+        &desktop_path(),
+        &desktop_entry("Gamescope Session", "Steam Big Picture in gamescope"),
+        0o644,
+    )?;
+    Ok(())
+}
+
+fn setup_session(builder: &Builder, window: &ApplicationWindow) {
+    let row = extract_widget::<adw::ActionRow>(builder, "gamescope_session_row");
+    let button = extract_widget::<Button>(builder, "btn_gamescope_session");
+    update_session_row(&row, &button);
+
+    let window = window.clone();
+    button.connect_clicked(move |button| {
+        info!("Gamescope session button clicked");
+        show_session_dialog(&window, &row, button);
+    });
+}
+
+fn update_session_row(row: &adw::ActionRow, button: &Button) {
+    match SessionConfig::load().filter(|_| wrapper_path().exists()) {
+        Some(config) => {
+            row.set_subtitle(&config.summary());
+            button.set_label("Edit");
+        }
+        None => {
+            row.set_subtitle("Launch Steam Big Picture inside gamescope");
+            button.set_label("Create");
+        }
+    }
+}
+
+fn choice_row(title: &str, labels: &[&str], selected: usize) -> ComboRow {
+    let row = ComboRow::new();
+    row.set_title(title);
+    row.set_model(Some(&gtk4::StringList::new(labels)));
+    row.set_selected(selected as u32);
+    row
+}
+
+fn show_session_dialog(window: &ApplicationWindow, row: &adw::ActionRow, button: &Button) {
+    let current = SessionConfig::load().unwrap_or_default();
+    let index_of = |found: Option<usize>| found.unwrap_or(0);
+
+    let resolution = choice_row(
+        "Resolution",
+        &RESOLUTIONS.iter().map(|(l, _)| *l).collect::<Vec<_>>(),
+        index_of(RESOLUTIONS.iter().position(|(_, r)| *r == current.resolution)),
+    );
+    let refresh = choice_row(
+        "Refresh Rate",
+        &REFRESH_RATES.iter().map(|(l, _)| *l).collect::<Vec<_>>(),
+        index_of(REFRESH_RATES.iter().position(|(_, r)| *r == current.refresh)),
+    );
+    let upscaler = choice_row(
+        "Upscaling",
+        &UPSCALERS.iter().map(|(l, _)| *l).collect::<Vec<_>>(),
+        index_of(UPSCALERS.iter().position(|(_, u)| *u == current.upscaler)),
+    );
+    let hdr = adw::SwitchRow::new();
+    hdr.set_title("HDR");
+    hdr.set_subtitle("Needs an HDR display and a recent Mesa or NVIDIA driver");
+    hdr.set_active(current.hdr);
+
+    let form = adw::PreferencesGroup::new();
+    form.set_margin_start(12);
+    form.set_margin_end(12);
+    form.add(&resolution);
+    form.add(&refresh);
+    form.add(&upscaler);
+    form.add(&hdr);
+
+    let session_installed = Path::new(WAYLAND_SESSION_PATH).exists();
+    let config = SelectionDialogConfig::new(
+        "Gamescope Session",
+        "Creates a launcher that starts Steam Big Picture inside gamescope with these \
+         settings, and adds it to your app menu.",
+    )
+    .add_option(SelectionOption::new(
+        "login",
+        "Add to Login Screen",
+        "Offer it as a session in your display manager (needs admin rights)",
+        session_installed,
+    ))
+    .selection_required(false)
+    .allow_removal(true)
+    .extra_widget(&form)
+    .confirm_label("Save");
+
+    let window_inner = window.clone();
+    let row = row.clone();
+    let button = button.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let config = SessionConfig {
+            resolution: RESOLUTIONS[resolution.selected() as usize].1,
+            refresh: REFRESH_RATES[refresh.selected() as usize].1,
+            hdr: hdr.is_active(),
+            upscaler: UPSCALERS[upscaler.selected() as usize].1,
+        };
+        if let Err(e) = write_session_files(&config) {
+            show_error(&window_inner, &format!("Could not write the gamescope session: {e}"));
+            return;
+        }
+        info!("gamescope session written: {}", config.summary());
+        update_session_row(&row, &button);
+
+        let want_login = selected.iter().any(|s| s == "login");
+        if let Some(commands) = login_session_plan(want_login, session_installed) {
+            task_runner::run(window_inner.upcast_ref(), commands, "Gamescope Session");
+        }
+    });
+}
+
+/// Install or remove the display-manager entry when that choice changed.
+/// The file is staged from Rust and only copied into place as root.
+fn login_session_plan(want: bool, installed: bool) -> Option<CommandSequence> {
+    if want == installed {
+        return None;
+    }
+    let step = if want {
+        let staging = std::env::temp_dir().join(format!("{SESSION_NAME}.desktop"));
+        let entry = desktop_entry(
+            "Gamescope Session (CyberXero)",
+            "Steam Big Picture in gamescope",
+        );
+        if let Err(e) = write_atomic(&staging, &entry, 0o644) {
+            warn!("Could not stage the gamescope session entry: {}", e);
+            return None;
+        }
+        Command::builder()
+            .privileged()
+            .program("install")
+            .args(&["-Dm644", &staging.to_string_lossy(), WAYLAND_SESSION_PATH])
+            .description("Adding the session to the login screen...")
+            .build()
+    } else {
+        Command::builder()
+            .privileged()
+            .program("rm")
+            .args(&["-f", WAYLAND_SESSION_PATH])
+            .description("Removing the session from the login screen...")
+            .build()
+    };
+    Some(CommandSequence::new().then(step).build())
+}
@@ -2,17 +2,101 @@
 //!
 //! Handles:
 //! - Gaming suite installation (CachyOS meta or curated package list fallback)
+//! - Steam with multilib preflight and GPU-matched 32-bit drivers
 //! - LACT GPU overclocking
 //! - Game launchers (Bottles)
 //! - Controller tools
 //! - Falcond gaming utility
+//! - ProtonUp-Qt (GE-Proton manager)
+//! - MangoHud performance overlay
 
-use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::core;
+use crate::core::gpu::GpuVendor;
+use crate::ui::dialogs::selection::{show_selection_dialog, SelectionDialogConfig, SelectionOption};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::navigation::Tool;
+use crate::ui::task_runner::{self, shell_quote, Command, CommandSequence};
+use crate::ui::utils::{extract_widget, on_refocus, refresh_button_states, InstallCheck};
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Gaming Suite",
+        keywords: "steam lutris heroic wine proton mangohud",
+        button: "btn_gaming_meta",
+    },
+    Tool {
+        name: "Steam",
+        keywords: "valve games multilib lib32 proton",
+        button: "btn_steam",
+    },
+    Tool {
+        name: "LACT GPU Control",
+        keywords: "overclock amd",
+        button: "btn_lact_oc",
+    },
+    Tool {
+        name: "Bottles",
+        keywords: "wine flatpak",
+        button: "btn_bottles",
+    },
+    Tool {
+        name: "Controller Tools",
+        keywords: "gamepad xbox dualsense xone",
+        button: "btn_controller",
+    },
+    Tool {
+        name: "Falcond Game Mode",
+        keywords: "tuned performance",
+        button: "btn_falcond",
+    },
+    Tool {
+        name: "ProtonUp-Qt",
+        keywords: "ge-proton proton-ge compatibility tools flatpak",
+        button: "btn_protonup",
+    },
+    Tool {
+        name: "MangoHud",
+        keywords: "overlay fps goverlay performance hud",
+        button: "btn_mangohud",
+    },
+];
+
+const PROTONUP_FLATPAK: &str = "net.davidotek.pupgui2";
+
+/// Where native and Flatpak Steam look for extra compatibility tools.
+const STEAM_COMPAT_DIRS: &[&str] = &[
+    "xdg-data/Steam/compatibilitytools.d:create",
+    "~/.var/app/com.valvesoftware.Steam/data/Steam/compatibilitytools.d:create",
+];
+
+/// Check install states off the main thread, then update the button pairs.
+/// Called on initial page load and on window refocus.
+fn async_refresh_states(
+    steam: &(Button, Button),
+    protonup: &(Button, Button),
+    mangohud: &(Button, Button),
+) {
+    refresh_button_states(vec![
+        (steam.0.clone(), steam.1.clone(), InstallCheck::Package("steam"), "Steam"),
+        (
+            protonup.0.clone(),
+            protonup.1.clone(),
+            InstallCheck::Flatpak(PROTONUP_FLATPAK),
+            "ProtonUp-Qt",
+        ),
+        (
+            mangohud.0.clone(),
+            mangohud.1.clone(),
+            InstallCheck::Package("mangohud"),
+            "MangoHud",
+        ),
+    ]);
+}
+
 /// Set up all button handlers for the gaming tools page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_gaming_meta(page_builder, window);
@@ -20,6 +104,14 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
     setup_bottles(page_builder, window);
     setup_controller(page_builder, window);
     setup_falcond(page_builder, window);
+    let steam_btns = setup_steam(page_builder, window);
+    let protonup_btns = setup_protonup(page_builder, window);
+    let mangohud_btns = setup_mangohud(page_builder, window);
+
+    async_refresh_states(&steam_btns, &protonup_btns, &mangohud_btns);
+    on_refocus(window, move || {
+        async_refresh_states(&steam_btns, &protonup_btns, &mangohud_btns);
+    });
 }
 
 fn setup_gaming_meta(builder: &Builder, window: &ApplicationWindow) {
@@ -144,6 +236,102 @@ fn setup_gaming_meta(builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
+/// 64- and 32-bit graphics drivers for every GPU in the machine, so hybrid
+/// laptops get both stacks. Falls back to Mesa when nothing is recognised.
+fn steam_driver_packages() -> Vec<&'static str> {
+    let mut packages = Vec::new();
+    for vendor in core::gpu::detect_gpu_vendors() {
+        packages.extend(match vendor {
+            GpuVendor::Nvidia => ["nvidia-utils", "lib32-nvidia-utils"].as_slice(),
+            GpuVendor::Amd => &["mesa", "lib32-mesa", "vulkan-radeon", "lib32-vulkan-radeon"],
+            GpuVendor::Intel => &["mesa", "lib32-mesa", "vulkan-intel", "lib32-vulkan-intel"],
+        });
+    }
+    if packages.is_empty() {
+        packages.extend(["mesa", "lib32-mesa"]);
+    }
+    packages.sort_unstable();
+    packages.dedup();
+    packages
+}
+
+/// Optionally enable multilib, then install Steam and the 32-bit drivers.
+/// A freshly enabled multilib has no database yet, so that path installs
+/// with `-Syu` in the same transaction rather than a partial `-Sy` sync.
+fn steam_plan(enable_multilib: bool) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    if enable_multilib {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&["-c", core::package::ENABLE_MULTILIB_SCRIPT])
+                .description("Enabling [multilib] in pacman.conf...")
+                .build(),
+        );
+    }
+
+    let sync = if enable_multilib { "-Syu" } else { "-S" };
+    let mut args = vec![sync, "--noconfirm", "--needed", "steam"];
+    args.extend(steam_driver_packages());
+    commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing Steam and 32-bit graphics drivers...")
+                .build(),
+        )
+        .build()
+}
+
+fn setup_steam(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, "btn_steam");
+    let btn_uninstall = extract_widget::<Button>(builder, "btn_steam_uninstall");
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Steam install button clicked");
+
+        if core::is_multilib_enabled() {
+            task_runner::run(window_clone.upcast_ref(), steam_plan(false), "Steam Setup");
+            return;
+        }
+        let window = window_clone.clone();
+        show_warning_confirmation(
+            window_clone.upcast_ref(),
+            "Multilib Required",
+            "Steam and its 32-bit graphics drivers come from the <b>multilib</b> \
+             repository, which is disabled on this system.\n\n\
+             Enable multilib in /etc/pacman.conf and continue with the install?",
+            move || task_runner::run(window.upcast_ref(), steam_plan(true), "Steam Setup"),
+        );
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Steam uninstall button clicked");
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Rns", "--noconfirm", "steam"])
+                    .description("Removing Steam...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "Steam Uninstall");
+    });
+
+    (btn_install, btn_uninstall)
+}
+
 fn setup_lact_oc(builder: &Builder, window: &ApplicationWindow) {
     let button = extract_widget::<Button>(builder, "btn_lact_oc");
     let window = window.clone();
@@ -183,11 +371,8 @@ fn setup_bottles(builder: &Builder, window: &ApplicationWindow) {
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
+                    .flatpak_install()
                     .args(&[
-                        "install",
-                        "-y",
                         "com.usebottles.bottles",
                         "org.freedesktop.Platform.VulkanLayer.gamescope/x86_64/25.08",
                         "org.freedesktop.Platform.VulkanLayer.MangoHud/x86_64/25.08",
@@ -374,3 +559,204 @@ fn setup_falcond(builder: &Builder, window: &ApplicationWindow) {
         task_runner::run(window.upcast_ref(), commands.build(), "Falcond Installation");
     });
 }
+
+fn setup_protonup(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, "btn_protonup");
+    let btn_uninstall = extract_widget::<Button>(builder, "btn_protonup_uninstall");
+
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("ProtonUp-Qt install button clicked");
+
+        let mut override_args = vec!["override", "--user"];
+        let filesystems: Vec<String> = STEAM_COMPAT_DIRS
+            .iter()
+            .map(|dir| format!("--filesystem={}", dir))
+            .collect();
+        override_args.extend(filesystems.iter().map(String::as_str));
+        override_args.push(PROTONUP_FLATPAK);
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .flatpak_install()
+                    .args(&[PROTONUP_FLATPAK])
+                    .description("Installing ProtonUp-Qt from Flathub...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("flatpak")
+                    .args(&override_args)
+                    .description("Granting access to Steam's compatibility tools folders...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "ProtonUp-Qt Setup");
+    });
+
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("ProtonUp-Qt uninstall button clicked");
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .flatpak_uninstall()
+                    .args(&[PROTONUP_FLATPAK])
+                    .description("Removing ProtonUp-Qt...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("flatpak")
+                    .args(&["override", "--user", "--reset", PROTONUP_FLATPAK])
+                    .description("Clearing ProtonUp-Qt permission overrides...")
+                    .allow_failure(true)
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "ProtonUp-Qt Uninstall");
+    });
+
+    (btn_install, btn_uninstall)
+}
+
+/// Packages the MangoHud uninstall removes, if present.
+const MANGOHUD_PACKAGES: &[&str] = &["mangohud", "lib32-mangohud", "goverlay"];
+
+/// Starter config written only when the user has none.
+const MANGOHUD_DEFAULT_CONF: &[&str] = &[
+    "# Written by CyberXero Toolkit. Edit freely; it is never overwritten.",
+    "# Toggle the overlay in game with Right Shift + F12.",
+    "legacy_layout=false",
+    "fps",
+    "frametime",
+    "frame_timing",
+    "cpu_stats",
+    "cpu_temp",
+    "gpu_stats",
+    "gpu_temp",
+    "ram",
+    "vram",
+    "position=top-left",
+    "toggle_hud=Shift_R+F12",
+];
+
+fn setup_mangohud(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, "btn_mangohud");
+    let btn_uninstall = extract_widget::<Button>(builder, "btn_mangohud_uninstall");
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("MangoHud install button clicked");
+
+        let multilib = core::is_multilib_enabled();
+        let mut description = String::from(
+            "MangoHud itself is always installed. A default config is written to \
+             ~/.config/MangoHud/MangoHud.conf unless you already have one.",
+        );
+        let mut lib32 = SelectionOption::new(
+            "lib32-mangohud",
+            "32-bit support",
+            "Overlay for 32-bit games and Proton titles",
+            core::is_package_installed("lib32-mangohud"),
+        );
+        if multilib {
+            lib32 = lib32.preselected(true);
+        } else {
+            lib32 = lib32.unavailable("Needs the multilib repository");
+            description.push_str(
+                "\n\nThe multilib repository is disabled, so 32-bit support can't be \
+                 installed. Enable it under Servicing → Enable Multilib.",
+            );
+        }
+
+        let config = SelectionDialogConfig::new("MangoHud", &description)
+            .add_option(lib32)
+            .add_option(SelectionOption::new(
+                "goverlay",
+                "GOverlay",
+                "Graphical editor for the MangoHud config",
+                core::is_package_installed("goverlay"),
+            ))
+            .selection_required(false)
+            .confirm_label("Install");
+
+        let window = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let mut args = vec!["-S", "--noconfirm", "--needed", "mangohud"];
+            args.extend(selected.iter().map(String::as_str));
+
+            let script = format!(
+                "conf=\"${{XDG_CONFIG_HOME:-$HOME/.config}}/MangoHud/MangoHud.conf\"; \
+                 if [ -e \"$conf\" ]; then echo \"Keeping existing $conf\"; exit 0; fi; \
+                 mkdir -p \"$(dirname \"$conf\")\" && printf '%s\\n' {} > \"$conf\" && \
+                 echo \"Wrote $conf\"",
+                MANGOHUD_DEFAULT_CONF
+                    .iter()
+                    .map(|line| shell_quote(line))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("pacman")
+                        .args(&args)
+                        .description("Installing MangoHud...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", &script])
+                        .description("Writing default MangoHud config...")
+                        .build(),
+                )
+                .build();
+
+            task_runner::run(window.upcast_ref(), commands, "MangoHud Setup");
+        });
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("MangoHud uninstall button clicked");
+
+        let installed: Vec<&str> = MANGOHUD_PACKAGES
+            .iter()
+            .copied()
+            .filter(|pkg| core::is_package_installed(pkg))
+            .collect();
+        if installed.is_empty() {
+            return;
+        }
+        let mut args = vec!["-Rns", "--noconfirm"];
+        args.extend(installed);
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description("Removing MangoHud...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "MangoHud Uninstall");
+    });
+
+    (btn_install, btn_uninstall)
+}
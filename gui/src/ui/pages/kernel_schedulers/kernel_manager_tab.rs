@@ -20,6 +20,11 @@ use gtk4::{
 };
 use log::{info, warn};
 
+use crate::core;
+use crate::core::system_check::{detect_bootloader, Bootloader, GRUB_CFG};
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
 use crate::ui::dialogs::warning::show_warning_confirmation;
 use crate::ui::task_runner::{self, Command, CommandSequence};
 use crate::ui::utils::extract_widget;
@@ -34,6 +39,7 @@ pub fn setup_handlers(
 ) {
     let tab = KernelTab::new(builder.clone(), window.clone());
     tab.bind_refresh_button();
+    tab.bind_install_kernel_button();
     tab.rescan(None);
 }
 
@@ -53,6 +59,93 @@ impl KernelTab {
         btn.connect_clicked(move |button| me.rescan(Some(button.clone())));
     }
 
+    fn bind_install_kernel_button(self: &Rc<Self>) {
+        let me = self.clone();
+        let btn = extract_widget::<Button>(&self.builder, "btn_install_kernel");
+        btn.connect_clicked(move |_| me.choose_kernel());
+    }
+
+    /// Offer the popular kernels, then install the pick with its headers.
+    fn choose_kernel(self: &Rc<Self>) {
+        info!("Popular kernels dialog opened");
+        let mut config = SelectionDialogConfig::new(
+            "Install a Kernel",
+            "The kernel is installed next to your current one, with its headers so \
+             DKMS modules keep building. The boot menu is regenerated afterwards.",
+        )
+        .selection_type(SelectionType::Single)
+        .item_noun("kernel")
+        .confirm_label("Install");
+        for (suffix, label, description) in POPULAR_KERNELS {
+            let package = format!("linux-{suffix}");
+            let mut description = description.to_string();
+            if core::is_package_installed(&package) {
+                description.push_str(" — already installed");
+            } else if !core::is_package_in_repos(&package) {
+                description.push_str(" — not in your repos, builds from the AUR");
+            }
+            config = config.add_option(SelectionOption::new(suffix, label, &description, false));
+        }
+
+        let me = self.clone();
+        show_selection_dialog(self.window.upcast_ref(), config, move |selected| {
+            let Some(suffix) = selected.first() else {
+                return;
+            };
+            let kernel = format!("linux-{suffix}");
+            let headers = core::package::kernel_headers_package(suffix);
+            if core::is_package_installed(&kernel) {
+                let me = me.clone();
+                let window = me.window.clone();
+                show_warning_confirmation(
+                    window.upcast_ref(),
+                    "Kernel Already Installed",
+                    &format!(
+                        "<b>{kernel}</b> is already installed and won't be reinstalled.\n\n\
+                         Make sure <b>{headers}</b> is present and regenerate the boot menu anyway?"
+                    ),
+                    move || me.install_kernel(&kernel, &headers),
+                );
+            } else {
+                me.install_kernel(&kernel, &headers);
+            }
+        });
+    }
+
+    fn install_kernel(self: &Rc<Self>, kernel: &str, headers: &str) {
+        info!("installing {kernel} + {headers} with bootloader update");
+        let mut commands = CommandSequence::new().then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", kernel, headers])
+                .description(&format!("Installing {kernel} and {headers}..."))
+                .build(),
+        );
+        commands = match detect_bootloader() {
+            Some(Bootloader::Grub) => commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("grub-mkconfig")
+                    .args(&["-o", GRUB_CFG])
+                    .description("Regenerating the GRUB menu...")
+                    .build(),
+            ),
+            Some(Bootloader::SystemdBoot) => commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", SYSTEMD_BOOT_ENTRIES_SCRIPT])
+                    .description("Updating systemd-boot entries...")
+                    .build(),
+            ),
+            None => {
+                warn!("no GRUB or systemd-boot found; skipping boot menu update");
+                commands
+            }
+        };
+        self.run_sequence(commands.build(), "Install Kernel");
+    }
+
     /// Kick off a background scan, freezing the list controls until the
     /// worker thread posts its result back through the channel.
     fn rescan(self: &Rc<Self>, button: Option<Button>) {
@@ -192,7 +285,13 @@ impl KernelTab {
                     .description(description)
                     .build(),
             )
+            // Both callers have already asked.
+            .confirm(false)
             .build();
+        self.run_sequence(commands, title);
+    }
+
+    fn run_sequence(self: &Rc<Self>, commands: CommandSequence, title: &str) {
         task_runner::run(self.window.upcast_ref(), commands, title);
 
         // Poll the runner until it finishes, then rescan once so the rows
@@ -209,6 +308,26 @@ impl KernelTab {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Popular kernels and boot menu
+// ---------------------------------------------------------------------------
+
+/// Kernels offered by the quick installer: (flavour suffix, label, description).
+const POPULAR_KERNELS: &[(&str, &str, &str)] = &[
+    ("zen", "Zen", "Desktop-tuned, low latency"),
+    ("cachyos", "CachyOS", "Scheduler and compiler optimisations for gaming"),
+    ("hardened", "Hardened", "Security-focused patch set"),
+    ("lts", "LTS", "Long-term support, the safe fallback"),
+];
+
+/// systemd-boot has no menu generator of its own; use whichever helper the
+/// distribution ships, which also picks up the new kernel's initramfs.
+const SYSTEMD_BOOT_ENTRIES_SCRIPT: &str = "\
+if command -v sdboot-manage >/dev/null 2>&1; then sdboot-manage gen; \
+elif command -v reinstall-kernels >/dev/null 2>&1; then reinstall-kernels; \
+else echo 'No systemd-boot entry generator found: add a loader entry for the new kernel \
+under /boot/loader/entries.' >&2; exit 1; fi";
+
 // ---------------------------------------------------------------------------
 // Pacman scan
 // ---------------------------------------------------------------------------
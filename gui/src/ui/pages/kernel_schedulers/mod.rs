@@ -1,22 +1,51 @@
-//! Kernel & Schedulers page with subtabs for Kernel Manager and SCX Scheduler.
+//! Kernel & Schedulers page with subtabs for Kernel Manager, SCX Scheduler
+//! and CPU Power.
 //!
-//! This module provides a unified page with two subtabs:
+//! This module provides a unified page with three subtabs:
 //! - Kernel Manager: Install/remove kernels and headers
 //! - SCX Scheduler: Manage sched-ext BPF CPU schedulers
+//! - CPU Power: Switch performance / balanced / power-saver profiles
 
 pub mod kernel_manager_tab;
+pub mod power_tab;
 pub mod scheduler_tab;
 
+use crate::ui::navigation::Tool;
 use gtk4::{ApplicationWindow, Builder};
 use log::info;
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Kernel Manager",
+        keywords: "linux zen lts cachyos",
+        button: "btn_refresh_kernels",
+    },
+    Tool {
+        name: "Install Kernel",
+        keywords: "linux-zen linux-cachyos hardened lts headers grub systemd-boot",
+        button: "btn_install_kernel",
+    },
+    Tool {
+        name: "SCX Scheduler",
+        keywords: "sched_ext scx lavd bpfland rusty",
+        button: "btn_switch_scheduler",
+    },
+    Tool {
+        name: "CPU Power Profile",
+        keywords: "governor performance powersave balanced power-profiles-daemon cpupower battery",
+        button: "btn_profile_balanced",
+    },
+];
+
 /// Set up all handlers for the kernel & schedulers page with subtabs.
 pub fn setup_handlers(page_builder: &Builder, main_builder: &Builder, window: &ApplicationWindow) {
     info!("Setting up Kernel & Schedulers page with subtabs");
 
-    // Setup handlers for both subtabs
+    // Setup handlers for all subtabs
     kernel_manager_tab::setup_handlers(page_builder, main_builder, window);
     scheduler_tab::setup_handlers(page_builder, main_builder, window);
+    power_tab::setup_handlers(page_builder, main_builder, window);
 
     info!("Kernel & Schedulers page handlers initialized");
 }
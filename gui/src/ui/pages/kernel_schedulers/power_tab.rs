@@ -0,0 +1,282 @@
+//! CPU Power tab: switch between performance, balanced and power-saving.
+//!
+//! `power-profiles-daemon` is preferred and installed on first use; it
+//! remembers the last profile across reboots on its own. Systems that
+//! already run `cpupower` instead get the matching governor applied and
+//! written to its service config. The current governor is read from sysfs,
+//! and the whole tab is disabled when the CPU exposes no cpufreq policy
+//! (typical for virtual machines).
+
+use std::fs;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, Label};
+use log::info;
+
+use crate::core;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{extract_widget, run_command};
+
+const GOVERNOR_PATH: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor";
+const AVAILABLE_GOVERNORS_PATH: &str =
+    "/sys/devices/system/cpu/cpu0/cpufreq/scaling_available_governors";
+
+const PPD_PACKAGE: &str = "power-profiles-daemon";
+const PPD_SERVICE: &str = "power-profiles-daemon.service";
+
+/// Writes the governor into whichever config the installed cpupower reads:
+/// `/etc/default/cpupower-service.conf` on current packages, the older
+/// `/etc/default/cpupower` otherwise. `$1` is the governor.
+const CPUPOWER_PERSIST_SCRIPT: &str = r#"
+if [ -f /etc/default/cpupower-service.conf ]; then
+    sed -i -E "s/^#?GOVERNOR=.*/GOVERNOR='$1'/" /etc/default/cpupower-service.conf
+else
+    sed -i -E "s/^#?governor=.*/governor='$1'/" /etc/default/cpupower
+fi
+"#;
+
+const WAIT_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    Performance,
+    Balanced,
+    PowerSaver,
+}
+
+impl Profile {
+    const ALL: [Profile; 3] = [Profile::Performance, Profile::Balanced, Profile::PowerSaver];
+
+    fn button_id(self) -> &'static str {
+        match self {
+            Profile::Performance => "btn_profile_performance",
+            Profile::Balanced => "btn_profile_balanced",
+            Profile::PowerSaver => "btn_profile_powersave",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Profile::Performance => "Performance",
+            Profile::Balanced => "Balanced",
+            Profile::PowerSaver => "Power Saver",
+        }
+    }
+
+    /// Name understood by `powerprofilesctl`.
+    fn ppd_name(self) -> &'static str {
+        match self {
+            Profile::Performance => "performance",
+            Profile::Balanced => "balanced",
+            Profile::PowerSaver => "power-saver",
+        }
+    }
+
+    /// Best cpufreq governor for this profile among those the driver offers.
+    /// `intel_pstate` and `amd-pstate` only offer performance/powersave, and
+    /// their powersave already scales with load, so it doubles as balanced.
+    fn governor(self, available: &[String]) -> Option<String> {
+        let wanted: &[&str] = match self {
+            Profile::Performance => &["performance"],
+            Profile::Balanced => &["schedutil", "ondemand", "powersave"],
+            Profile::PowerSaver => &["powersave", "conservative"],
+        };
+        wanted
+            .iter()
+            .find(|g| available.iter().any(|a| a == *g))
+            .map(|g| (*g).to_owned())
+    }
+}
+
+/// Which tool applies the profile.
+enum Backend {
+    /// power-profiles-daemon, installed on demand.
+    Daemon,
+    /// cpupower is already set up and ppd isn't; don't pull in a second tool.
+    Cpupower,
+}
+
+fn detect_backend() -> Backend {
+    if !core::is_package_installed(PPD_PACKAGE) && core::is_package_installed("cpupower") {
+        Backend::Cpupower
+    } else {
+        Backend::Daemon
+    }
+}
+
+fn read_sysfs(path: &str) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_owned())
+}
+
+fn available_governors() -> Vec<String> {
+    read_sysfs(AVAILABLE_GOVERNORS_PATH)
+        .map(|s| s.split_whitespace().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+pub fn setup_handlers(
+    builder: &Builder,
+    _main_builder: &Builder,
+    window: &ApplicationWindow,
+) {
+    let tab = Rc::new(PowerTab {
+        builder: builder.clone(),
+        window: window.clone(),
+    });
+    for profile in Profile::ALL {
+        let me = tab.clone();
+        extract_widget::<Button>(builder, profile.button_id())
+            .connect_clicked(move |_| me.apply(profile));
+    }
+    tab.refresh();
+}
+
+struct PowerTab {
+    builder: Builder,
+    window: ApplicationWindow,
+}
+
+impl PowerTab {
+    /// Reflect the current governor (and ppd profile, when running) and
+    /// lock the buttons when there's no cpufreq to drive.
+    fn refresh(&self) {
+        let governor_label = extract_widget::<Label>(&self.builder, "cpu_governor_label");
+        let note = extract_widget::<Label>(&self.builder, "cpu_power_unavailable_label");
+
+        let Some(governor) = read_sysfs(GOVERNOR_PATH) else {
+            governor_label.set_text("Unavailable");
+            note.set_visible(true);
+            for profile in Profile::ALL {
+                extract_widget::<Button>(&self.builder, profile.button_id()).set_sensitive(false);
+            }
+            return;
+        };
+        note.set_visible(false);
+
+        let active = match run_command("powerprofilesctl", &["get"]) {
+            Some(profile) => {
+                governor_label.set_text(&format!("{governor} ({profile})"));
+                Profile::ALL.into_iter().find(|p| p.ppd_name() == profile)
+            }
+            None => {
+                governor_label.set_text(&governor);
+                let available = available_governors();
+                // Only claim a profile when the governor maps back uniquely.
+                let matching: Vec<Profile> = Profile::ALL
+                    .into_iter()
+                    .filter(|p| p.governor(&available).as_deref() == Some(governor.as_str()))
+                    .collect();
+                match matching.as_slice() {
+                    [only] => Some(*only),
+                    _ => None,
+                }
+            }
+        };
+
+        for profile in Profile::ALL {
+            let button = extract_widget::<Button>(&self.builder, profile.button_id());
+            button.set_sensitive(true);
+            if Some(profile) == active {
+                button.add_css_class("suggested-action");
+            } else {
+                button.remove_css_class("suggested-action");
+            }
+        }
+    }
+
+    fn apply(self: &Rc<Self>, profile: Profile) {
+        info!("Switching CPU power profile to {}", profile.label());
+        let commands = match detect_backend() {
+            Backend::Daemon => daemon_commands(profile),
+            Backend::Cpupower => {
+                let Some(governor) = profile.governor(&available_governors()) else {
+                    crate::ui::dialogs::error::show_error(
+                        &self.window,
+                        &format!(
+                            "The CPU frequency driver offers no governor suitable for the {} profile.",
+                            profile.label()
+                        ),
+                    );
+                    return;
+                };
+                cpupower_commands(&governor)
+            }
+        };
+        task_runner::run(
+            self.window.upcast_ref(),
+            commands.build(),
+            &format!("Power Profile: {}", profile.label()),
+        );
+
+        let me = self.clone();
+        glib::timeout_add_local(WAIT_POLL, move || {
+            if task_runner::is_running() {
+                glib::ControlFlow::Continue
+            } else {
+                me.refresh();
+                glib::ControlFlow::Break
+            }
+        });
+    }
+}
+
+fn daemon_commands(profile: Profile) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    if !core::is_package_installed(PPD_PACKAGE) {
+        commands = commands
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", PPD_PACKAGE])
+                    .description("Installing power-profiles-daemon...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["enable", "--now", PPD_SERVICE])
+                    .description("Starting power-profiles-daemon...")
+                    .build(),
+            );
+    }
+    commands.then(
+        Command::builder()
+            .normal()
+            .program("powerprofilesctl")
+            .args(&["set", profile.ppd_name()])
+            .description(&format!("Setting the {} profile...", profile.label()))
+            .build(),
+    )
+}
+
+fn cpupower_commands(governor: &str) -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("cpupower")
+                .args(&["frequency-set", "-g", governor])
+                .description(&format!("Applying the {governor} governor..."))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", CPUPOWER_PERSIST_SCRIPT, "sh", governor])
+                .description("Saving the governor for future boots...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "cpupower.service"])
+                .description("Enabling cpupower at boot...")
+                .build(),
+        )
+}
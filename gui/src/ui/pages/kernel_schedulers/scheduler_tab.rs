@@ -4,7 +4,8 @@
 //! the `scx_loader` D-Bus service (falling back to a `/usr/bin/scx_*` scan
 //! when the loader isn't running), and drives switch / start / stop through
 //! the loader's D-Bus interface. Persistence is handled by writing
-//! `/etc/scx_loader.toml` and enabling `scx_loader.service`.
+//! `/etc/scx_loader.toml` and enabling `scx_loader.service`. When no
+//! schedulers are installed yet, the start button installs them instead.
 
 use std::cell::RefCell;
 use std::collections::HashSet;
@@ -24,6 +25,12 @@ use crate::ui::utils::{extract_widget, is_service_enabled, path_exists, run_comm
 
 const SCHED_EXT_PATH: &str = "/sys/kernel/sched_ext";
 
+/// First mainline release with sched_ext.
+const SCHED_EXT_MIN_KERNEL: (u32, u32) = (6, 12);
+
+const SWITCH_LABEL: &str = "Start/Switch Scheduler";
+const INSTALL_LABEL: &str = "Install Schedulers";
+
 const LOADER_SERVICE: &str = "scx_loader.service";
 const LOADER_CONFIG_PATH: &str = "/etc/scx_loader.toml";
 const LOADER_CONFIG_STAGING: &str = "/tmp/scx_loader.toml";
@@ -121,7 +128,15 @@ impl SchedTab {
         } else {
             icon.set_icon_name(Some("circle-xmark"));
             icon.add_css_class("error");
-            version_label.set_text(&format!("{version} (no sched-ext)"));
+            let reason = if kernel_at_least(&version, SCHED_EXT_MIN_KERNEL) {
+                String::from("no sched-ext")
+            } else {
+                format!(
+                    "needs Linux {}.{}+",
+                    SCHED_EXT_MIN_KERNEL.0, SCHED_EXT_MIN_KERNEL.1
+                )
+            };
+            version_label.set_text(&format!("{version} ({reason})"));
             version_label.add_css_class("warning");
             legend.set_text("Not supported");
         }
@@ -233,6 +248,10 @@ impl SchedTab {
     // -- switch / start / stop ---------------------------------------------
 
     fn switch_or_start(self: &Rc<Self>) {
+        if self.state.borrow().schedulers.is_empty() {
+            self.confirm_install();
+            return;
+        }
         let Some(sched_name) = self.state.borrow().picked.clone() else {
             warn!("start/switch with no scheduler picked");
             return;
@@ -264,6 +283,57 @@ impl SchedTab {
         task_runner::run(self.window.upcast_ref(), seq, title);
     }
 
+    /// Install the scheduler binaries and scx_loader. On a kernel without
+    /// sched_ext they would never load, so ask first.
+    fn confirm_install(self: &Rc<Self>) {
+        if self.state.borrow().kernel_supported {
+            self.install_schedulers();
+            return;
+        }
+        let me = self.clone();
+        show_warning_confirmation(
+            self.window.upcast_ref(),
+            "Kernel Not Supported",
+            &format!(
+                "The running kernel has no <b>sched_ext</b> support, so no scx scheduler \
+                 can start on it. You need Linux {}.{} or newer built with sched_ext \
+                 (linux, linux-zen and linux-cachyos all are).\n\n\
+                 Install the schedulers anyway?",
+                SCHED_EXT_MIN_KERNEL.0, SCHED_EXT_MIN_KERNEL.1
+            ),
+            move || me.install_schedulers(),
+        );
+    }
+
+    fn install_schedulers(self: &Rc<Self>) {
+        info!("installing scx schedulers");
+        let mut args = vec!["-S", "--noconfirm", "--needed", "scx-scheds"];
+        // Newer packaging moved scx_loader into its own package.
+        if crate::core::is_package_in_repos("scx-tools") {
+            args.push("scx-tools");
+        }
+        let seq = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&args)
+                    .description("Installing sched-ext schedulers...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(self.window.upcast_ref(), seq, "Install Schedulers");
+
+        let me = self.clone();
+        glib::timeout_add_local(STATUS_REFRESH, move || {
+            if task_runner::is_running() {
+                glib::ControlFlow::Continue
+            } else {
+                me.rescan(None);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
     fn confirm_stop(self: &Rc<Self>) {
         let me = self.clone();
         show_warning_confirmation(
@@ -311,9 +381,10 @@ impl SchedTab {
             Ok(scan) => {
                 me.apply_scan(&scan);
                 if let Some(lock) = lock_slot.borrow_mut().take() {
+                    // With nothing installed the button offers the install.
                     lock.release(
                         &me.builder,
-                        scan.kernel_supported && !scan.schedulers.is_empty(),
+                        scan.kernel_supported || scan.schedulers.is_empty(),
                         scan.active,
                     );
                 }
@@ -345,6 +416,13 @@ impl SchedTab {
             extract_widget::<Label>(&self.builder, "selected_scheduler_label")
                 .set_label(&humanize(&pick));
         }
+        extract_widget::<Button>(&self.builder, "btn_switch_scheduler").set_label(
+            if scan.schedulers.is_empty() {
+                INSTALL_LABEL
+            } else {
+                SWITCH_LABEL
+            },
+        );
 
         render_active(
             &self.builder,
@@ -450,6 +528,18 @@ fn list_schedulers() -> Vec<String> {
     out
 }
 
+/// Whether a `uname -r` string such as `6.12.8-zen1-1-zen` is at least
+/// `major.minor`. Unparseable versions count as too old.
+fn kernel_at_least(release: &str, (major, minor): (u32, u32)) -> bool {
+    let mut parts = release
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|p| p.parse::<u32>().ok());
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(ma), Some(mi)) => (ma, mi) >= (major, minor),
+        _ => false,
+    }
+}
+
 fn default_pick(available: &[String]) -> String {
     for preferred in DEFAULT_PICK_ORDER {
         if available.iter().any(|s| s == preferred) {
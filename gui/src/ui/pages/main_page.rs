@@ -14,12 +14,37 @@ use crate::ui::dialogs::selection::{
 };
 use crate::ui::dialogs::terminal;
 use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
 use crate::ui::utils::extract_widget;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Update System",
+        keywords: "upgrade pacman",
+        button: "btn_update_system",
+    },
+    Tool {
+        name: "Package Manager GUI",
+        keywords: "octopi pacseek bauh warehouse flatseal bazaar",
+        button: "btn_pkg_manager",
+    },
+    Tool {
+        name: "Download Arch ISO",
+        keywords: "installer image endeavouros",
+        button: "btn_download_arch_iso",
+    },
+    Tool {
+        name: "Nix Package Manager",
+        keywords: "nixpkgs",
+        button: "btn_install_nix",
+    },
+];
+
 /// Set up all button handlers for the main page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_update_system(page_builder, window);
@@ -84,19 +109,19 @@ fn setup_pkg_manager(builder: &Builder, window: &ApplicationWindow) {
             "warehouse",
             "Warehouse",
             "Flatpak package manager (Flatpak)",
-            core::is_flatpak_installed("io.github.flattool.Warehouse"),
+            core::is_flatpak_installed("io.github.flattool.Warehouse", core::FlatpakKind::App),
         ))
         .add_option(SelectionOption::new(
             "flatseal",
             "Flatseal",
             "Flatpak permissions manager (Flatpak)",
-            core::is_flatpak_installed("com.github.tchx84.Flatseal"),
+            core::is_flatpak_installed("com.github.tchx84.Flatseal", core::FlatpakKind::App),
         ))
         .add_option(SelectionOption::new(
             "bazaar",
             "Bazaar",
             "Browse and install Flatpak apps (Flatpak)",
-            core::is_flatpak_installed("io.github.kolunmi.Bazaar"),
+            core::is_flatpak_installed("io.github.kolunmi.Bazaar", core::FlatpakKind::App),
         ))
         .confirm_label("Install");
 
@@ -152,9 +177,8 @@ fn build_pkg_manager_commands(selected: &[String]) -> CommandSequence {
     if selected.iter().any(|s| s == "warehouse") {
         commands = commands.then(
             Command::builder()
-                .normal()
-                .program("flatpak")
-                .args(&["install", "-y", "io.github.flattool.Warehouse"])
+                .flatpak_install()
+                .args(&["io.github.flattool.Warehouse"])
                 .description("Installing Warehouse from Flathub...")
                 .build(),
         );
@@ -163,9 +187,8 @@ fn build_pkg_manager_commands(selected: &[String]) -> CommandSequence {
     if selected.iter().any(|s| s == "flatseal") {
         commands = commands.then(
             Command::builder()
-                .normal()
-                .program("flatpak")
-                .args(&["install", "-y", "com.github.tchx84.Flatseal"])
+                .flatpak_install()
+                .args(&["com.github.tchx84.Flatseal"])
                 .description("Installing Flatseal from Flathub...")
                 .build(),
         );
@@ -174,9 +197,8 @@ fn build_pkg_manager_commands(selected: &[String]) -> CommandSequence {
     if selected.iter().any(|s| s == "bazaar") {
         commands = commands.then(
             Command::builder()
-                .normal()
-                .program("flatpak")
-                .args(&["install", "-y", "io.github.kolunmi.Bazaar"])
+                .flatpak_install()
+                .args(&["io.github.kolunmi.Bazaar"])
                 .description("Installing Bazaar from Flathub...")
                 .build(),
         );
@@ -1,345 +1,798 @@
 //! Multimedia tools page button handlers.
 //!
 //! Handles:
-//! - OBS-Studio with plugins and V4L2
+//! - OBS-Studio (native or Flatpak) with plugins and V4L2
 //! - Kdenlive video editor
-//! - Jellyfin server installation
+//! - DaVinci Resolve (Free or Studio) with the GPU compute runtime it needs
+//! - Jellyfin server installation, firewall port and access URL
+//! - HandBrake and Audacity (native or Flatpak)
 //! - GPU Screen Recorder GTK (repo-first, AUR fallback)
-//! - Streaming service web app installer
+//! - Streaming service web app installer (Chrome, Brave, Chromium or Firefox),
+//!   including one custom URL
 //! - Enhanced Audio (PipeWire spatial convolver)
 
 use crate::core;
+use crate::core::gpu::GpuVendor;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
-use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::navigation::Tool;
+use crate::ui::task_runner::{self, shell_quote, Command, CommandSequence};
+use crate::ui::utils::{
+    desktop_exec_quote, extract_widget, is_service_active, on_refocus, refresh_button_states,
+    run_command, InstallCheck,
+};
 use gtk4::prelude::*;
-use gtk4::{ApplicationWindow, Builder};
+use gtk4::{glib, ApplicationWindow, Builder, Button};
 use log::info;
 
 fn sanitize_filename(name: &str) -> String {
     name.replace('+', "Plus")
 }
 
-/// Streaming service entries: (name, url)
-const STREAMING_SERVICES: &[(&str, &str)] = &[
-    ("ABC IView", "https://iview.abc.net.au"),
-    ("AirGPU", "https://app.airgpu.com"),
-    ("Amazon Luna", "https://luna.amazon.com/"),
-    ("Amazon Prime Video", "https://www.amazon.com/video"),
-    ("Angry Birds TV", "https://www.angrybirds.com/series/"),
-    ("Antstream", "https://live.antstream.com/"),
-    ("Apple TV", "https://tv.apple.com/"),
-    ("BBC iPlayer", "https://www.bbc.co.uk/iplayer/"),
-    ("BritBox", "https://britbox.com"),
-    ("Binge", "https://binge.com.au"),
-    ("Blacknut", "https://www.blacknut.com/en-gb/games"),
-    ("Boosteroid", "https://cloud.boosteroid.com"),
-    ("CBBC", "https://www.bbc.co.uk/cbbc"),
-    ("CBeebies", "https://www.bbc.co.uk/cbeebies"),
-    ("Channel 4", "https://www.channel4.com/"),
-    ("Crave", "https://www.crave.ca/"),
-    ("Criterion Channel", "https://www.criterionchannel.com"),
-    ("Crunchyroll", "https://www.crunchyroll.com/"),
-    ("Curiosity Stream", "https://curiositystream.com"),
-    ("Daily Wire", "https://www.dailywire.com/watch"),
-    ("Discord", "https://discord.com/app"),
-    ("Disney+", "https://www.disneyplus.com/"),
-    ("DocPlay", "https://www.docplay.com"),
-    ("Dropout", "https://www.dropout.tv/browse"),
-    ("Emby Theater", "https://emby.media/"),
-    ("Fox", "https://www.fox.com/"),
-    ("Fubo TV", "https://www.fubo.tv"),
-    ("GeForce Now", "https://play.geforcenow.com/mall/"),
-    ("GBNews Live", "https://www.gbnews.com/watch/live"),
-    ("GlobalComix", "https://globalcomix.com/"),
-    ("Google Play Books", "https://play.google.com/store/books"),
-    ("HBO Max", "https://www.max.com/"),
-    ("Home Assistant", "https://demo.home-assistant.io/"),
-    ("Hulu", "https://www.hulu.com/"),
-    ("Internet Archive Movies", "https://archive.org/details/movies"),
-    ("ITV X", "https://www.itv.com/"),
-    ("Kanopy", "https://www.kanopy.com"),
-    ("Microsoft Movies and TV", "https://apps.microsoft.com/movies"),
-    ("My5", "https://www.channel5.com/"),
-    ("Nebula", "https://nebula.tv/"),
-    ("Netflix", "https://www.netflix.com/"),
-    ("Newgrounds Movies", "https://www.newgrounds.com/movies"),
-    ("Newgrounds Games", "https://www.newgrounds.com/games"),
-    ("Kogama", "https://www.kogama.com/"),
-    ("Paramount+", "https://www.paramountplus.com/"),
-    ("Peacock TV", "https://www.peacocktv.com/"),
-    ("POP Player", "https://player.pop.co.uk/"),
-    ("Puffer", "https://puffer.stanford.edu/player/"),
-    ("Plex", "https://app.plex.tv/"),
-    ("Pocket Casts", "https://play.pocketcasts.com"),
-    ("Poki", "https://poki.com/"),
-    ("Reddit", "https://www.reddit.com/r/all/"),
-    ("SBS Ondemand", "https://www.sbs.com.au/ondemand/"),
-    ("Scratch", "https://scratch.mit.edu/explore/projects/all"),
-    ("Sling TV", "https://www.sling.com"),
-    ("Spotify", "https://open.spotify.com/"),
-    ("Stan", "https://www.stan.com.au"),
-    ("Steam Broadcasts", "https://steamcommunity.com/?subsection=broadcasts"),
-    ("Squid TV", "https://www.squidtv.net/"),
-    ("TikTok", "https://www.tiktok.com/"),
-    ("Threads", "https://www.threads.net/"),
-    ("Twitch", "https://www.twitch.tv/"),
-    ("Twitter", "https://twitter.com/"),
-    ("Vimeo", "https://vimeo.com/"),
-    ("Virgin TV Go", "https://virgintvgo.virginmedia.com/en/home"),
-    ("VK Play", "https://cloud.vkplay.ru/"),
-    ("Xbox Game Pass Streaming", "https://www.xbox.com/play"),
-    ("Xiaohongshu (RedNote)", "https://www.xiaohongshu.com/explore"),
-    ("YouTube Music", "https://music.youtube.com/"),
-    ("YouTube TV", "https://tv.youtube.com/"),
-    ("YouTube", "https://www.youtube.com/"),
-    ("WebRcade", "https://play.webrcade.com/"),
+const GROUP_VIDEO: &str = "Video";
+const GROUP_MUSIC: &str = "Music & Podcasts";
+const GROUP_GAMES: &str = "Cloud Gaming & Games";
+const GROUP_OTHER: &str = "Social & Other";
+
+/// Streaming service entries: (name, url, dialog group)
+const STREAMING_SERVICES: &[(&str, &str, &str)] = &[
+    ("ABC IView", "https://iview.abc.net.au", GROUP_VIDEO),
+    ("AirGPU", "https://app.airgpu.com", GROUP_GAMES),
+    ("Amazon Luna", "https://luna.amazon.com/", GROUP_GAMES),
+    ("Amazon Prime Video", "https://www.amazon.com/video", GROUP_VIDEO),
+    ("Angry Birds TV", "https://www.angrybirds.com/series/", GROUP_VIDEO),
+    ("Antstream", "https://live.antstream.com/", GROUP_GAMES),
+    ("Apple TV", "https://tv.apple.com/", GROUP_VIDEO),
+    ("BBC iPlayer", "https://www.bbc.co.uk/iplayer/", GROUP_VIDEO),
+    ("BritBox", "https://britbox.com", GROUP_VIDEO),
+    ("Binge", "https://binge.com.au", GROUP_VIDEO),
+    ("Blacknut", "https://www.blacknut.com/en-gb/games", GROUP_GAMES),
+    ("Boosteroid", "https://cloud.boosteroid.com", GROUP_GAMES),
+    ("CBBC", "https://www.bbc.co.uk/cbbc", GROUP_VIDEO),
+    ("CBeebies", "https://www.bbc.co.uk/cbeebies", GROUP_VIDEO),
+    ("Channel 4", "https://www.channel4.com/", GROUP_VIDEO),
+    ("Crave", "https://www.crave.ca/", GROUP_VIDEO),
+    ("Criterion Channel", "https://www.criterionchannel.com", GROUP_VIDEO),
+    ("Crunchyroll", "https://www.crunchyroll.com/", GROUP_VIDEO),
+    ("Curiosity Stream", "https://curiositystream.com", GROUP_VIDEO),
+    ("Daily Wire", "https://www.dailywire.com/watch", GROUP_VIDEO),
+    ("Discord", "https://discord.com/app", GROUP_OTHER),
+    ("Disney+", "https://www.disneyplus.com/", GROUP_VIDEO),
+    ("DocPlay", "https://www.docplay.com", GROUP_VIDEO),
+    ("Dropout", "https://www.dropout.tv/browse", GROUP_VIDEO),
+    ("Emby Theater", "https://emby.media/", GROUP_VIDEO),
+    ("Fox", "https://www.fox.com/", GROUP_VIDEO),
+    ("Fubo TV", "https://www.fubo.tv", GROUP_VIDEO),
+    ("GeForce Now", "https://play.geforcenow.com/mall/", GROUP_GAMES),
+    ("GBNews Live", "https://www.gbnews.com/watch/live", GROUP_VIDEO),
+    ("GlobalComix", "https://globalcomix.com/", GROUP_OTHER),
+    ("Google Play Books", "https://play.google.com/store/books", GROUP_OTHER),
+    ("HBO Max", "https://www.max.com/", GROUP_VIDEO),
+    ("Home Assistant", "https://demo.home-assistant.io/", GROUP_OTHER),
+    ("Hulu", "https://www.hulu.com/", GROUP_VIDEO),
+    ("Internet Archive Movies", "https://archive.org/details/movies", GROUP_VIDEO),
+    ("ITV X", "https://www.itv.com/", GROUP_VIDEO),
+    ("Kanopy", "https://www.kanopy.com", GROUP_VIDEO),
+    ("Microsoft Movies and TV", "https://apps.microsoft.com/movies", GROUP_VIDEO),
+    ("My5", "https://www.channel5.com/", GROUP_VIDEO),
+    ("Nebula", "https://nebula.tv/", GROUP_VIDEO),
+    ("Netflix", "https://www.netflix.com/", GROUP_VIDEO),
+    ("Newgrounds Movies", "https://www.newgrounds.com/movies", GROUP_VIDEO),
+    ("Newgrounds Games", "https://www.newgrounds.com/games", GROUP_GAMES),
+    ("Kogama", "https://www.kogama.com/", GROUP_GAMES),
+    ("Paramount+", "https://www.paramountplus.com/", GROUP_VIDEO),
+    ("Peacock TV", "https://www.peacocktv.com/", GROUP_VIDEO),
+    ("POP Player", "https://player.pop.co.uk/", GROUP_VIDEO),
+    ("Puffer", "https://puffer.stanford.edu/player/", GROUP_VIDEO),
+    ("Plex", "https://app.plex.tv/", GROUP_VIDEO),
+    ("Pocket Casts", "https://play.pocketcasts.com", GROUP_MUSIC),
+    ("Poki", "https://poki.com/", GROUP_GAMES),
+    ("Reddit", "https://www.reddit.com/r/all/", GROUP_OTHER),
+    ("SBS Ondemand", "https://www.sbs.com.au/ondemand/", GROUP_VIDEO),
+    ("Scratch", "https://scratch.mit.edu/explore/projects/all", GROUP_OTHER),
+    ("Sling TV", "https://www.sling.com", GROUP_VIDEO),
+    ("Spotify", "https://open.spotify.com/", GROUP_MUSIC),
+    ("Stan", "https://www.stan.com.au", GROUP_VIDEO),
+    ("Steam Broadcasts", "https://steamcommunity.com/?subsection=broadcasts", GROUP_VIDEO),
+    ("Squid TV", "https://www.squidtv.net/", GROUP_VIDEO),
+    ("TikTok", "https://www.tiktok.com/", GROUP_OTHER),
+    ("Threads", "https://www.threads.net/", GROUP_OTHER),
+    ("Twitch", "https://www.twitch.tv/", GROUP_VIDEO),
+    ("Twitter", "https://twitter.com/", GROUP_OTHER),
+    ("Vimeo", "https://vimeo.com/", GROUP_VIDEO),
+    ("Virgin TV Go", "https://virgintvgo.virginmedia.com/en/home", GROUP_VIDEO),
+    ("VK Play", "https://cloud.vkplay.ru/", GROUP_GAMES),
+    ("Xbox Game Pass Streaming", "https://www.xbox.com/play", GROUP_GAMES),
+    ("Xiaohongshu (RedNote)", "https://www.xiaohongshu.com/explore", GROUP_OTHER),
+    ("YouTube Music", "https://music.youtube.com/", GROUP_MUSIC),
+    ("YouTube TV", "https://tv.youtube.com/", GROUP_VIDEO),
+    ("YouTube", "https://www.youtube.com/", GROUP_VIDEO),
+    ("WebRcade", "https://play.webrcade.com/", GROUP_GAMES),
+];
+
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "OBS Studio",
+        keywords: "streaming recording plugins",
+        button: "btn_obs_studio_aio",
+    },
+    Tool {
+        name: "Kdenlive",
+        keywords: "video editor",
+        button: "btn_kdenlive",
+    },
+    Tool {
+        name: "DaVinci Resolve",
+        keywords: "video editor color grading blackmagic",
+        button: "btn_davinci_resolve",
+    },
+    Tool {
+        name: "Jellyfin Server",
+        keywords: "media server",
+        button: "btn_jellyfin",
+    },
+    Tool {
+        name: "HandBrake",
+        keywords: "video transcoder converter",
+        button: "btn_handbrake",
+    },
+    Tool {
+        name: "Audacity",
+        keywords: "audio editor recording",
+        button: "btn_audacity",
+    },
+    Tool {
+        name: "GPU Screen Recorder",
+        keywords: "recording capture",
+        button: "btn_gpu_screen_recorder",
+    },
+    Tool {
+        name: "Streaming Services",
+        keywords: "netflix youtube spotify web apps",
+        button: "btn_streaming_services",
+    },
+    Tool {
+        name: "Enhanced Audio",
+        keywords: "pipewire easyeffects",
+        button: "btn_enhanced_audio",
+    },
 ];
 
 /// Set up all button handlers for the multimedia tools page
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_obs_studio_aio(page_builder, window);
     setup_kdenlive(page_builder, window);
+    setup_davinci_resolve(page_builder, window);
     setup_jellyfin(page_builder, window);
     setup_gpu_screen_recorder(page_builder, window);
     setup_streaming_services(page_builder, window);
     setup_enhanced_audio(page_builder, window);
+
+    let apps: Vec<(Button, Button, &'static QuickApp)> = QUICK_APPS
+        .iter()
+        .map(|app| {
+            let (install, uninstall) = setup_quick_app(page_builder, window, app);
+            (install, uninstall, app)
+        })
+        .collect();
+    async_refresh_quick_apps(&apps);
+    on_refocus(window, move || async_refresh_quick_apps(&apps));
+}
+
+/// How OBS itself is installed; plugins follow the same backend.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ObsBackend {
+    Native,
+    Flatpak,
+}
+
+const OBS_FLATPAK: &str = "com.obsproject.Studio";
+
+/// A plugin bundle offered in the OBS dialog, with its packages for each
+/// backend. An empty list means the bundle isn't packaged for that backend.
+struct ObsPluginSet {
+    id: &'static str,
+    label: &'static str,
+    group: &'static str,
+    description: &'static str,
+    native: &'static [&'static str],
+    flatpak: &'static [&'static str],
 }
 
+impl ObsPluginSet {
+    fn packages(&self, backend: ObsBackend) -> &'static [&'static str] {
+        match backend {
+            ObsBackend::Native => self.native,
+            ObsBackend::Flatpak => self.flatpak,
+        }
+    }
+}
+
+// obs-websocket has been bundled with obs-studio since v28, so it is
+// intentionally omitted as a standalone option.
+const OBS_PLUGIN_SETS: &[ObsPluginSet] = &[
+    ObsPluginSet {
+        id: "graphics_capture",
+        label: "Graphics Capture Plugins",
+        group: "Capture",
+        description: "Installing graphics capture plugins...",
+        native: &["obs-vkcapture", "lib32-obs-vkcapture", "obs-gstreamer", "obs-vaapi"],
+        flatpak: &[
+            "com.obsproject.Studio.Plugin.OBSVkCapture",
+            "com.obsproject.Studio.Plugin.Gstreamer",
+            "com.obsproject.Studio.Plugin.GStreamerVaapi",
+        ],
+    },
+    ObsPluginSet {
+        id: "transitions_effects",
+        label: "Transitions & Effects",
+        group: "Transitions",
+        description: "Installing transitions & effects plugins...",
+        native: &["obs-move-transition", "obs-transition-table", "obs-scale-to-sound"],
+        flatpak: &[
+            "com.obsproject.Studio.Plugin.MoveTransition",
+            "com.obsproject.Studio.Plugin.TransitionTable",
+            "com.obsproject.Studio.Plugin.ScaleToSound",
+        ],
+    },
+    ObsPluginSet {
+        id: "streaming_tools",
+        label: "Streaming & Recording Tools",
+        group: "Streaming",
+        description: "Installing streaming & recording tools...",
+        native: &["obs-advanced-scene-switcher", "droidcam-obs"],
+        flatpak: &[
+            "com.obsproject.Studio.Plugin.SceneSwitcher",
+            "com.obsproject.Studio.Plugin.DroidCam",
+        ],
+    },
+    ObsPluginSet {
+        id: "audio_video_tools",
+        label: "Audio & Video Tools",
+        group: "Streaming",
+        description: "Installing audio/video enhancement plugins...",
+        native: &["obs-waveform", "obs-vertical-canvas", "obs-backgroundremoval"],
+        flatpak: &[
+            "com.obsproject.Studio.Plugin.waveform",
+            "com.obsproject.Studio.Plugin.BackgroundRemoval",
+        ],
+    },
+];
+
 fn setup_obs_studio_aio(page_builder: &Builder, window: &ApplicationWindow) {
     let btn_obs_studio_aio = extract_widget::<gtk4::Button>(page_builder, "btn_obs_studio_aio");
     let window = window.clone();
     btn_obs_studio_aio.connect_clicked(move |_| {
         info!("Multimedia tools: OBS-Studio AiO button clicked");
-        let window_ref = window.upcast_ref();
-
-        // State detection via pacman/-Q — all packages are native Arch/AUR.
-        // obs-websocket has been bundled with obs-studio since v28, so it is
-        // intentionally omitted as a standalone option.
-        let obs_installed = core::is_package_installed("obs-studio");
-
-        let graphics_capture_installed =
-            core::is_package_installed("obs-vkcapture") &&
-            core::is_package_installed("lib32-obs-vkcapture") &&
-            core::is_package_installed("obs-gstreamer") &&
-            core::is_package_installed("obs-vaapi");
-
-        let transitions_effects_installed =
-            core::is_package_installed("obs-move-transition") &&
-            core::is_package_installed("obs-transition-table") &&
-            core::is_package_installed("obs-scale-to-sound");
-
-        let streaming_tools_installed =
-            core::is_package_installed("obs-advanced-scene-switcher") &&
-            core::is_package_installed("droidcam-obs");
-
-        let audio_video_tools_installed =
-            core::is_package_installed("obs-waveform") &&
-            core::is_package_installed("obs-vertical-canvas") &&
-            core::is_package_installed("obs-backgroundremoval");
-
-        let v4l2_installed = core::is_package_installed("v4l2loopback-dkms");
 
+        let installed_note = |installed: bool| if installed { " (installed)" } else { "" };
         let config = SelectionDialogConfig::new(
-            "OBS-Studio & Plugins Installation",
-            "OBS-Studio will be installed from repos. Optionally select plugins to install.",
+            "OBS-Studio Installation",
+            "Choose how OBS-Studio and its plugins are installed.",
         )
-        .selection_type(SelectionType::Multi)
-        .selection_required(false)
-        .add_option(SelectionOption::new(
-            "graphics_capture",
-            "Graphics Capture Plugins",
-            "obs-vkcapture (32 & 64-bit), obs-gstreamer, obs-vaapi",
-            graphics_capture_installed,
-        ))
-        .add_option(SelectionOption::new(
-            "transitions_effects",
-            "Transitions & Effects",
-            "obs-move-transition, obs-transition-table, obs-scale-to-sound",
-            transitions_effects_installed,
-        ))
-        .add_option(SelectionOption::new(
-            "streaming_tools",
-            "Streaming & Recording Tools",
-            "obs-advanced-scene-switcher, droidcam-obs",
-            streaming_tools_installed,
-        ))
+        .selection_type(SelectionType::Single)
         .add_option(SelectionOption::new(
-            "audio_video_tools",
-            "Audio & Video Tools",
-            "obs-waveform, obs-vertical-canvas, obs-backgroundremoval",
-            audio_video_tools_installed,
+            "native",
+            "Native (pacman/AUR)",
+            &format!(
+                "Best system integration; plugins from the repos and AUR{}",
+                installed_note(core::is_package_installed("obs-studio"))
+            ),
+            false,
         ))
         .add_option(SelectionOption::new(
-            "v4l2",
-            "V4L2loopback Virtual Camera",
-            "Enable OBS virtual camera functionality",
-            v4l2_installed,
+            "flatpak",
+            "Flatpak",
+            &format!(
+                "Sandboxed build from Flathub; plugins as Flatpak extensions{}",
+                installed_note(core::is_flatpak_installed(OBS_FLATPAK, core::FlatpakKind::App))
+            ),
+            false,
         ))
-        .confirm_label(if obs_installed { "Update" } else { "Install" });
+        .confirm_label("Continue");
 
         let window_for_closure = window.clone();
-        show_selection_dialog(window_ref, config, move |selected_ids| {
-            let mut commands = CommandSequence::new();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let backend = if selected.iter().any(|s| s == "flatpak") {
+                ObsBackend::Flatpak
+            } else {
+                ObsBackend::Native
+            };
+            show_obs_plugins_dialog(&window_for_closure, backend);
+        });
+    });
+}
+
+fn show_obs_plugins_dialog(window: &ApplicationWindow, backend: ObsBackend) {
+    // Detection follows the chosen backend: pacman -Q for native packages,
+    // the Flatpak list for the sandboxed build and its extensions.
+    let is_installed = move |pkg: &str| match backend {
+        ObsBackend::Native => core::is_package_installed(pkg),
+        ObsBackend::Flatpak => core::is_flatpak_installed(pkg, core::FlatpakKind::Any),
+    };
+    let obs_installed = match backend {
+        ObsBackend::Native => is_installed("obs-studio"),
+        ObsBackend::Flatpak => is_installed(OBS_FLATPAK),
+    };
+    let source = match backend {
+        ObsBackend::Native => "repos",
+        ObsBackend::Flatpak => "Flathub",
+    };
+
+    let mut config = SelectionDialogConfig::new(
+        "OBS-Studio & Plugins Installation",
+        &format!(
+            "OBS-Studio will be installed from {}. Optionally select plugins to install.",
+            source
+        ),
+    )
+    .selection_type(SelectionType::Multi)
+    .selection_required(false)
+    .remember_as("obs_plugins");
+
+    for set in OBS_PLUGIN_SETS {
+        let packages = set.packages(backend);
+        let option = if packages.is_empty() {
+            SelectionOption::new(set.id, set.label, "", false)
+                .unavailable("Not packaged for this install method")
+        } else {
+            let installed = packages.iter().all(|&pkg| is_installed(pkg));
+            SelectionOption::new(set.id, set.label, &packages.join(", "), installed)
+                .installs(packages)
+        };
+        config = config.add_option(option.group(set.group));
+    }
+
+    // The virtual camera needs the host kernel module whichever way OBS
+    // itself is installed.
+    config = config
+        .add_option(
+            SelectionOption::new(
+                "v4l2",
+                "V4L2loopback Virtual Camera",
+                "Enable OBS virtual camera functionality",
+                core::is_package_installed("v4l2loopback-dkms"),
+            )
+            .installs(&["v4l2loopback-dkms", "v4l2loopback-utils"])
+            .group("Capture"),
+        )
+        .confirm_label(if obs_installed { "Update" } else { "Install" });
+
+    let window_for_closure = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let mut commands = CommandSequence::new();
 
-            // Always install / refresh obs-studio from repos
+        // Always install / refresh OBS itself
+        commands = commands.then(match backend {
+            ObsBackend::Native => Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "obs-studio"])
+                .description("Installing OBS-Studio...")
+                .build(),
+            ObsBackend::Flatpak => Command::builder()
+                .flatpak_install()
+                .args(&[OBS_FLATPAK])
+                .description("Installing OBS-Studio (Flatpak)...")
+                .build(),
+        });
+
+        for set in OBS_PLUGIN_SETS {
+            let packages = set.packages(backend);
+            if packages.is_empty() || !selected.iter().any(|s| s == set.id) {
+                continue;
+            }
+            let step = match backend {
+                ObsBackend::Native => {
+                    let mut args = vec!["-S", "--noconfirm", "--needed"];
+                    args.extend_from_slice(packages);
+                    Command::builder().aur().args(&args)
+                }
+                ObsBackend::Flatpak => Command::builder().flatpak_install().args(packages),
+            };
+            commands = commands.then(step.description(set.description).build());
+        }
+
+        if selected.iter().any(|s| s == "v4l2") {
             commands = commands.then(
                 Command::builder()
                     .aur()
-                    .args(&["-S", "--noconfirm", "--needed", "obs-studio"])
-                    .description("Installing OBS-Studio...")
+                    .args(&["-S", "--noconfirm", "--needed", "v4l2loopback-dkms", "v4l2loopback-utils"])
+                    .description("Installing V4L2 loopback modules...")
                     .build(),
             );
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        "echo 'v4l2loopback' > /etc/modules-load.d/v4l2loopback-cyberxero.conf",
+                    ])
+                    .description("Enabling V4L2 loopback module at boot...")
+                    .build(),
+            );
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        // Leave options the user already set for the module alone;
+                        // otherwise write them to our own drop-in.
+                        "grep -qs '^options v4l2loopback' /etc/modprobe.d/*.conf || \
+                         echo 'options v4l2loopback exclusive_caps=1 card_label=\"OBS Virtual Camera\"' \
+                         > /etc/modprobe.d/v4l2loopback-cyberxero.conf",
+                    ])
+                    .description("Configuring virtual camera options...")
+                    .build(),
+            );
+        }
 
-            if selected_ids.iter().any(|s| s == "graphics_capture") {
-                commands = commands.then(
-                    Command::builder()
-                        .aur()
-                        .args(&[
-                            "-S", "--noconfirm", "--needed",
-                            "obs-vkcapture",
-                            "lib32-obs-vkcapture",
-                            "obs-gstreamer",
-                            "obs-vaapi",
-                        ])
-                        .description("Installing graphics capture plugins...")
-                        .build(),
-                );
-            }
+        task_runner::run(window_for_closure.upcast_ref(), commands.build(), "OBS-Studio Setup");
+    });
+}
 
-            if selected_ids.iter().any(|s| s == "transitions_effects") {
-                commands = commands.then(
-                    Command::builder()
-                        .aur()
-                        .args(&[
-                            "-S", "--noconfirm", "--needed",
-                            "obs-move-transition",
-                            "obs-transition-table",
-                            "obs-scale-to-sound",
-                        ])
-                        .description("Installing transitions & effects plugins...")
-                        .build(),
-                );
-            }
+fn setup_kdenlive(page_builder: &Builder, window: &ApplicationWindow) {
+    let btn_kdenlive = extract_widget::<gtk4::Button>(page_builder, "btn_kdenlive");
+    let window = window.clone();
+    btn_kdenlive.connect_clicked(move |_| {
+        info!("Multimedia tools: Kdenlive button clicked");
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "kdenlive"])
+                    .description("Installing Kdenlive...")
+                    .build(),
+            )
+            .build();
 
-            if selected_ids.iter().any(|s| s == "streaming_tools") {
-                commands = commands.then(
-                    Command::builder()
-                        .aur()
-                        .args(&[
-                            "-S", "--noconfirm", "--needed",
-                            "obs-advanced-scene-switcher",
-                            "droidcam-obs",
-                        ])
-                        .description("Installing streaming & recording tools...")
-                        .build(),
-                );
+        task_runner::run(window.upcast_ref(), commands, "Kdenlive Installation");
+    });
+}
+
+const RESOLVE_LABEL: &str = "DaVinci Resolve";
+const RESOLVE_FREE: &str = "davinci-resolve";
+const RESOLVE_STUDIO: &str = "davinci-resolve-studio";
+
+/// Libraries the AUR packages link against but don't always pull in;
+/// installing them first avoids the most common mid-build failures.
+const RESOLVE_RUNTIME_DEPS: &[&str] = &["glu", "gtk2", "libxcrypt-compat", "ocl-icd", "qt5-x11extras"];
+
+/// GPU compute runtime Resolve renders with. NVIDIA's CUDA driver library
+/// ships in nvidia-utils, so the full CUDA toolkit isn't needed.
+fn resolve_gpu_packages() -> Vec<&'static str> {
+    let mut packages = Vec::new();
+    for vendor in core::gpu::detect_gpu_vendors() {
+        packages.extend(match vendor {
+            GpuVendor::Nvidia => ["nvidia-utils", "opencl-nvidia"].as_slice(),
+            GpuVendor::Amd => &["rocm-opencl-runtime"],
+            GpuVendor::Intel => &["intel-compute-runtime"],
+        });
+    }
+    packages
+}
+
+fn is_resolve_installed() -> bool {
+    core::is_package_installed(RESOLVE_FREE) || core::is_package_installed(RESOLVE_STUDIO)
+}
+
+fn async_refresh_resolve(button: gtk4::Button) {
+    let (tx, rx) = async_channel::bounded::<bool>(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking(is_resolve_installed());
+    });
+    glib::MainContext::default().spawn_local(async move {
+        if let Ok(installed) = rx.recv().await {
+            if installed {
+                button.set_label(&format!("{} ✓", RESOLVE_LABEL));
+            } else {
+                button.set_label(RESOLVE_LABEL);
             }
+        }
+    });
+}
+
+fn setup_davinci_resolve(page_builder: &Builder, window: &ApplicationWindow) {
+    let btn_resolve = extract_widget::<gtk4::Button>(page_builder, "btn_davinci_resolve");
+    async_refresh_resolve(btn_resolve.clone());
+
+    let btn_clone = btn_resolve.clone();
+    on_refocus(window, move || {
+        async_refresh_resolve(btn_clone.clone());
+    });
 
-            if selected_ids.iter().any(|s| s == "audio_video_tools") {
+    let window = window.clone();
+    btn_resolve.connect_clicked(move |_| {
+        info!("Multimedia tools: DaVinci Resolve button clicked");
+        let studio_installed = core::is_package_installed(RESOLVE_STUDIO);
+        let config = SelectionDialogConfig::new(
+            "DaVinci Resolve Installation",
+            "Resolve is built from the AUR and downloads about 3 GB from Blackmagic Design. \
+             Choose the edition you are licensed for.",
+        )
+        .selection_type(SelectionType::Single)
+        .add_option(
+            SelectionOption::new(
+                "free",
+                "DaVinci Resolve (Free)",
+                "No license needed; no H.264/H.265 GPU decoding or AAC audio on Linux",
+                core::is_package_installed(RESOLVE_FREE),
+            )
+            .preselected(!studio_installed),
+        )
+        .add_option(
+            SelectionOption::new(
+                "studio",
+                "DaVinci Resolve Studio",
+                "Paid edition; requires a license key or USB dongle to activate",
+                studio_installed,
+            )
+            .preselected(studio_installed)
+            .details(
+                "Studio adds hardware-accelerated H.264/H.265 decoding and encoding, \
+                 noise reduction and the remaining effects. It installs without a key \
+                 but will not start until activated with a license bought from \
+                 Blackmagic Design. Free and Studio conflict; picking one replaces the other.",
+            ),
+        )
+        .confirm_label("Install");
+
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let (package, other) = if selected.iter().any(|s| s == "studio") {
+                (RESOLVE_STUDIO, RESOLVE_FREE)
+            } else {
+                (RESOLVE_FREE, RESOLVE_STUDIO)
+            };
+
+            let mut deps = vec!["-S", "--noconfirm", "--needed"];
+            deps.extend_from_slice(RESOLVE_RUNTIME_DEPS);
+            deps.extend(resolve_gpu_packages());
+
+            // The two editions conflict, and --noconfirm declines the swap.
+            let mut commands = CommandSequence::new();
+            if core::is_package_installed(other) {
                 commands = commands.then(
                     Command::builder()
                         .aur()
-                        .args(&[
-                            "-S", "--noconfirm", "--needed",
-                            "obs-waveform",
-                            "obs-vertical-canvas",
-                            "obs-backgroundremoval",
-                        ])
-                        .description("Installing audio/video enhancement plugins...")
+                        .args(&["-R", "--noconfirm", other])
+                        .description("Removing the other Resolve edition...")
                         .build(),
                 );
             }
 
-            if selected_ids.iter().any(|s| s == "v4l2") {
-                commands = commands.then(
+            // Build logs are kept so a failed dependency is visible afterwards.
+            let commands = commands
+                .then(
                     Command::builder()
                         .aur()
-                        .args(&["-S", "--noconfirm", "--needed", "v4l2loopback-dkms", "v4l2loopback-utils"])
-                        .description("Installing V4L2 loopback modules...")
+                        .args(&deps)
+                        .description("Installing Resolve runtime and GPU compute libraries...")
+                        .capture_output(true)
                         .build(),
-                );
-                commands = commands.then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&["-c", "echo 'v4l2loopback' > /etc/modules-load.d/v4l2loopback.conf"])
-                        .description("Enabling V4L2 loopback module at boot...")
-                        .build(),
-                );
-                commands = commands.then(
+                )
+                .then(
                     Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "echo 'options v4l2loopback exclusive_caps=1 card_label=\"OBS Virtual Camera\"' > /etc/modprobe.d/v4l2loopback.conf",
-                        ])
-                        .description("Configuring virtual camera options...")
+                        .aur()
+                        .args(&["-S", "--noconfirm", "--needed", package])
+                        .description("Building and installing DaVinci Resolve...")
+                        .capture_output(true)
                         .build(),
-                );
-            }
+                )
+                .build();
 
-            task_runner::run(window_for_closure.upcast_ref(), commands.build(), "OBS-Studio Setup");
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                commands,
+                "DaVinci Resolve Installation",
+            );
         });
     });
 }
 
-fn setup_kdenlive(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_kdenlive = extract_widget::<gtk4::Button>(page_builder, "btn_kdenlive");
-    let window = window.clone();
-    btn_kdenlive.connect_clicked(move |_| {
-        info!("Multimedia tools: Kdenlive button clicked");
-        let commands = CommandSequence::new()
-            .then(
+const JELLYFIN_LABEL: &str = "Jellyfin Server";
+const JELLYFIN_SERVICE: &str = "jellyfin.service";
+const JELLYFIN_PORT: &str = "8096";
+
+/// Host firewalls the toolkit knows how to open a port in.
+#[derive(Clone, Copy)]
+enum Firewall {
+    Ufw,
+    Firewalld,
+}
+
+impl Firewall {
+    fn name(self) -> &'static str {
+        match self {
+            Firewall::Ufw => "UFW",
+            Firewall::Firewalld => "firewalld",
+        }
+    }
+
+    fn open_port_steps(self) -> Vec<Command> {
+        let port = format!("{}/tcp", JELLYFIN_PORT);
+        match self {
+            Firewall::Ufw => vec![Command::builder()
+                .privileged()
+                .program("ufw")
+                .args(&["allow", &port])
+                .description("Opening the Jellyfin port in UFW...")
+                .build()],
+            Firewall::Firewalld => vec![
                 Command::builder()
-                    .aur()
-                    .args(&["-S", "--noconfirm", "--needed", "kdenlive"])
-                    .description("Installing Kdenlive...")
+                    .privileged()
+                    .program("firewall-cmd")
+                    .args(&["--permanent", &format!("--add-port={}", port)])
+                    .description("Opening the Jellyfin port in firewalld...")
                     .build(),
-            )
-            .build();
+                Command::builder()
+                    .privileged()
+                    .program("firewall-cmd")
+                    .args(&["--reload"])
+                    .description("Reloading firewalld...")
+                    .build(),
+            ],
+        }
+    }
+}
 
-        task_runner::run(window.upcast_ref(), commands, "Kdenlive Installation");
+/// The running firewall, if any. Installed-but-stopped ones don't block
+/// anything, so they are left alone.
+fn active_firewall() -> Option<Firewall> {
+    if is_service_active("ufw.service") {
+        Some(Firewall::Ufw)
+    } else if is_service_active("firewalld.service") {
+        Some(Firewall::Firewalld)
+    } else {
+        None
+    }
+}
+
+/// Source address of the default route: the IP other devices on the LAN
+/// reach this machine at. The target is never contacted.
+fn primary_lan_ip() -> Option<String> {
+    let route = run_command("ip", &["-4", "route", "get", "1.1.1.1"])?;
+    let mut words = route.split_whitespace();
+    words.find(|w| *w == "src")?;
+    words.next().map(str::to_owned)
+}
+
+/// Installed-and-running gets the ✓; installed but stopped offers a start.
+fn update_jellyfin_button(button: &gtk4::Button, installed: bool, running: bool) {
+    match (installed, running) {
+        (true, true) => {
+            button.set_label(&format!("{} ✓", JELLYFIN_LABEL));
+            button.set_sensitive(false);
+            button.remove_css_class("suggested-action");
+            button.add_css_class("dim-label");
+        }
+        (true, false) => {
+            button.set_label("Start Jellyfin");
+            button.set_sensitive(true);
+            button.add_css_class("suggested-action");
+            button.remove_css_class("dim-label");
+        }
+        (false, _) => {
+            button.set_label(JELLYFIN_LABEL);
+            button.set_sensitive(true);
+            button.add_css_class("suggested-action");
+            button.remove_css_class("dim-label");
+        }
+    }
+}
+
+/// Check package and service off the main thread, then update the button.
+fn async_refresh_jellyfin(button: gtk4::Button) {
+    let (tx, rx) = async_channel::bounded::<(bool, bool)>(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking((
+            core::is_package_installed("jellyfin-server"),
+            is_service_active(JELLYFIN_SERVICE),
+        ));
+    });
+    glib::MainContext::default().spawn_local(async move {
+        if let Ok((installed, running)) = rx.recv().await {
+            update_jellyfin_button(&button, installed, running);
+        }
     });
 }
 
 fn setup_jellyfin(page_builder: &Builder, window: &ApplicationWindow) {
     let btn_jellyfin = extract_widget::<gtk4::Button>(page_builder, "btn_jellyfin");
+    async_refresh_jellyfin(btn_jellyfin.clone());
+
+    let btn_clone = btn_jellyfin.clone();
+    on_refocus(window, move || {
+        async_refresh_jellyfin(btn_clone.clone());
+    });
+
     let window = window.clone();
     btn_jellyfin.connect_clicked(move |_| {
         info!("Multimedia tools: Jellyfin button clicked");
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .aur()
-                    .args(&[
-                        "-S",
-                        "--noconfirm",
-                        "--needed",
-                        "jellyfin-server",
-                        "jellyfin-web",
-                        "jellyfin-ffmpeg",
-                    ])
-                    .description("Installing Jellyfin server and components...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["enable", "--now", "jellyfin.service"])
-                    .description("Starting Jellyfin service...")
-                    .build(),
+        let Some(firewall) = active_firewall() else {
+            run_jellyfin_setup(&window, None);
+            return;
+        };
+
+        let config = SelectionDialogConfig::new(
+            "Jellyfin Server Setup",
+            &format!(
+                "{} is active and will block other devices from reaching Jellyfin.",
+                firewall.name()
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .add_option(
+            SelectionOption::new(
+                "open_port",
+                &format!("Open port {}/tcp", JELLYFIN_PORT),
+                "Allow phones, TVs and other computers on your network to connect",
+                false,
             )
-            .build();
+            .preselected(true),
+        )
+        .confirm_label("Continue");
 
-        task_runner::run(window.upcast_ref(), commands, "Jellyfin Server Setup");
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let open_port = selected.iter().any(|s| s == "open_port");
+            run_jellyfin_setup(&window_for_closure, open_port.then_some(firewall));
+        });
     });
 }
 
+fn run_jellyfin_setup(window: &ApplicationWindow, open_port: Option<Firewall>) {
+    let mut commands = CommandSequence::new();
+    if !core::is_package_installed("jellyfin-server") {
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "jellyfin-server",
+                    "jellyfin-web",
+                    "jellyfin-ffmpeg",
+                ])
+                .description("Installing Jellyfin server and components...")
+                .build(),
+        );
+    }
+    commands = commands.then(
+        Command::builder()
+            .privileged()
+            .program("systemctl")
+            .args(&["enable", "--now", JELLYFIN_SERVICE])
+            .description("Starting Jellyfin service...")
+            .build(),
+    );
+    if let Some(firewall) = open_port {
+        for step in firewall.open_port_steps() {
+            commands = commands.then(step);
+        }
+    }
+
+    let local = format!("http://localhost:{}", JELLYFIN_PORT);
+    let note = match primary_lan_ip() {
+        Some(ip) => format!(
+            "Jellyfin is ready at http://{}:{} (or {} on this computer).",
+            ip, JELLYFIN_PORT, local
+        ),
+        None => format!("Jellyfin is ready at {}.", local),
+    };
+
+    task_runner::run(
+        window.upcast_ref(),
+        commands.completion_note(&note).build(),
+        "Jellyfin Server Setup",
+    );
+}
+
 fn setup_gpu_screen_recorder(page_builder: &Builder, window: &ApplicationWindow) {
     let btn_gpu_screen_recorder =
         extract_widget::<gtk4::Button>(page_builder, "btn_gpu_screen_recorder");
@@ -373,189 +826,538 @@ fn setup_gpu_screen_recorder(page_builder: &Builder, window: &ApplicationWindow)
     });
 }
 
+/// A single-purpose app offered natively or from Flathub, with a matching
+/// uninstall button that removes whichever variant is present.
+struct QuickApp {
+    label: &'static str,
+    button: &'static str,
+    uninstall_button: &'static str,
+    packages: &'static [&'static str],
+    flatpak: &'static str,
+}
+
+const QUICK_APPS: &[QuickApp] = &[
+    QuickApp {
+        label: "HandBrake",
+        button: "btn_handbrake",
+        uninstall_button: "btn_handbrake_uninstall",
+        packages: &["handbrake", "handbrake-cli"],
+        flatpak: "fr.handbrake.ghb",
+    },
+    QuickApp {
+        label: "Audacity",
+        button: "btn_audacity",
+        uninstall_button: "btn_audacity_uninstall",
+        packages: &["audacity"],
+        flatpak: "org.audacityteam.Audacity",
+    },
+];
+
+fn async_refresh_quick_apps(apps: &[(Button, Button, &'static QuickApp)]) {
+    refresh_button_states(
+        apps.iter()
+            .map(|(install, uninstall, app)| {
+                (
+                    install.clone(),
+                    uninstall.clone(),
+                    InstallCheck::PackageOrFlatpak(app.packages[0], app.flatpak),
+                    app.label,
+                )
+            })
+            .collect(),
+    );
+}
+
+fn setup_quick_app(
+    builder: &Builder,
+    window: &ApplicationWindow,
+    app: &'static QuickApp,
+) -> (Button, Button) {
+    let btn_install = extract_widget::<Button>(builder, app.button);
+    let btn_uninstall = extract_widget::<Button>(builder, app.uninstall_button);
+
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Multimedia tools: {} button clicked", app.label);
+
+        let config = SelectionDialogConfig::new(
+            &format!("{} Installation", app.label),
+            &format!("Choose how {} is installed.", app.label),
+        )
+        .selection_type(SelectionType::Single)
+        .add_option(SelectionOption::new(
+            "native",
+            "Native (pacman)",
+            &app.packages.join(", "),
+            false,
+        ))
+        .add_option(SelectionOption::new(
+            "flatpak",
+            "Flatpak",
+            "Sandboxed build from Flathub",
+            false,
+        ))
+        .confirm_label("Install");
+
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let step = if selected.iter().any(|s| s == "flatpak") {
+                Command::builder()
+                    .flatpak_install()
+                    .args(&[app.flatpak])
+                    .description(&format!("Installing {} from Flathub...", app.label))
+                    .build()
+            } else {
+                let mut args = vec!["-S", "--noconfirm", "--needed"];
+                args.extend_from_slice(app.packages);
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description(&format!("Installing {}...", app.label))
+                    .build()
+            };
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                CommandSequence::new().then(step).build(),
+                &format!("{} Installation", app.label),
+            );
+        });
+    });
+
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Multimedia tools: {} uninstall button clicked", app.label);
+
+        let mut commands = CommandSequence::new();
+        let installed: Vec<&str> = app
+            .packages
+            .iter()
+            .copied()
+            .filter(|pkg| core::is_package_installed(pkg))
+            .collect();
+        if !installed.is_empty() {
+            let mut args = vec!["-Rns", "--noconfirm"];
+            args.extend(installed);
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description(&format!("Removing {}...", app.label))
+                    .build(),
+            );
+        }
+        if core::is_flatpak_installed(app.flatpak, core::FlatpakKind::App) {
+            commands = commands.then(
+                Command::builder()
+                    .flatpak_uninstall()
+                    .args(&[app.flatpak])
+                    .description(&format!("Removing {} (Flatpak)...", app.label))
+                    .build(),
+            );
+        }
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands.build(),
+            &format!("{} Uninstall", app.label),
+        );
+    });
+
+    (btn_install, btn_uninstall)
+}
+
+/// A browser flatpak that can host the streaming kiosk web apps.
+struct WebAppBrowser {
+    /// Flatpak app id, also used as the launcher icon.
+    app_id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    /// Flags placed before the URL to open it fullscreen without browser UI.
+    kiosk_args: &'static str,
+}
+
+const CHROMIUM_KIOSK_ARGS: &str = "--kiosk --start-fullscreen --force-device-scale-factor=1.5";
+
+const WEB_APP_BROWSERS: &[WebAppBrowser] = &[
+    WebAppBrowser {
+        app_id: "com.google.Chrome",
+        name: "Google Chrome",
+        description: "Widevine DRM works out of the box for every service",
+        kiosk_args: CHROMIUM_KIOSK_ARGS,
+    },
+    WebAppBrowser {
+        app_id: "com.brave.Browser",
+        name: "Brave",
+        description: "Chromium-based with built-in ad and tracker blocking",
+        kiosk_args: CHROMIUM_KIOSK_ARGS,
+    },
+    WebAppBrowser {
+        app_id: "org.chromium.Chromium",
+        name: "Chromium",
+        description: "Open-source Chromium; DRM-protected services may not play",
+        kiosk_args: CHROMIUM_KIOSK_ARGS,
+    },
+    WebAppBrowser {
+        // Firefox has no scale-factor or separate fullscreen switch;
+        // --kiosk alone gives a chromeless fullscreen window.
+        app_id: "org.mozilla.firefox",
+        name: "Firefox",
+        description: "Non-Chromium; uses your Firefox profile for logins",
+        kiosk_args: "--kiosk",
+    },
+];
+
+/// Directory the kiosk `.desktop` files live in. SteamOS only picks up
+/// `~/Applications` for non-Steam shortcuts.
+fn web_apps_dir(is_steamos: bool) -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    if is_steamos {
+        format!("{}/Applications", home)
+    } else {
+        format!("{}/.local/share/applications", home)
+    }
+}
+
+/// Whether `desktop_path` is a web app launched through `browser`. The file
+/// name doesn't say which browser wrote it, so look at the Exec line.
+fn is_web_app_installed(desktop_path: &str, browser: &WebAppBrowser) -> bool {
+    std::fs::read_to_string(desktop_path)
+        .map(|entry| entry.contains(&format!(" {} ", browser.app_id)))
+        .unwrap_or(false)
+}
+
 fn setup_streaming_services(page_builder: &Builder, window: &ApplicationWindow) {
     let btn_streaming = extract_widget::<gtk4::Button>(page_builder, "btn_streaming_services");
     let window = window.clone();
 
     btn_streaming.connect_clicked(move |_| {
         info!("Multimedia tools: Streaming Services button clicked");
-        let window_ref = window.upcast_ref();
 
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let is_steamos = std::path::Path::new("/usr/bin/steamos-add-to-steam").exists();
-        if is_steamos {
-            info!("Handheld device detected");
+        let last_used = core::settings::web_app_browser();
+        let mut config = SelectionDialogConfig::new(
+            "Web App Browser",
+            "Choose the browser the streaming web apps open in.
+             Its Flatpak will be installed if needed.",
+        )
+        .selection_type(SelectionType::Single)
+        .confirm_label("Continue");
+
+        for browser in WEB_APP_BROWSERS {
+            let description = if last_used.as_deref() == Some(browser.app_id) {
+                format!("{} (last used)", browser.description)
+            } else {
+                browser.description.to_string()
+            };
+            config = config.add_option(SelectionOption::new(
+                browser.app_id,
+                browser.name,
+                &description,
+                false,
+            ));
         }
 
-        let apps_dir = if is_steamos {
-            format!("{}/Applications", home)
-        } else {
-            format!("{}/.local/share/applications", home)
-        };
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let Some(browser) = selected
+                .first()
+                .and_then(|id| WEB_APP_BROWSERS.iter().find(|b| b.app_id == id.as_str()))
+            else {
+                return;
+            };
+            core::settings::set_web_app_browser(browser.app_id);
+            show_streaming_services_dialog(&window_for_closure, browser);
+        });
+    });
+}
 
-        let dialog_desc = if is_steamos {
-            "Select services to add as fullscreen Chrome kiosk web apps.\n\
-             Flatpak Google Chrome will be installed if needed.\n\
-             Handheld device detected — selected apps will be added to Steam."
-        } else {
-            "Select services to add as fullscreen Chrome kiosk web apps.\n\
-             Flatpak Google Chrome will be installed if needed."
-        };
+fn show_streaming_services_dialog(window: &ApplicationWindow, browser: &'static WebAppBrowser) {
+    let is_steamos = core::distro::is_steamos();
+    if is_steamos {
+        info!("Handheld device detected");
+    }
+    let apps_dir = web_apps_dir(is_steamos);
+
+    let mut dialog_desc = format!(
+        "Select services to add as fullscreen {} kiosk web apps; uncheck one to remove it.\n\
+         Flatpak {} will be installed if needed.",
+        browser.name, browser.name
+    );
+    if is_steamos {
+        dialog_desc.push_str(
+            "\nHandheld device detected — selected apps will be added to Steam. \
+             Remove shortcuts of unchecked apps in Steam (Manage → Remove non-Steam game).",
+        );
+    }
 
-        let mut config = SelectionDialogConfig::new(
-            "Streaming Service Web Apps",
-            dialog_desc,
-        )
+    let mut config = SelectionDialogConfig::new("Streaming Service Web Apps", &dialog_desc)
         .selection_type(SelectionType::Multi)
-        .selection_required(true)
-        .confirm_label("Add Selected");
-
-        for (name, _url) in STREAMING_SERVICES {
-            let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
-            let installed = std::path::Path::new(&desktop_path).exists();
-            config = config.add_option(SelectionOption::new(name, name, "", installed));
+        .selection_required(false)
+        .allow_removal(true)
+        .remember_as("streaming_web_apps")
+        .confirm_label("Apply");
+
+    // Only web apps this toolkit wrote for `browser` count as installed, so
+    // only those can be removed below.
+    let mut installed: Vec<&'static str> = Vec::new();
+    for (name, _url, group) in STREAMING_SERVICES {
+        let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
+        let is_installed = is_web_app_installed(&desktop_path, browser);
+        if is_installed {
+            installed.push(name);
         }
+        config =
+            config.add_option(SelectionOption::new(name, name, "", is_installed).group(group));
+    }
 
-        let window_for_closure = window.clone();
-        show_selection_dialog(window_ref, config, move |selected_ids| {
-            if selected_ids.is_empty() {
+    let (custom_box, custom_name, custom_url) = build_custom_web_app_inputs();
+    config = config.extra_widget(&custom_box);
+
+    let window_for_closure = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |checked_ids| {
+        let mut apps: Vec<(String, String)> = checked_ids
+            .iter()
+            .filter(|id| !installed.contains(&id.as_str()))
+            .filter_map(|id| STREAMING_SERVICES.iter().find(|(n, _, _)| *n == id.as_str()))
+            .map(|(name, url, _)| (name.to_string(), url.to_string()))
+            .collect();
+        match custom_web_app(&custom_name.text(), &custom_url.text()) {
+            Ok(Some(app)) => apps.push(app),
+            Ok(None) => {}
+            Err(msg) => {
+                crate::ui::dialogs::error::show_error(&window_for_closure, msg);
                 return;
             }
+        }
+        let removed: Vec<String> = installed
+            .iter()
+            .filter(|name| !checked_ids.iter().any(|id| id.as_str() == **name))
+            .map(|name| format!("{}/{}.desktop", apps_dir, sanitize_filename(name)))
+            .collect();
+        if apps.is_empty() && removed.is_empty() {
+            return;
+        }
 
-            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-            let is_steamos = std::path::Path::new("/usr/bin/steamos-add-to-steam").exists();
+        let mut commands = CommandSequence::new();
 
-            let apps_dir = if is_steamos {
-                format!("{}/Applications", home)
-            } else {
-                format!("{}/.local/share/applications", home)
-            };
+        if !removed.is_empty() {
+            let mut args = vec!["-f".to_string()];
+            args.extend(removed.iter().cloned());
+            let refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            commands = commands.then(
+                Command::builder()
+                    .normal()
+                    .program("rm")
+                    .args(&refs)
+                    .description(&format!(
+                        "Removing {} streaming service web app(s)...",
+                        removed.len()
+                    ))
+                    .build(),
+            );
+        }
 
-            let mut commands = CommandSequence::new();
+        if !apps.is_empty() {
+            commands = web_app_install_steps(commands, browser, &apps, &apps_dir, is_steamos);
+        }
 
-            // Install Chrome flatpak if not present
-            if !core::is_flatpak_installed("com.google.Chrome") {
-                commands = commands.then(
-                    Command::builder()
-                        .normal()
-                        .program("flatpak")
-                        .args(&["install", "-y", "com.google.Chrome"])
-                        .description("Installing Google Chrome (Flatpak)...")
-                        .build(),
-                );
-            }
+        task_runner::run(
+            window_for_closure.upcast_ref(),
+            commands.build(),
+            "Streaming Services Setup",
+        );
+    });
+}
 
-            // Flatpak overrides: udev for controller support (always)
-            // + ~/Applications filesystem access on SteamOS
-            if is_steamos {
-                commands = commands.then(
-                    Command::builder()
-                        .normal()
-                        .program("flatpak")
-                        .args(&[
-                            "override",
-                            "--user",
-                            "--filesystem=/run/udev:ro",
-                            &format!("--filesystem={}/Applications", home),
-                            "com.google.Chrome",
-                        ])
-                        .description("Handheld device detected, configuring Chrome permissions...")
-                        .build(),
-                );
-            } else {
-                commands = commands.then(
-                    Command::builder()
-                        .normal()
-                        .program("flatpak")
-                        .args(&[
-                            "override",
-                            "--user",
-                            "--filesystem=/run/udev:ro",
-                            "com.google.Chrome",
-                        ])
-                        .description("Configuring Chrome controller permissions...")
-                        .build(),
-                );
-            }
+/// Name and URL inputs for one extra, user-defined web app.
+fn build_custom_web_app_inputs() -> (gtk4::Box, gtk4::Entry, gtk4::Entry) {
+    let container = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    container.set_margin_start(12);
+    container.set_margin_end(12);
+    container.set_margin_top(8);
+    container.set_margin_bottom(8);
+
+    let heading = gtk4::Label::new(Some("Custom Web App"));
+    heading.set_css_classes(&["heading"]);
+    heading.set_halign(gtk4::Align::Start);
+
+    let name = gtk4::Entry::new();
+    name.set_placeholder_text(Some("Name, e.g. My Dashboard"));
+    let url = gtk4::Entry::new();
+    url.set_placeholder_text(Some("https://example.com"));
+    url.set_input_purpose(gtk4::InputPurpose::Url);
+
+    container.append(&heading);
+    container.append(&name);
+    container.append(&url);
+    (container, name, url)
+}
 
-            // Build a single shell script that creates all selected .desktop files
-            let mut script_parts = vec![format!("mkdir -p '{}'", apps_dir)];
-
-            for selected_name in &selected_ids {
-                if let Some((name, url)) = STREAMING_SERVICES
-                    .iter()
-                    .find(|(n, _)| *n == selected_name.as_str())
-                {
-                    let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
-                    script_parts.push(format!(
-                        concat!(
-                            "printf '%s\\n' ",
-                            "'[Desktop Entry]' ",
-                            "'Name={}' ",
-                            "'Type=Application' ",
-                            "'Icon=com.google.Chrome' ",
-                            "'Exec=/usr/bin/flatpak run --branch=stable --arch=x86_64 ",
-                            "com.google.Chrome --kiosk --start-fullscreen ",
-                            "--force-device-scale-factor=1.5 \"{}\"' ",
-                            "'Categories=Network;WebBrowser;' ",
-                            "> '{}' && chmod 0644 '{}'"
-                        ),
-                        name, url, desktop_path, desktop_path
-                    ));
-                }
-            }
+/// Validate the custom web app fields. Both empty means "none"; otherwise
+/// the name is reduced to safe characters and the URL must be http(s).
+fn custom_web_app(name: &str, url: &str) -> Result<Option<(String, String)>, &'static str> {
+    if name.trim().is_empty() && url.trim().is_empty() {
+        return Ok(None);
+    }
 
-            let full_script = script_parts.join(" && ");
-            let desc = format!(
-                "Creating {} streaming service web app(s)...",
-                selected_ids.len()
-            );
+    let name = sanitize_app_name(name);
+    if name.is_empty() {
+        return Err("Give the custom web app a name using letters or numbers.");
+    }
+
+    let url = reqwest::Url::parse(url.trim())
+        .map_err(|_| "The custom web app URL is not a valid address.")?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err("The custom web app URL must start with http:// or https://.");
+    }
+    Ok(Some((name, url.to_string())))
+}
+
+/// Keep only characters that are safe in a file name and a `.desktop` value.
+fn sanitize_app_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || matches!(*c, ' ' | '-' | '_' | '+' | '&' | '(' | ')'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Lines of the kiosk `.desktop` file opening `url` in `browser`.
+fn desktop_entry(name: &str, browser: &WebAppBrowser, url: &str) -> Vec<String> {
+    vec![
+        String::from("[Desktop Entry]"),
+        format!("Name={}", name.replace(['\n', '\r'], " ")),
+        String::from("Type=Application"),
+        format!("Icon={}", browser.app_id),
+        format!(
+            "Exec=/usr/bin/flatpak run --branch=stable --arch=x86_64 {} {} {}",
+            browser.app_id,
+            browser.kiosk_args,
+            desktop_exec_quote(url)
+        ),
+        String::from("Categories=Network;WebBrowser;"),
+    ]
+}
+
+/// Append the steps that install `browser`, grant it controller access and
+/// write a kiosk `.desktop` file per selected service.
+fn web_app_install_steps(
+    mut commands: CommandSequence,
+    browser: &WebAppBrowser,
+    apps: &[(String, String)],
+    apps_dir: &str,
+    is_steamos: bool,
+) -> CommandSequence {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+
+    // Install the browser flatpak if not present
+    if !core::is_flatpak_installed(browser.app_id, core::FlatpakKind::App) {
+        commands = commands.then(
+            Command::builder()
+                .flatpak_install()
+                .args(&[browser.app_id])
+                .description(&format!("Installing {} (Flatpak)...", browser.name))
+                .build(),
+        );
+    }
+
+    // Flatpak overrides: udev for controller support (always)
+    // + ~/Applications filesystem access on SteamOS
+    if is_steamos {
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&[
+                    "override",
+                    "--user",
+                    "--filesystem=/run/udev:ro",
+                    &format!("--filesystem={}/Applications", home),
+                    browser.app_id,
+                ])
+                .description(&format!(
+                    "Handheld device detected, configuring {} permissions...",
+                    browser.name
+                ))
+                .build(),
+        );
+    } else {
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&[
+                    "override",
+                    "--user",
+                    "--filesystem=/run/udev:ro",
+                    browser.app_id,
+                ])
+                .description(&format!(
+                    "Configuring {} controller permissions...",
+                    browser.name
+                ))
+                .build(),
+        );
+    }
 
+    // Build a single shell script that creates all selected .desktop files.
+    // Every interpolated value goes through `shell_quote`.
+    let mut script_parts = vec![format!("mkdir -p {}", shell_quote(apps_dir))];
+
+    for (name, url) in apps {
+        let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
+        let lines: Vec<String> = desktop_entry(name, browser, url)
+            .iter()
+            .map(|line| shell_quote(line))
+            .collect();
+        script_parts.push(format!(
+            "printf '%s\\n' {} > {path} && chmod 0644 {path}",
+            lines.join(" "),
+            path = shell_quote(&desktop_path)
+        ));
+    }
+
+    let full_script = script_parts.join(" && ");
+    let desc = format!(
+        "Creating {} streaming service web app(s)...",
+        apps.len()
+    );
+
+    commands = commands.then(
+        Command::builder()
+            .normal()
+            .program("sh")
+            .args(&["-c", &full_script])
+            .description(&desc)
+            .build(),
+    );
+
+    // On SteamOS, add each .desktop file to Steam
+    if is_steamos {
+        let mut steam_parts = Vec::new();
+        for (name, _url) in apps {
+            let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
+            steam_parts.push(format!(
+                "steamos-add-to-steam {} || true",
+                shell_quote(&desktop_path)
+            ));
+        }
+
+        if !steam_parts.is_empty() {
+            let steam_script = steam_parts.join(" && ");
             commands = commands.then(
                 Command::builder()
                     .normal()
                     .program("sh")
-                    .args(&["-c", &full_script])
-                    .description(&desc)
+                    .args(&["-c", &steam_script])
+                    .description("Handheld device detected — adding web apps to Steam...")
                     .build(),
             );
+        }
+    }
 
-            // On SteamOS, add each .desktop file to Steam
-            if is_steamos {
-                let mut steam_parts = Vec::new();
-                for selected_name in &selected_ids {
-                    if let Some((name, _url)) = STREAMING_SERVICES
-                        .iter()
-                        .find(|(n, _)| *n == selected_name.as_str())
-                    {
-                        let desktop_path = format!("{}/{}.desktop", apps_dir, sanitize_filename(name));
-                        steam_parts.push(format!(
-                            "steamos-add-to-steam '{}' || true",
-                            desktop_path
-                        ));
-                    }
-                }
-
-                if !steam_parts.is_empty() {
-                    let steam_script = steam_parts.join(" && ");
-                    commands = commands.then(
-                        Command::builder()
-                            .normal()
-                            .program("sh")
-                            .args(&["-c", &steam_script])
-                            .description("Handheld device detected — adding web apps to Steam...")
-                            .build(),
-                    );
-                }
-            }
-
-            task_runner::run(
-                window_for_closure.upcast_ref(),
-                commands.build(),
-                "Streaming Services Setup",
-            );
-        });
-    });
+    commands
 }
 
 // ── Enhanced Audio ────────────────────────────────────────────────────────────
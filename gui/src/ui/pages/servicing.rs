@@ -3,9 +3,16 @@
 use adw::prelude::*;
 use crate::config;
 use crate::core;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
 use crate::ui::dialogs::terminal;
+use crate::ui::navigation::Tool;
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::{extract_widget, is_package_installed, is_service_enabled, is_user_service_enabled};
+use crate::ui::utils::{
+    is_package_installed, is_service_enabled, is_user_service_enabled, on_refocus,
+    try_extract_widget, WidgetError,
+};
 use gtk4::{
     ApplicationWindow, Box as GtkBox, Builder, CheckButton, Frame, Label, Orientation,
     ScrolledWindow, Separator, ToggleButton,
@@ -13,30 +20,234 @@ use gtk4::{
 use log::info;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
+
+/// Keyservers occasionally accept the connection and never answer.
+const KEYSERVER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Tools on this page, indexed by the sidebar search.
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Update System",
+        keywords: "upgrade syu update all packages aur flatpak",
+        button: "btn_update_all",
+    },
+    Tool {
+        name: "Repair Keyring",
+        keywords: "pgp signature invalid corrupted package archlinux-keyring pacman-key",
+        button: "btn_repair_keyring",
+    },
+    Tool {
+        name: "Clear Pacman Cache",
+        keywords: "clean disk space",
+        button: "btn_clr_pacman",
+    },
+    Tool {
+        name: "Unlock Pacman DB",
+        keywords: "db.lck lock",
+        button: "btn_unlock_pacman",
+    },
+    Tool {
+        name: "Clean Package Cache",
+        keywords: "paccache disk space old versions",
+        button: "btn_paccache",
+    },
+    Tool {
+        name: "Remove Orphans",
+        keywords: "unused packages cleanup",
+        button: "btn_remove_orphans",
+    },
+    Tool {
+        name: "Reinstall All Packages",
+        keywords: "repair",
+        button: "btn_reinstall_all",
+    },
+    Tool {
+        name: "Fix GPGME Database",
+        keywords: "gpg error",
+        button: "btn_fix_gpgme",
+    },
+    Tool {
+        name: "Fix Arch Keyring",
+        keywords: "pgp signature keys",
+        button: "btn_fix_arch_keyring",
+    },
+    Tool {
+        name: "Pacman DB Fix",
+        keywords: "database repair",
+        button: "btn_pacman_db_fix",
+    },
+    Tool {
+        name: "Update Mirrorlist",
+        keywords: "rate-mirrors",
+        button: "btn_update_mirrorlist",
+    },
+    Tool {
+        name: "Rank Mirrors (Reflector)",
+        keywords: "reflector mirrorlist country fastest",
+        button: "btn_reflector",
+    },
+    Tool {
+        name: "Parallel Downloads",
+        keywords: "pacman.conf speed",
+        button: "btn_parallel_downloads",
+    },
+    Tool {
+        name: "CachyOS Repos",
+        keywords: "repository",
+        button: "btn_cachyos_repos",
+    },
+    Tool {
+        name: "Chaotic-AUR",
+        keywords: "repository",
+        button: "btn_chaotic_aur",
+    },
+    Tool {
+        name: "XeroLinux Repo",
+        keywords: "repository",
+        button: "btn_xero_repo",
+    },
+    Tool {
+        name: "Garuda Repo",
+        keywords: "repository",
+        button: "btn_garuda_repo",
+    },
+    Tool {
+        name: "Enable Multilib",
+        keywords: "32-bit lib32 repository pacman.conf steam wine",
+        button: "btn_multilib",
+    },
+    Tool {
+        name: "Plasma X11 Session",
+        keywords: "kde xorg",
+        button: "btn_plasma_x11",
+    },
+    Tool {
+        name: "WayDroid Guide",
+        keywords: "android",
+        button: "btn_waydroid_guide",
+    },
+    Tool {
+        name: "cxPackageManager",
+        keywords: "package manager gui",
+        button: "btn_xpackagemanager",
+    },
+    Tool {
+        name: "zram Swap",
+        keywords: "zram-generator compressed memory ram",
+        button: "btn_zram",
+    },
+    Tool {
+        name: "SSD TRIM",
+        keywords: "fstrim timer ssd nvme discard",
+        button: "btn_fstrim",
+    },
+    Tool {
+        name: "Firmware Updates",
+        keywords: "fwupd fwupdmgr bios uefi lvfs",
+        button: "btn_fwupd",
+    },
+    Tool {
+        name: "System Snapshots",
+        keywords: "timeshift snapper snap-pac btrfs backup restore rollback",
+        button: "btn_snapshots",
+    },
+    Tool {
+        name: "Update Toolkit",
+        keywords: "self update",
+        button: "btn_update_toolkit",
+    },
+];
 
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
-    setup_clr_pacman(page_builder, window);
-    setup_unlock_pacman(page_builder, window);
-    setup_remove_orphans(page_builder, window);
-    setup_reinstall_all(page_builder, window);
-    setup_plasma_x11(page_builder, window);
-    setup_pacman_db_fix(page_builder, window);
-    setup_waydroid_guide(page_builder);
-    setup_fix_gpgme(page_builder, window);
-    setup_fix_arch_keyring(page_builder, window);
-    setup_update_mirrorlist(page_builder, window);
-    setup_parallel_downloads(page_builder, window);
-    setup_cachyos_repos(page_builder, window);
-    setup_chaotic_aur(page_builder, window);
-    setup_xero_repo(page_builder, window);
-    setup_garuda_repo(page_builder, window);
-    setup_xpackagemanager(page_builder, window);
-    setup_update_toolkit(page_builder, window);
-    setup_optimization_services(page_builder, window);
+    // Each tool wires itself independently, so a widget id that drifted out
+    // of the .ui file only costs that one tool instead of the whole page.
+    let results = [
+        setup_update_all(page_builder, window),
+        setup_clr_pacman(page_builder, window),
+        setup_unlock_pacman(page_builder, window),
+        setup_paccache(page_builder, window),
+        setup_remove_orphans(page_builder, window),
+        setup_reinstall_all(page_builder, window),
+        setup_plasma_x11(page_builder, window),
+        setup_pacman_db_fix(page_builder, window),
+        setup_waydroid_guide(page_builder),
+        setup_fix_gpgme(page_builder, window),
+        setup_repair_keyring(page_builder, window),
+        setup_fix_arch_keyring(page_builder, window),
+        setup_update_mirrorlist(page_builder, window),
+        setup_reflector(page_builder, window),
+        setup_parallel_downloads(page_builder, window),
+        setup_cachyos_repos(page_builder, window),
+        setup_chaotic_aur(page_builder, window),
+        setup_xero_repo(page_builder, window),
+        setup_garuda_repo(page_builder, window),
+        setup_multilib(page_builder, window),
+        setup_xpackagemanager(page_builder, window),
+        setup_zram(page_builder, window),
+        setup_fstrim(page_builder, window),
+        setup_snapshots(page_builder, window),
+        setup_fwupd(page_builder, window),
+        setup_update_toolkit(page_builder, window),
+        setup_optimization_services(page_builder, window),
+    ];
+    let failures: Vec<String> = results
+        .into_iter()
+        .filter_map(Result::err)
+        .map(|e| e.to_string())
+        .collect();
+    if !failures.is_empty() {
+        crate::ui::dialogs::error::report_failure(
+            window,
+            "Some Servicing tools could not be set up and will not respond.",
+            &failures.join("\n"),
+        );
+    }
 }
 
-fn setup_clr_pacman(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_clr_pacman = extract_widget::<gtk4::Button>(page_builder, "btn_clr_pacman");
+/// Full upgrade of repo and AUR packages, then flatpaks. Plain installs
+/// elsewhere don't sync on their own. The exceptions are the repository
+/// tools (Enable Multilib, the CachyOS and Chaotic-AUR setups, Fix GPGME
+/// Database), which refresh the databases so new repos resolve. None of
+/// them upgrade anything, so this is what brings the system back in line.
+fn setup_update_all(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_update_all = try_extract_widget::<gtk4::Button>(page_builder, "btn_update_all")?;
+    let window = window.clone();
+    btn_update_all.connect_clicked(move |_| {
+        info!("Servicing: Update System button clicked");
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-Syu", "--noconfirm"])
+                    .description("Updating repo and AUR packages...")
+                    .capture_output(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("bash")
+                    .args(&[
+                        "-c",
+                        "if command -v flatpak >/dev/null 2>&1; then \
+                            flatpak update -y --noninteractive; \
+                         else \
+                            echo 'Flatpak not installed, skipping.'; \
+                         fi",
+                    ])
+                    .description("Updating Flatpak apps and runtimes...")
+                    .capture_output(true)
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, "Update System");
+    });
+    Ok(())
+}
+
+fn setup_clr_pacman(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_clr_pacman = try_extract_widget::<gtk4::Button>(page_builder, "btn_clr_pacman")?;
     let window = window.clone();
     btn_clr_pacman.connect_clicked(move |_| {
         info!("Servicing: Clear Pacman Cache button clicked");
@@ -106,10 +317,11 @@ fn setup_clr_pacman(page_builder: &Builder, window: &ApplicationWindow) {
             .build();
         task_runner::run(window.upcast_ref(), commands, "Clear Package Caches");
     });
+    Ok(())
 }
 
-fn setup_unlock_pacman(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_unlock_pacman = extract_widget::<gtk4::Button>(page_builder, "btn_unlock_pacman");
+fn setup_unlock_pacman(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_unlock_pacman = try_extract_widget::<gtk4::Button>(page_builder, "btn_unlock_pacman")?;
     let window = window.clone();
     btn_unlock_pacman.connect_clicked(move |_| {
         info!("Servicing: Unlock Pacman DB button clicked");
@@ -118,13 +330,134 @@ fn setup_unlock_pacman(page_builder: &Builder, window: &ApplicationWindow) {
                 Command::builder()
                     .privileged()
                     .program("rm")
-                    .args(&["-f", "/var/lib/pacman/db.lck"])
+                    .args(&["-f", core::package::PACMAN_DB_LOCK])
                     .description("Removing Pacman lock file...")
                     .build(),
             )
             .build();
         task_runner::run(window.upcast_ref(), commands, "Unlock Pacman Database");
     });
+    Ok(())
+}
+
+const PACMAN_CACHE_DIR: &str = "/var/cache/pacman/pkg";
+
+/// Total size of the files in pacman's package cache.
+fn pacman_cache_size() -> u64 {
+    std::fs::read_dir(PACMAN_CACHE_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok()?.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn setup_paccache(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn = try_extract_widget::<gtk4::Button>(page_builder, "btn_paccache")?;
+    let window = window.clone();
+
+    btn.connect_clicked(move |btn| {
+        info!("Servicing: Clean Package Cache button clicked");
+
+        // Summing a large cache can take a moment; keep it off the main loop.
+        btn.set_sensitive(false);
+        let (tx, rx) = async_channel::bounded::<u64>(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(pacman_cache_size());
+        });
+
+        let btn = btn.clone();
+        let window = window.clone();
+        gtk4::glib::MainContext::default().spawn_local(async move {
+            let size = rx.recv().await;
+            btn.set_sensitive(true);
+            if let Ok(before) = size {
+                show_paccache_dialog(&window, before);
+            }
+        });
+    });
+    Ok(())
+}
+
+fn show_paccache_dialog(window: &ApplicationWindow, before: u64) {
+    let config = SelectionDialogConfig::new(
+        "Clean Package Cache",
+        &format!(
+            "The package cache currently uses {}. Choose what to keep.",
+            core::download::humanize_bytes(before)
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .add_option(SelectionOption::new(
+        "keep3",
+        "Keep latest 3 versions",
+        "paccache -rk3 — room to downgrade a package that breaks",
+        false,
+    ))
+    .add_option(SelectionOption::new(
+        "keep1",
+        "Keep only installed versions",
+        "paccache -rk1 — one cached copy of each package",
+        false,
+    ))
+    .add_option(SelectionOption::new(
+        "uninstalled",
+        "Clear all uninstalled",
+        "paccache -ruk0 — drop every cached package that is no longer installed",
+        false,
+    ))
+    .confirm_label("Clean");
+
+    let window = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let paccache_args: &[&str] = match selected.first().map(String::as_str) {
+            Some("keep3") => &["-rk3"],
+            Some("keep1") => &["-rk1"],
+            Some("uninstalled") => &["-ruk0"],
+            _ => return,
+        };
+
+        let mut commands = CommandSequence::new();
+        if !core::aur::is_executable_in_path("paccache") {
+            commands = commands.then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "pacman-contrib"])
+                    .description("Installing pacman-contrib (provides paccache)...")
+                    .build(),
+            );
+        }
+
+        let report = format!(
+            "after=$(du -sb {dir} | cut -f1); \
+             echo \"Package cache now uses $(numfmt --to=iec \"$after\")B, \
+             freed $(numfmt --to=iec $(({before} - after)))B.\"",
+            dir = PACMAN_CACHE_DIR,
+            before = before
+        );
+        commands = commands
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("paccache")
+                    .args(paccache_args)
+                    .description("Cleaning package cache...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&["-c", &report])
+                    .description("Measuring freed space...")
+                    .build(),
+            );
+
+        task_runner::run(window.upcast_ref(), commands.build(), "Clean Package Cache");
+    });
 }
 
 /// Query pacman for orphaned packages (installed as deps, no longer required).
@@ -144,8 +477,8 @@ fn get_orphan_packages() -> Vec<String> {
         .unwrap_or_default()
 }
 
-fn setup_remove_orphans(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn = extract_widget::<gtk4::Button>(page_builder, "btn_remove_orphans");
+fn setup_remove_orphans(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn = try_extract_widget::<gtk4::Button>(page_builder, "btn_remove_orphans")?;
     let window = window.clone();
 
     btn.connect_clicked(move |_| {
@@ -390,6 +723,7 @@ fn setup_remove_orphans(page_builder: &Builder, window: &ApplicationWindow) {
                         .description("Removing orphaned packages...")
                         .build(),
                 )
+                .confirm(false)
                 .build();
 
             task_runner::run(
@@ -403,10 +737,11 @@ fn setup_remove_orphans(page_builder: &Builder, window: &ApplicationWindow) {
         dialog.set_content(Some(&toolbar));
         dialog.present();
     });
+    Ok(())
 }
 
-fn setup_reinstall_all(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn = extract_widget::<gtk4::Button>(page_builder, "btn_reinstall_all");
+fn setup_reinstall_all(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn = try_extract_widget::<gtk4::Button>(page_builder, "btn_reinstall_all")?;
     let window = window.clone();
     btn.connect_clicked(move |_| {
         info!("Servicing: Reinstall All Packages button clicked");
@@ -422,10 +757,11 @@ fn setup_reinstall_all(page_builder: &Builder, window: &ApplicationWindow) {
             .build();
         task_runner::run(window.upcast_ref(), commands, "Reinstall All Packages");
     });
+    Ok(())
 }
 
-fn setup_plasma_x11(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_plasma_x11 = extract_widget::<gtk4::Button>(page_builder, "btn_plasma_x11");
+fn setup_plasma_x11(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_plasma_x11 = try_extract_widget::<gtk4::Button>(page_builder, "btn_plasma_x11")?;
     let window = window.clone();
     btn_plasma_x11.connect_clicked(move |_| {
         info!("Servicing: Plasma X11 Session button clicked");
@@ -440,10 +776,11 @@ fn setup_plasma_x11(page_builder: &Builder, window: &ApplicationWindow) {
             .build();
         task_runner::run(window.upcast_ref(), commands, "Install KDE X11 Session");
     });
+    Ok(())
 }
 
-fn setup_pacman_db_fix(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_pacman_db_fix = extract_widget::<gtk4::Button>(page_builder, "btn_pacman_db_fix");
+fn setup_pacman_db_fix(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_pacman_db_fix = try_extract_widget::<gtk4::Button>(page_builder, "btn_pacman_db_fix")?;
     let window = window.clone();
     btn_pacman_db_fix.connect_clicked(move |_| {
         info!("Servicing: Pacman DB Fix button clicked");
@@ -462,34 +799,120 @@ fn setup_pacman_db_fix(page_builder: &Builder, window: &ApplicationWindow) {
             .build();
         task_runner::run(window.upcast_ref(), commands, "Pacman DB Fix");
     });
+    Ok(())
 }
 
-fn setup_waydroid_guide(page_builder: &Builder) {
-    let btn_waydroid_guide = extract_widget::<gtk4::Button>(page_builder, "btn_waydroid_guide");
+fn setup_waydroid_guide(page_builder: &Builder) -> Result<(), WidgetError> {
+    let btn_waydroid_guide = try_extract_widget::<gtk4::Button>(page_builder, "btn_waydroid_guide")?;
     btn_waydroid_guide.connect_clicked(move |_| {
         info!("Servicing: WayDroid Guide button clicked - opening guide");
         let _ = std::process::Command::new("xdg-open")
             .arg("https://xerolinux.xyz/posts/waydroid-guide/")
             .spawn();
     });
+    Ok(())
 }
 
-fn setup_fix_gpgme(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_fix_gpgme = extract_widget::<gtk4::Button>(page_builder, "btn_fix_gpgme");
+fn setup_fix_gpgme(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_fix_gpgme = try_extract_widget::<gtk4::Button>(page_builder, "btn_fix_gpgme")?;
     let window = window.clone();
     btn_fix_gpgme.connect_clicked(move |_| {
         info!("Servicing: Fix GPGME Database button clicked");
         terminal::show_terminal_dialog(
             window.upcast_ref(),
             "Fix GPGME Database",
-            "pkexec",
+            core::escalation::program(),
             &["sh", "-c", "rm -rf /var/lib/pacman/sync && pacman -Syy"],
         );
     });
+    Ok(())
+}
+
+/// Public keyring files pacman-key creates; world-readable, so checking them
+/// needs no privileges.
+const PACMAN_PUBRINGS: &[&str] = &["/etc/pacman.d/gnupg/pubring.gpg", "/etc/pacman.d/gnupg/pubring.kbx"];
+
+/// No keyring, or one with nothing in it: every signed install will fail.
+fn is_keyring_empty() -> bool {
+    !PACMAN_PUBRINGS
+        .iter()
+        .any(|path| std::fs::metadata(path).is_ok_and(|m| m.len() > 0))
+}
+
+fn update_keyring_button(button: &gtk4::Button) {
+    if is_keyring_empty() {
+        button.set_label("Repair Keyring (needed)");
+        button.remove_css_class("suggested-action");
+        button.add_css_class("destructive-action");
+    } else {
+        button.set_label("Repair Keyring");
+        button.remove_css_class("destructive-action");
+        button.add_css_class("suggested-action");
+    }
+}
+
+/// The standard, non-destructive keyring repair: refresh the keyring package
+/// ahead of everything else it signs, finish the upgrade, then (re)initialize
+/// and populate the local keys. "Fix Arch Keyring" is the heavier fallback
+/// that wipes the GnuPG directory first.
+fn setup_repair_keyring(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_repair_keyring = try_extract_widget::<gtk4::Button>(page_builder, "btn_repair_keyring")?;
+    update_keyring_button(&btn_repair_keyring);
+    {
+        let btn_repair_keyring = btn_repair_keyring.clone();
+        on_refocus(window, move || update_keyring_button(&btn_repair_keyring));
+    }
+
+    let window = window.clone();
+    btn_repair_keyring.connect_clicked(move |_| {
+        info!("Servicing: Repair Keyring button clicked");
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Sy", "--noconfirm", "archlinux-keyring"])
+                    .description("Refreshing archlinux-keyring...")
+                    .capture_output(true)
+                    .build(),
+            )
+            // Finish the upgrade right away; a sync that stops at the
+            // keyring leaves the system partially upgraded.
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Su", "--noconfirm"])
+                    .description("Upgrading the rest of the system...")
+                    .capture_output(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman-key")
+                    .args(&["--init"])
+                    .description("Initializing the keyring...")
+                    .capture_output(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman-key")
+                    .args(&["--populate", "archlinux"])
+                    .description("Populating Arch Linux keys...")
+                    .capture_output(true)
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, "Repair Keyring");
+    });
+    Ok(())
 }
 
-fn setup_fix_arch_keyring(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_fix_arch_keyring = extract_widget::<gtk4::Button>(page_builder, "btn_fix_arch_keyring");
+fn setup_fix_arch_keyring(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_fix_arch_keyring = try_extract_widget::<gtk4::Button>(page_builder, "btn_fix_arch_keyring")?;
     let window = window.clone();
     btn_fix_arch_keyring.connect_clicked(move |_| {
         info!("Servicing: Fix Arch Keyring button clicked");
@@ -527,10 +950,11 @@ fn setup_fix_arch_keyring(page_builder: &Builder, window: &ApplicationWindow) {
             .build();
         task_runner::run(window.upcast_ref(), commands, "Fix GnuPG Keyring");
     });
+    Ok(())
 }
 
-fn setup_update_mirrorlist(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_update_mirrorlist = extract_widget::<gtk4::Button>(page_builder, "btn_update_mirrorlist");
+fn setup_update_mirrorlist(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_update_mirrorlist = try_extract_widget::<gtk4::Button>(page_builder, "btn_update_mirrorlist")?;
     let window = window.clone();
     btn_update_mirrorlist.connect_clicked(move |_| {
         info!("Servicing: Update Mirrorlist button clicked");
@@ -592,26 +1016,204 @@ echo "Updated $dst (backup at $dst.bak)"
             }
         }
 
-        task_runner::run(window.upcast_ref(), commands.build(), "Update System Mirrorlists");
+        task_runner::run(
+            window.upcast_ref(),
+            commands.confirm(true).build(),
+            "Update System Mirrorlists",
+        );
     });
+    Ok(())
+}
+
+/// Countries offered for reflector: (ISO code, name).
+const REFLECTOR_COUNTRIES: &[(&str, &str)] = &[
+    ("AU", "Australia"),
+    ("AT", "Austria"),
+    ("BE", "Belgium"),
+    ("BR", "Brazil"),
+    ("CA", "Canada"),
+    ("CN", "China"),
+    ("CZ", "Czechia"),
+    ("DK", "Denmark"),
+    ("FI", "Finland"),
+    ("FR", "France"),
+    ("DE", "Germany"),
+    ("IN", "India"),
+    ("IT", "Italy"),
+    ("JP", "Japan"),
+    ("NL", "Netherlands"),
+    ("NZ", "New Zealand"),
+    ("NO", "Norway"),
+    ("PL", "Poland"),
+    ("PT", "Portugal"),
+    ("KR", "South Korea"),
+    ("ES", "Spain"),
+    ("SE", "Sweden"),
+    ("CH", "Switzerland"),
+    ("GB", "United Kingdom"),
+    ("US", "United States"),
+];
+
+/// Mirrors kept in the ranked list.
+const REFLECTOR_MIRRORS: u32 = 20;
+
+/// Country code from the locale (`en_GB.UTF-8` → `GB`), if it has one.
+fn locale_country() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty())
+        .and_then(|locale| {
+            let region = locale.split(['.', '@']).next()?.split('_').nth(1)?;
+            (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+                .then(|| region.to_ascii_uppercase())
+        })
 }
 
-fn setup_parallel_downloads(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_parallel_downloads = extract_widget::<gtk4::Button>(page_builder, "btn_parallel_downloads");
+fn setup_reflector(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn = try_extract_widget::<gtk4::Button>(page_builder, "btn_reflector")?;
+    let window = window.clone();
+    btn.connect_clicked(move |_| {
+        info!("Servicing: Rank Mirrors (Reflector) button clicked");
+
+        let detected = locale_country();
+        let mut config = SelectionDialogConfig::new(
+            "Rank Mirrors",
+            &format!(
+                "Reflector will rank the {} fastest Arch mirrors in the chosen countries and \
+                 write /etc/pacman.d/mirrorlist. The current list is backed up first.",
+                REFLECTOR_MIRRORS
+            ),
+        )
+        .confirm_label("Rank Mirrors");
+
+        // A locale country reflector knows but this list doesn't still gets offered.
+        if let Some(code) = detected.as_deref() {
+            if !REFLECTOR_COUNTRIES.iter().any(|(c, _)| *c == code) {
+                config = config.add_option(
+                    SelectionOption::new(code, code, "Detected from your locale", false)
+                        .preselected(true)
+                        .group("Country"),
+                );
+            }
+        }
+        for (code, name) in REFLECTOR_COUNTRIES {
+            let is_detected = detected.as_deref() == Some(*code);
+            let description = if is_detected { "Detected from your locale" } else { "" };
+            config = config.add_option(
+                SelectionOption::new(code, name, description, false)
+                    .preselected(is_detected)
+                    .group("Country"),
+            );
+        }
+        config = config
+            .add_option(
+                SelectionOption::new("https", "HTTPS", "Encrypted transport", false)
+                    .preselected(true)
+                    .group("Protocol"),
+            )
+            .add_option(
+                SelectionOption::new("http", "HTTP", "More mirrors, unencrypted", false)
+                    .group("Protocol"),
+            );
+
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let (protocols, countries): (Vec<&String>, Vec<&String>) = selected
+                .iter()
+                .partition(|id| matches!(id.as_str(), "https" | "http"));
+            let protocols = if protocols.is_empty() {
+                String::from("https")
+            } else {
+                protocols.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(",")
+            };
+            let countries = countries.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(",");
+
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                reflector_plan(&countries, &protocols).confirm(true),
+                "Rank Mirrors",
+            );
+        });
+    });
+    Ok(())
+}
+
+/// Install reflector if needed, then rank into a temp file and only replace
+/// the mirrorlist once the backup exists and the new list has servers.
+fn reflector_plan(countries: &str, protocols: &str) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    if !core::is_package_installed("reflector") {
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--needed", "--noconfirm", "reflector"])
+                .description("Installing reflector...")
+                .build(),
+        );
+    }
+
+    let country_arg = if countries.is_empty() {
+        String::new()
+    } else {
+        format!("--country {}", task_runner::shell_quote(countries))
+    };
+    let script = format!(
+        r#"set -u
+dst=/etc/pacman.d/mirrorlist
+tmp="$(mktemp "${{dst}}.XXXXXX")" || {{ echo "Could not create temp file next to $dst" >&2; exit 1; }}
+trap 'rm -f "$tmp"' EXIT
+if ! reflector {country} --protocol {protocols} --latest 50 --fastest {count} --sort rate --save "$tmp"; then
+    echo "reflector failed; keeping existing $dst." >&2
+    exit 1
+fi
+if ! grep -qE '^[[:space:]]*Server[[:space:]]*=' "$tmp"; then
+    echo "reflector returned no mirrors; keeping existing $dst." >&2
+    exit 1
+fi
+if ! cp -a -- "$dst" "$dst.bak"; then
+    echo "Could not back up $dst; leaving it untouched." >&2
+    exit 1
+fi
+chmod 0644 "$tmp"
+mv -f -- "$tmp" "$dst"
+trap - EXIT
+echo "Updated $dst (backup at $dst.bak)"
+"#,
+        country = country_arg,
+        protocols = task_runner::shell_quote(protocols),
+        count = REFLECTOR_MIRRORS,
+    );
+
+    commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&["-c", &script])
+                .description("Ranking mirrors with reflector...")
+                .build(),
+        )
+        .build()
+}
+
+fn setup_parallel_downloads(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_parallel_downloads = try_extract_widget::<gtk4::Button>(page_builder, "btn_parallel_downloads")?;
     let window = window.clone();
     btn_parallel_downloads.connect_clicked(move |_| {
         info!("Servicing: Change Parallel Downloads button clicked");
         terminal::show_terminal_dialog(
             window.upcast_ref(),
             "Change Parallel Downloads",
-            "pkexec",
+            core::escalation::program(),
             &["pmpd"],
         );
     });
+    Ok(())
 }
 
-fn setup_cachyos_repos(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_cachyos_repos = extract_widget::<gtk4::Button>(page_builder, "btn_cachyos_repos");
+fn setup_cachyos_repos(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_cachyos_repos = try_extract_widget::<gtk4::Button>(page_builder, "btn_cachyos_repos")?;
     let window = window.clone();
     btn_cachyos_repos.connect_clicked(move |_| {
         info!("Servicing: Install CachyOS Repos button clicked");
@@ -659,10 +1261,11 @@ fn setup_cachyos_repos(page_builder: &Builder, window: &ApplicationWindow) {
 
         task_runner::run(window.upcast_ref(), commands, "Install CachyOS Repositories");
     });
+    Ok(())
 }
 
-fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_chaotic_aur = extract_widget::<gtk4::Button>(page_builder, "btn_chaotic_aur");
+fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_chaotic_aur = try_extract_widget::<gtk4::Button>(page_builder, "btn_chaotic_aur")?;
     let window = window.clone();
     btn_chaotic_aur.connect_clicked(move |_| {
         info!("Servicing: Install Chaotic-AUR button clicked");
@@ -674,6 +1277,7 @@ fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
                     .program("pacman-key")
                     .args(&["--recv-key", "3056513887B78AEB", "--keyserver", "keyserver.ubuntu.com"])
                     .description("Receiving Chaotic-AUR signing key...")
+                    .timeout(KEYSERVER_TIMEOUT)
                     .build(),
             )
             .then(
@@ -731,6 +1335,7 @@ fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
 
         task_runner::run(window.upcast_ref(), commands, "Install Chaotic-AUR Repository");
     });
+    Ok(())
 }
 
 const XERO_REPO_WARNING: &str =
@@ -743,8 +1348,8 @@ const XERO_REPO_WARNING: &str =
      <span foreground=\"yellow\" weight=\"bold\">please report it to me, not upstream XeroLinux</span>. \
      They do not want this feature set enabled outside their distro and will not support it.";
 
-fn setup_xero_repo(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_xero_repo = extract_widget::<gtk4::Button>(page_builder, "btn_xero_repo");
+fn setup_xero_repo(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_xero_repo = try_extract_widget::<gtk4::Button>(page_builder, "btn_xero_repo")?;
     let window = window.clone();
     btn_xero_repo.connect_clicked(move |_| {
         info!("Servicing: Add Xero Linux Repository button clicked");
@@ -781,6 +1386,7 @@ fn setup_xero_repo(page_builder: &Builder, window: &ApplicationWindow) {
             },
         );
     });
+    Ok(())
 }
 
 const GARUDA_REPO_WARNING: &str =
@@ -795,8 +1401,8 @@ const GARUDA_REPO_WARNING: &str =
      <span foreground=\"yellow\" weight=\"bold\">please do not report them to the Garuda Linux team</span> \
      \u{2014} they only support their own distribution.";
 
-fn setup_garuda_repo(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_garuda_repo = extract_widget::<gtk4::Button>(page_builder, "btn_garuda_repo");
+fn setup_garuda_repo(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_garuda_repo = try_extract_widget::<gtk4::Button>(page_builder, "btn_garuda_repo")?;
     let window = window.clone();
     btn_garuda_repo.connect_clicked(move |_| {
         info!("Servicing: Add Garuda Repository button clicked");
@@ -821,6 +1427,7 @@ fn setup_garuda_repo(page_builder: &Builder, window: &ApplicationWindow) {
                                  pacman-key --lsign-key 3056513887B78AEB",
                             ])
                             .description("Importing Chaotic-AUR signing key...")
+                            .timeout(KEYSERVER_TIMEOUT)
                             .build(),
                     )
                     // Step 2: Install chaotic-keyring if not already present.
@@ -894,11 +1501,51 @@ fn setup_garuda_repo(page_builder: &Builder, window: &ApplicationWindow) {
             },
         );
     });
+    Ok(())
+}
+
+fn setup_multilib(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_multilib = try_extract_widget::<gtk4::Button>(page_builder, "btn_multilib")?;
+
+    update_enable_button(&btn_multilib, core::is_multilib_enabled(), "Enable Multilib");
+    {
+        let btn_multilib = btn_multilib.clone();
+        on_refocus(window, move || {
+            update_enable_button(&btn_multilib, core::is_multilib_enabled(), "Enable Multilib");
+        });
+    }
+
+    let window = window.clone();
+    btn_multilib.connect_clicked(move |_| {
+        info!("Servicing: Enable Multilib button clicked");
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("bash")
+                    .args(&["-c", core::package::ENABLE_MULTILIB_SCRIPT])
+                    .description("Enabling [multilib] in pacman.conf...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Sy"])
+                    .description("Syncing package databases...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Enable Multilib");
+    });
+    Ok(())
 }
 
-fn setup_xpackagemanager(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_xpackagemanager = extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager");
-    let btn_xpackagemanager_uninstall = extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager_uninstall");
+fn setup_xpackagemanager(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_xpackagemanager = try_extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager")?;
+    let btn_xpackagemanager_uninstall = try_extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager_uninstall")?;
 
     fn update_button_state(setup_btn: &gtk4::Button, uninstall_btn: &gtk4::Button, is_installed: bool) {
         if is_installed {
@@ -917,11 +1564,9 @@ fn setup_xpackagemanager(page_builder: &Builder, window: &ApplicationWindow) {
 
     let btn_setup_clone = btn_xpackagemanager.clone();
     let btn_uninstall_clone = btn_xpackagemanager_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xpackagemanager").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    on_refocus(window, move || {
+        let is_installed = std::path::Path::new("/usr/bin/xpackagemanager").exists();
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     let window_clone = window.clone();
@@ -1168,6 +1813,7 @@ EOF"#,
             "Uninstall cxPackageManager",
         );
     });
+    Ok(())
 }
 
 /// Get the latest remote commit hash from the toolkit GitHub repository.
@@ -1346,8 +1992,8 @@ pub fn show_update_dialog(window: &ApplicationWindow, info: UpdateInfo) {
     dialog.present();
 }
 
-fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn = extract_widget::<gtk4::Button>(page_builder, "btn_update_toolkit");
+fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn = try_extract_widget::<gtk4::Button>(page_builder, "btn_update_toolkit")?;
     let window = window.clone();
 
     btn.connect_clicked(move |btn| {
@@ -1389,6 +2035,7 @@ fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
             },
         );
     });
+    Ok(())
 }
 
 /// Small modal dialog with an icon, primary message, optional caption, and an OK button.
@@ -1447,6 +2094,684 @@ fn show_simple_info_dialog(
     dialog.present();
 }
 
+/// Drop-in owned by the toolkit; leaves a distro-shipped zram-generator.conf alone.
+const ZRAM_DROPIN: &str = "/etc/systemd/zram-generator.conf.d/cyberxero.conf";
+const ZRAM_SETUP_UNIT: &str = "systemd-zram-setup@zram0.service";
+
+/// zram device size choices: (id, label, zram-size expression in MiB).
+const ZRAM_SIZES: &[(&str, &str, &str)] = &[
+    ("quarter", "A quarter of RAM (max 4 GiB)", "min(ram / 4, 4096)"),
+    ("half", "Half of RAM (max 8 GiB)", "min(ram / 2, 8192)"),
+    ("full", "All of RAM (max 16 GiB)", "min(ram, 16384)"),
+];
+
+/// Whether a zram device is currently in use as swap.
+fn is_zram_swap_active() -> bool {
+    std::fs::read_to_string("/proc/swaps")
+        .map(|swaps| swaps.lines().any(|line| line.starts_with("/dev/zram")))
+        .unwrap_or(false)
+}
+
+fn update_zram_buttons(enable_btn: &gtk4::Button, disable_btn: &gtk4::Button) {
+    let configured = std::path::Path::new(ZRAM_DROPIN).exists();
+    if configured && is_zram_swap_active() {
+        enable_btn.set_label("zram Swap ✓");
+        enable_btn.remove_css_class("suggested-action");
+    } else {
+        enable_btn.set_label("Enable zram Swap");
+        enable_btn.add_css_class("suggested-action");
+    }
+    disable_btn.set_visible(configured);
+}
+
+fn setup_zram(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_zram = try_extract_widget::<gtk4::Button>(page_builder, "btn_zram")?;
+    let btn_zram_disable = try_extract_widget::<gtk4::Button>(page_builder, "btn_zram_disable")?;
+
+    update_zram_buttons(&btn_zram, &btn_zram_disable);
+    {
+        let btn_zram = btn_zram.clone();
+        let btn_zram_disable = btn_zram_disable.clone();
+        on_refocus(window, move || {
+            update_zram_buttons(&btn_zram, &btn_zram_disable);
+        });
+    }
+
+    let window_clone = window.clone();
+    btn_zram.connect_clicked(move |_| {
+        info!("Servicing: zram Swap button clicked");
+
+        let mut config = SelectionDialogConfig::new(
+            "zram Swap",
+            "Compressed swap in RAM. Choose how large the zram device may grow; \
+             the size is capped so machines with lots of memory don't over-commit.",
+        )
+        .selection_type(SelectionType::Single)
+        .confirm_label("Apply");
+        for (id, label, expr) in ZRAM_SIZES {
+            config = config.add_option(
+                SelectionOption::new(id, label, &format!("zram-size = {}", expr), false)
+                    .preselected(*id == "half"),
+            );
+        }
+
+        let window = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let Some(&(_, _, size)) = ZRAM_SIZES
+                .iter()
+                .find(|(id, _, _)| selected.first().map(String::as_str) == Some(*id))
+            else {
+                return;
+            };
+
+            let mut commands = CommandSequence::new();
+            if !core::is_package_installed("zram-generator") {
+                commands = commands.then(
+                    Command::builder()
+                        .privileged()
+                        .program("pacman")
+                        .args(&["-S", "--noconfirm", "--needed", "zram-generator"])
+                        .description("Installing zram-generator...")
+                        .build(),
+                );
+            }
+
+            let script = format!(
+                "set -e\n\
+                 mkdir -p \"$(dirname {dropin})\"\n\
+                 printf '[zram0]\\nzram-size = %s\\ncompression-algorithm = zstd\\n' {size} > {dropin}\n\
+                 echo \"Wrote {dropin}\"",
+                dropin = task_runner::shell_quote(ZRAM_DROPIN),
+                size = task_runner::shell_quote(size),
+            );
+            commands = commands
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("sh")
+                        .args(&["-c", &script])
+                        .description("Writing zram configuration...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("systemctl")
+                        .args(&["daemon-reload"])
+                        .description("Reloading systemd units...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("systemctl")
+                        .args(&["restart", ZRAM_SETUP_UNIT])
+                        .description("Activating zram swap...")
+                        .build(),
+                );
+
+            task_runner::run(window.upcast_ref(), commands.build(), "Enable zram Swap");
+        });
+    });
+
+    let window_clone = window.clone();
+    btn_zram_disable.connect_clicked(move |_| {
+        info!("Servicing: Disable zram Swap button clicked");
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["stop", ZRAM_SETUP_UNIT])
+                    .description("Deactivating zram swap...")
+                    .allow_failure(true)
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("rm")
+                    .args(&["-f", ZRAM_DROPIN])
+                    .description("Removing zram configuration...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["daemon-reload"])
+                    .description("Reloading systemd units...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "Disable zram Swap");
+    });
+    Ok(())
+}
+
+const FSTRIM_TIMER: &str = "fstrim.timer";
+
+const NO_SSD_WARNING: &str = "No solid-state disk was detected. TRIM only helps SSDs and NVMe \
+drives; on spinning disks the timer does nothing useful.\n\nEnable it anyway?";
+
+/// Whether any real block device reports itself as non-rotational.
+fn has_non_rotational_disk() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/block") else {
+        return false;
+    };
+    entries.filter_map(|e| e.ok()).any(|entry| {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Virtual devices are always "non-rotational" and say nothing about the disks.
+        if ["loop", "ram", "zram", "dm-", "sr"].iter().any(|p| name.starts_with(p)) {
+            return false;
+        }
+        std::fs::read_to_string(entry.path().join("queue/rotational"))
+            .map(|v| v.trim() == "0")
+            .unwrap_or(false)
+    })
+}
+
+/// One-way "enable" buttons: greyed with "✓" once the thing is on.
+fn update_enable_button(button: &gtk4::Button, enabled: bool, label: &str) {
+    if enabled {
+        button.set_label(&format!("{} ✓", label));
+        button.set_sensitive(false);
+        button.remove_css_class("suggested-action");
+        button.add_css_class("dim-label");
+    } else {
+        button.set_label(label);
+        button.set_sensitive(true);
+        button.add_css_class("suggested-action");
+        button.remove_css_class("dim-label");
+    }
+}
+
+fn update_fstrim_button(button: &gtk4::Button) {
+    update_enable_button(button, is_service_enabled(FSTRIM_TIMER), "Enable SSD TRIM");
+}
+
+fn setup_fstrim(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_fstrim = try_extract_widget::<gtk4::Button>(page_builder, "btn_fstrim")?;
+
+    update_fstrim_button(&btn_fstrim);
+    {
+        let btn_fstrim = btn_fstrim.clone();
+        on_refocus(window, move || {
+            update_fstrim_button(&btn_fstrim);
+        });
+    }
+
+    let window = window.clone();
+    btn_fstrim.connect_clicked(move |_| {
+        info!("Servicing: SSD TRIM button clicked");
+
+        let enable = {
+            let window = window.clone();
+            move || {
+                let commands = CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .privileged()
+                            .program("systemctl")
+                            .args(&["enable", "--now", FSTRIM_TIMER])
+                            .description("Enabling weekly TRIM (fstrim.timer)...")
+                            .build(),
+                    )
+                    .build();
+                task_runner::run(window.upcast_ref(), commands, "Enable SSD TRIM");
+            }
+        };
+
+        if has_non_rotational_disk() {
+            enable();
+        } else {
+            crate::ui::dialogs::warning::show_warning_confirmation(
+                window.upcast_ref(),
+                "No SSD Detected",
+                NO_SSD_WARNING,
+                enable,
+            );
+        }
+    });
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// System snapshots: Timeshift or snapper
+// ---------------------------------------------------------------------------
+
+/// Written to `/etc/timeshift/timeshift.json` when Timeshift has no config
+/// yet: daily snapshots of the root device, five kept, no first-run wizard.
+/// `$1` is the root filesystem UUID, `$2` `true` for Btrfs mode.
+const TIMESHIFT_CONFIG_SCRIPT: &str = r#"
+set -e
+[ -f /etc/timeshift/timeshift.json ] && exit 0
+mkdir -p /etc/timeshift
+cat > /etc/timeshift/timeshift.json <<EOF
+{
+  "backup_device_uuid" : "$1",
+  "parent_device_uuid" : "",
+  "do_first_run" : "false",
+  "btrfs_mode" : "$2",
+  "include_btrfs_home_for_backup" : "false",
+  "include_btrfs_home_for_restore" : "false",
+  "stop_cron_emails" : "true",
+  "schedule_monthly" : "false",
+  "schedule_weekly" : "false",
+  "schedule_daily" : "true",
+  "schedule_hourly" : "false",
+  "schedule_boot" : "false",
+  "count_monthly" : "2",
+  "count_weekly" : "3",
+  "count_daily" : "5",
+  "count_hourly" : "6",
+  "count_boot" : "5",
+  "exclude" : [],
+  "exclude-apps" : []
+}
+EOF
+"#;
+
+/// Create snapper's `root` config unless it exists. Layouts that already
+/// mount a dedicated `@snapshots` subvolume at `/.snapshots` trip up
+/// `create-config`, so that mount is moved aside, snapper's own nested
+/// subvolume swapped out for it, and the mount restored. Timeline snapshots
+/// are then limited to something a normal disk can hold.
+const SNAPPER_CONFIG_SCRIPT: &str = r#"
+set -e
+if [ ! -f /etc/snapper/configs/root ]; then
+    if mountpoint -q /.snapshots; then
+        umount /.snapshots
+        rmdir /.snapshots
+        snapper --no-dbus -c root create-config /
+        btrfs subvolume delete /.snapshots
+        mkdir /.snapshots
+        mount /.snapshots
+    else
+        snapper --no-dbus -c root create-config /
+    fi
+    chmod 750 /.snapshots
+fi
+snapper --no-dbus -c root set-config \
+    TIMELINE_CREATE=yes TIMELINE_CLEANUP=yes NUMBER_CLEANUP=yes NUMBER_LIMIT=10 \
+    TIMELINE_LIMIT_HOURLY=5 TIMELINE_LIMIT_DAILY=7 TIMELINE_LIMIT_WEEKLY=0 \
+    TIMELINE_LIMIT_MONTHLY=0 TIMELINE_LIMIT_YEARLY=0
+"#;
+
+/// What `/` sits on, as far as snapshot tooling cares.
+struct RootFilesystem {
+    uuid: String,
+    btrfs: bool,
+    /// Ubuntu-style `@` root subvolume, which Timeshift's Btrfs mode needs.
+    at_layout: bool,
+}
+
+fn detect_root_filesystem() -> Option<RootFilesystem> {
+    let out = crate::ui::utils::run_command("findmnt", &["-no", "FSTYPE,UUID,OPTIONS", "/"])?;
+    let mut fields = out.split_whitespace();
+    let fstype = fields.next()?;
+    let uuid = fields.next()?.to_owned();
+    let options = fields.next().unwrap_or_default();
+    Some(RootFilesystem {
+        uuid,
+        btrfs: fstype == "btrfs",
+        at_layout: options.split(',').any(|o| o == "subvol=/@" || o == "subvol=@"),
+    })
+}
+
+fn setup_snapshots(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_snapshots = try_extract_widget::<gtk4::Button>(page_builder, "btn_snapshots")?;
+    let window = window.clone();
+    btn_snapshots.connect_clicked(move |_| {
+        info!("Servicing: System Snapshots button clicked");
+        let Some(root) = detect_root_filesystem() else {
+            crate::ui::dialogs::error::show_error(
+                &window,
+                "Could not determine the root filesystem (findmnt failed).",
+            );
+            return;
+        };
+
+        let (description, timeshift_note) = if !root.btrfs {
+            (
+                "Your root filesystem isn't Btrfs, so snapshots are rsync copies and need free space.",
+                "rsync copies of the system, plus one before every pacman upgrade",
+            )
+        } else if root.at_layout {
+            (
+                "Your root filesystem is Btrfs: snapshots are instant and take no space up front.",
+                "Btrfs snapshots of the @ subvolume, plus one before every pacman upgrade",
+            )
+        } else {
+            (
+                "Your root filesystem is Btrfs: snapshots are instant and take no space up front.",
+                "rsync copies (the root subvolume isn't named @), plus one before every pacman upgrade",
+            )
+        };
+
+        let mut snapper = SelectionOption::new(
+            "snapper",
+            "snapper",
+            "Hourly and daily Btrfs snapshots, plus before/after pairs around every pacman transaction (snap-pac)",
+            core::is_package_installed("snapper"),
+        )
+        .preselected(root.btrfs && !root.at_layout);
+        if !root.btrfs {
+            snapper = snapper.unavailable("Needs a Btrfs root filesystem");
+        }
+        let config = SelectionDialogConfig::new("System Snapshots", description)
+            .selection_type(SelectionType::Single)
+            .add_option(
+                SelectionOption::new(
+                    "timeshift",
+                    "Timeshift",
+                    timeshift_note,
+                    core::is_package_installed("timeshift"),
+                )
+                .preselected(!root.btrfs || root.at_layout),
+            )
+            .add_option(snapper)
+            // Keep installed tools pickable: setup also (re)applies the config.
+            .allow_removal(true)
+            .confirm_label("Set Up");
+
+        let window_inner = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let (commands, title) = match selected.first().map(String::as_str) {
+                Some("timeshift") => (timeshift_plan(&root), "Timeshift Setup"),
+                Some("snapper") => (snapper_plan(), "Snapper Setup"),
+                _ => return,
+            };
+            task_runner::run(window_inner.upcast_ref(), commands.build(), title);
+        });
+    });
+    Ok(())
+}
+
+fn timeshift_plan(root: &RootFilesystem) -> CommandSequence {
+    let btrfs_mode = if root.btrfs && root.at_layout { "true" } else { "false" };
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "timeshift", "timeshift-autosnap", "cronie"])
+                .description("Installing Timeshift...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", TIMESHIFT_CONFIG_SCRIPT, "sh", &root.uuid, btrfs_mode])
+                .description("Configuring daily snapshots...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "cronie.service"])
+                .description("Enabling the snapshot schedule...")
+                .build(),
+        )
+}
+
+fn snapper_plan() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "snapper", "snap-pac"])
+                .description("Installing snapper and snap-pac...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", SNAPPER_CONFIG_SCRIPT])
+                .description("Creating the root snapshot config...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "snapper-timeline.timer", "snapper-cleanup.timer"])
+                .description("Enabling snapshot timers...")
+                .build(),
+        )
+}
+
+/// The parts of `fwupdmgr get-updates --json` the summary needs.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FwupdReport {
+    #[serde(default)]
+    devices: Vec<FwupdDevice>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FwupdDevice {
+    name: String,
+    device_id: String,
+    #[serde(default)]
+    flags: Vec<String>,
+    #[serde(default)]
+    update_message: Option<String>,
+    #[serde(default)]
+    releases: Vec<FwupdRelease>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FwupdRelease {
+    version: String,
+    #[serde(default)]
+    summary: String,
+}
+
+/// A device with a newer firmware release available.
+struct FirmwareUpdate {
+    device_id: String,
+    device: String,
+    version: String,
+    summary: String,
+    /// Guidance fwupd wants shown once the update is staged.
+    guidance: Option<String>,
+}
+
+/// Refresh the LVFS metadata and list pending firmware updates.
+fn fwupd_updates() -> Result<Vec<FirmwareUpdate>, String> {
+    // A stale or unreachable LVFS still leaves get-updates usable.
+    let _ = std::process::Command::new("fwupdmgr")
+        .args(["refresh", "--force"])
+        .output();
+
+    let output = std::process::Command::new("fwupdmgr")
+        .args(["get-updates", "--json"])
+        .output()
+        .map_err(|e| format!("Could not run fwupdmgr: {}", e))?;
+
+    let report: FwupdReport = match serde_json::from_slice(&output.stdout) {
+        Ok(report) => report,
+        // Exit code 2 is fwupd's "nothing to do".
+        Err(_) if output.status.code() == Some(2) => return Ok(Vec::new()),
+        Err(_) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(if stderr.is_empty() {
+                String::from("fwupdmgr returned no usable update list.")
+            } else {
+                stderr
+            });
+        }
+    };
+
+    Ok(report
+        .devices
+        .into_iter()
+        .filter_map(|device| {
+            let release = device.releases.into_iter().next()?;
+            let needs_reboot = device
+                .flags
+                .iter()
+                .any(|f| f == "needs-reboot" || f == "needs-shutdown");
+            let guidance = device.update_message.or_else(|| {
+                needs_reboot.then(|| format!("{} needs a reboot to finish updating.", device.name))
+            });
+            Some(FirmwareUpdate {
+                device_id: device.device_id,
+                device: device.name,
+                version: release.version,
+                summary: release.summary,
+                guidance,
+            })
+        })
+        .collect())
+}
+
+fn setup_fwupd(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
+    let btn_fwupd = try_extract_widget::<gtk4::Button>(page_builder, "btn_fwupd")?;
+    let window = window.clone();
+
+    btn_fwupd.connect_clicked(move |btn| {
+        info!("Servicing: Firmware Updates button clicked");
+
+        if core::aur::is_executable_in_path("fwupdmgr") {
+            check_firmware_updates(btn, &window);
+            return;
+        }
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-S", "--noconfirm", "--needed", "fwupd"])
+                    .description("Installing fwupd...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, "Install fwupd");
+
+        // Carry on to the update check once the install window is done.
+        let btn = btn.clone();
+        let window = window.clone();
+        gtk4::glib::timeout_add_local(Duration::from_millis(500), move || {
+            if task_runner::is_running() {
+                return gtk4::glib::ControlFlow::Continue;
+            }
+            if core::aur::is_executable_in_path("fwupdmgr") {
+                check_firmware_updates(&btn, &window);
+            }
+            gtk4::glib::ControlFlow::Break
+        });
+    });
+    Ok(())
+}
+
+fn check_firmware_updates(btn: &gtk4::Button, window: &ApplicationWindow) {
+    // Refreshing metadata goes over the network; keep it off the main loop.
+    btn.set_sensitive(false);
+    btn.set_label("Checking for Firmware...");
+    let (tx, rx) = async_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking(fwupd_updates());
+    });
+
+    let btn = btn.clone();
+    let window = window.clone();
+    gtk4::glib::MainContext::default().spawn_local(async move {
+        let result = rx.recv().await;
+        btn.set_label("Firmware Updates");
+        btn.set_sensitive(true);
+        match result {
+            Ok(Ok(updates)) if updates.is_empty() => show_simple_info_dialog(
+                &window,
+                "Firmware Updates",
+                "object-select-symbolic",
+                "All firmware is up to date.",
+                None,
+            ),
+            Ok(Ok(updates)) => show_fwupd_dialog(&window, updates),
+            Ok(Err(e)) => show_simple_info_dialog(
+                &window,
+                "Firmware Updates",
+                "dialog-warning-symbolic",
+                "Could not check for firmware updates.",
+                Some(&e),
+            ),
+            Err(_) => {}
+        }
+    });
+}
+
+fn show_fwupd_dialog(window: &ApplicationWindow, updates: Vec<FirmwareUpdate>) {
+    let mut config = SelectionDialogConfig::new(
+        "Firmware Updates",
+        "These devices have newer firmware on the LVFS. Keep the machine on AC power \
+         and don't unplug updated devices until the run has finished.",
+    )
+    .confirm_label("Update");
+    for update in &updates {
+        config = config.add_option(
+            SelectionOption::new(
+                &update.device_id,
+                &format!("{} → {}", update.device, update.version),
+                &update.summary,
+                false,
+            )
+            .preselected(true),
+        );
+    }
+
+    let window = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let chosen: Vec<&FirmwareUpdate> = updates
+            .iter()
+            .filter(|u| selected.contains(&u.device_id))
+            .collect();
+
+        let mut commands = CommandSequence::new();
+        for update in &chosen {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("fwupdmgr")
+                    .args(&[
+                        "update",
+                        &update.device_id,
+                        "--assume-yes",
+                        "--no-reboot-check",
+                    ])
+                    .description(&format!("Updating {} firmware...", update.device))
+                    .capture_output(true)
+                    .build(),
+            );
+        }
+
+        // Staged updates often only apply on the next boot; say so last.
+        let guidance: Vec<&str> = chosen.iter().filter_map(|u| u.guidance.as_deref()).collect();
+        if !guidance.is_empty() {
+            let mut args = vec!["%s\\n"];
+            args.extend(guidance);
+            commands = commands.then(
+                Command::builder()
+                    .normal()
+                    .program("printf")
+                    .args(&args)
+                    .description("Reboot to finish the firmware update")
+                    .build(),
+            );
+        }
+
+        task_runner::run(window.upcast_ref(), commands.build(), "Firmware Updates");
+    });
+}
+
 /// Service definition for the optimization toggles.
 struct ServiceDef {
     switch_id: &'static str,
@@ -1482,9 +2807,9 @@ const OPTIMIZATION_SERVICES: &[ServiceDef] = &[
     },
 ];
 
-fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindow) {
+fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindow) -> Result<(), WidgetError> {
     for svc in OPTIMIZATION_SERVICES {
-        let button = extract_widget::<ToggleButton>(page_builder, svc.switch_id);
+        let button = try_extract_widget::<ToggleButton>(page_builder, svc.switch_id)?;
 
         // Set initial state based on whether the service is currently enabled
         let enabled = if svc.is_user {
@@ -1592,4 +2917,5 @@ fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindo
             }
         });
     }
+    Ok(())
 }
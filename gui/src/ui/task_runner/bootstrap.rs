@@ -0,0 +1,101 @@
+//! Set up paru when a sequence needs an AUR helper and none is installed.
+//!
+//! Stock Arch ships without one. Rather than failing at the first AUR step,
+//! [`run`](super::run) offers to build `paru-bin` with makepkg and then
+//! starts the original sequence once the helper is on `PATH`.
+
+use std::time::Duration;
+
+use gtk4::glib;
+use gtk4::Window;
+use log::{info, warn};
+
+use super::{Command, CommandSequence};
+
+/// Prebuilt package, so the bootstrap doesn't need a Rust toolchain.
+const PARU_AUR_REPO: &str = "https://aur.archlinux.org/paru-bin.git";
+const WAIT_POLL: Duration = Duration::from_millis(500);
+
+/// Explain why `title` can't run yet and offer to install paru first.
+pub(super) fn offer(parent: &Window, commands: CommandSequence, title: &str) {
+    let message = format!(
+        "<b>{}</b> installs packages from the AUR, which needs an AUR helper \
+         such as paru or yay.\n\nThe toolkit can set up <b>paru</b> now: it \
+         installs base-devel and git, builds paru with makepkg, and then \
+         carries on where you left off.",
+        glib::markup_escape_text(title)
+    );
+    let parent_owned = parent.clone();
+    let title_owned = title.to_owned();
+    crate::ui::dialogs::warning::show_warning_confirmation(
+        parent,
+        "AUR Helper Needed",
+        &message,
+        move || bootstrap(parent_owned, commands, title_owned),
+    );
+}
+
+fn bootstrap(parent: Window, commands: CommandSequence, title: String) {
+    info!("No AUR helper, bootstrapping paru before {}", title);
+    super::start(&parent, plan(), "Paru Setup");
+
+    let mut pending = Some(commands);
+    glib::timeout_add_local(WAIT_POLL, move || {
+        if super::is_running() {
+            return glib::ControlFlow::Continue;
+        }
+        // A failed or cancelled build leaves no helper; the runner window
+        // already says why, so just drop the original request.
+        if crate::core::aur::refresh().is_some() {
+            if let Some(commands) = pending.take() {
+                super::run(&parent, commands, &title);
+            }
+        } else {
+            warn!("paru bootstrap did not produce a usable AUR helper");
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+/// Build tools, a fresh clone of the AUR package, then `makepkg -si`. The
+/// `sudo pacman -U` makepkg issues goes through the bundled sudo shim.
+fn plan() -> CommandSequence {
+    let dir = super::run_log::cache_dir().join("paru-bin");
+    let dir = dir.to_string_lossy();
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--needed", "--noconfirm", "base-devel", "git"])
+                .description("Installing build tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", &dir])
+                .description("Clearing an earlier paru download...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("git")
+                .args(&["clone", "--depth", "1", PARU_AUR_REPO, &dir])
+                .description("Downloading paru from the AUR...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("makepkg")
+                .args(&["-si", "--noconfirm"])
+                .cwd(&dir)
+                .description("Building and installing paru...")
+                .capture_output(true)
+                .build(),
+        )
+        .build()
+}
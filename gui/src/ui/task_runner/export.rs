@@ -0,0 +1,168 @@
+//! Turn a sequence into a standalone bash script that replays it on another
+//! Arch machine.
+//!
+//! Steps resolve the same way the runner resolves them, minus the parts that
+//! only exist on this machine: privileged steps run under `sudo` instead of
+//! the auth client, the AUR helper is called without `--sudo`, and the
+//! exporting user's name and home become `"$USER"` and `"$HOME"`.
+
+use super::pipeline::{flatpak_args, shell_quote};
+use super::{Command, Mode};
+
+/// Render `steps` as a bash script titled `title`.
+pub(super) fn script(title: &str, steps: &[Command]) -> String {
+    let helper = crate::core::aur_helper().unwrap_or("paru");
+
+    let mut out = String::new();
+    out.push_str("#!/usr/bin/env bash\n");
+    out.push_str(&format!(
+        "# {} — exported from CyberXero Toolkit {}\n",
+        title.replace('\n', " "),
+        crate::config::app_info::VERSION
+    ));
+    out.push_str("# Steps reflect this machine's state at export time (packages already\n");
+    out.push_str("# installed were skipped), so review before running elsewhere.\n");
+    out.push_str("# Run it as your normal user, not with sudo: \"$USER\" and \"$HOME\" stand\n");
+    out.push_str("# for the account it sets things up for.\n");
+    out.push_str("set -euo pipefail\n\n");
+
+    if steps.iter().any(|c| c.mode == Mode::Elevated) {
+        out.push_str("# Ask for the password once, up front.\nsudo -v\n\n");
+    }
+    if steps.iter().any(|c| c.mode == Mode::Aur) {
+        out.push_str(&format!(
+            "AUR_HELPER=\"${{AUR_HELPER:-{}}}\"\n\n",
+            helper
+        ));
+    }
+
+    for cmd in steps {
+        out.push_str(&format!("# {}\n", cmd.description.replace('\n', " ")));
+        let line = step_line(cmd);
+        match cmd.run_if.as_ref().map(|c| c.shell.as_deref()) {
+            None => out.push_str(&line),
+            Some(Some(test)) => out.push_str(&format!("if {}; then\n    {}\nfi", test, line)),
+            // No shell form of the condition: run it anyway, but don't let
+            // a step the toolkit would have skipped abort the script.
+            Some(None) if cmd.allow_failure => out.push_str(&line),
+            Some(None) => out.push_str(&format!("{} || true", line)),
+        }
+        out.push_str("\n\n");
+    }
+    out
+}
+
+fn step_line(cmd: &Command) -> String {
+    let env = crate::config::env::get();
+    let word = |arg: &str| portable_word(arg, &env.user, &env.home);
+    let quoted = |args: &[String]| args.iter().map(|a| word(a)).collect::<Vec<_>>().join(" ");
+
+    let mut words = Vec::new();
+    if let Some(limit) = cmd.timeout {
+        words.push(format!("timeout {}", limit.as_secs()));
+    }
+    if cmd.mode == Mode::Elevated {
+        words.push(String::from("sudo"));
+    }
+    // env(1) rather than a bare prefix, so it also survives sudo's reset
+    // and a leading `timeout`.
+    if !cmd.env.is_empty() {
+        words.push(String::from("env"));
+        words.extend(cmd.env.iter().map(|(k, v)| format!("{}={}", k, word(v))));
+    }
+    match cmd.mode {
+        Mode::Plain | Mode::Elevated => words.push(shell_quote(&cmd.program)),
+        Mode::Aur => words.push(String::from("\"$AUR_HELPER\"")),
+        Mode::Flatpak(_) => words.push(String::from("flatpak")),
+    }
+    let args = match cmd.mode {
+        Mode::Flatpak(action) => flatpak_args(action, &cmd.args),
+        _ => cmd.args.clone(),
+    };
+    if !args.is_empty() {
+        words.push(quoted(&args));
+    }
+
+    let mut line = words.join(" ");
+    if let Some(dir) = &cmd.cwd {
+        let quoted = word(&dir.to_string_lossy());
+        let dir = if dir.is_relative() {
+            format!("\"$HOME\"/{}", quoted)
+        } else {
+            quoted
+        };
+        line = format!("(cd {} && {})", dir, line);
+    }
+    if cmd.allow_failure {
+        line.push_str(" || true");
+    }
+    line
+}
+
+/// Quote `arg` for the script, swapping the exporting user's home directory
+/// for `"$HOME"` and their name, where it stands as a word of its own, for
+/// `"$USER"`. The rest is quoted piecewise, so substitutions inside a
+/// `bash -c` body still expand.
+fn portable_word(arg: &str, user: &str, home: &str) -> String {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || "_.-".contains(c);
+    // `needle` sits at byte `i` and isn't followed by more of a name.
+    let at = |i: usize, needle: &str| {
+        !needle.is_empty()
+            && arg[i..].starts_with(needle)
+            && !arg[i + needle.len()..].starts_with(is_name_char)
+    };
+
+    let mut out = String::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < arg.len() {
+        let var = if at(i, home) {
+            Some((home.len(), "\"$HOME\""))
+        } else if at(i, user) && !arg[..i].ends_with(is_name_char) {
+            Some((user.len(), "\"$USER\""))
+        } else {
+            None
+        };
+        match var {
+            Some((len, var)) => {
+                if start < i {
+                    out.push_str(&shell_quote(&arg[start..i]));
+                }
+                out.push_str(var);
+                i += len;
+                start = i;
+            }
+            None => i += arg[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    if start == 0 {
+        return shell_quote(arg);
+    }
+    if start < arg.len() {
+        out.push_str(&shell_quote(&arg[start..]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_word() {
+        let word = |arg: &str| portable_word(arg, "alice", "/home/alice");
+        assert_eq!(word("alice"), "\"$USER\"");
+        assert_eq!(word("libvirt"), "libvirt");
+        assert_eq!(word("alicex"), "alicex");
+        assert_eq!(
+            word("/home/alice/.local/share/applications"),
+            "\"$HOME\"/.local/share/applications"
+        );
+        assert_eq!(word("/home/alice2/x"), "/home/alice2/x");
+        assert_eq!(
+            word("usermod -aG docker alice"),
+            "'usermod -aG docker '\"$USER\""
+        );
+    }
+}
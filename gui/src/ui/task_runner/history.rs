@@ -0,0 +1,145 @@
+//! Index of finished runs, shown by the History dialog.
+//!
+//! Stored as TOML at `~/.cache/cyberxero-toolkit/history.toml`, next to the
+//! `logs/` directory the transcripts live in. Entries older than the
+//! configured age are dropped, together with their logs, whenever the index
+//! is read or written, so neither grows without bound.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+/// How a run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Outcome {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    /// Identifies the run across retries; see [`record`].
+    #[serde(default)]
+    pub(crate) id: u64,
+    /// Runner window title, e.g. "Install Docker".
+    pub(crate) title: String,
+    /// Unix time the run concluded.
+    pub(crate) finished: u64,
+    pub(crate) outcome: Outcome,
+    /// First line of the final status message.
+    pub(crate) message: String,
+    /// Transcript, if any step captured output.
+    pub(crate) log: Option<PathBuf>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    #[serde(default)]
+    runs: Vec<HistoryEntry>,
+}
+
+fn index_path() -> PathBuf {
+    super::run_log::cache_dir().join("history.toml")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn read() -> Index {
+    let path = index_path();
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return Index::default();
+    };
+    toml::from_str(&raw).unwrap_or_else(|e| {
+        warn!("Ignoring malformed {}: {}", path.display(), e);
+        Index::default()
+    })
+}
+
+fn write(index: &Index) {
+    let path = index_path();
+    let result = toml::to_string(index)
+        .map_err(anyhow::Error::from)
+        .and_then(|raw| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, raw)?;
+            Ok(())
+        });
+    if let Err(e) = result {
+        warn!("Failed to save {}: {}", path.display(), e);
+    }
+}
+
+/// Drop entries past the retention age and delete their logs. Returns
+/// whether anything was removed.
+fn prune(index: &mut Index) -> bool {
+    let max_age = u64::from(crate::core::settings::history_days()) * 86_400;
+    let cutoff = now().saturating_sub(max_age);
+    let before = index.runs.len();
+    index.runs.retain(|entry| {
+        if entry.finished >= cutoff {
+            return true;
+        }
+        if let Some(log) = &entry.log {
+            let _ = fs::remove_file(log);
+        }
+        false
+    });
+    let removed = before - index.runs.len();
+    if removed > 0 {
+        debug!("Pruned {} old run(s) from history", removed);
+    }
+    removed > 0
+}
+
+/// Add a finished run to the index and return its id. Passing the id from
+/// an earlier call replaces that entry instead, so a retried run stays one
+/// entry carrying its latest outcome.
+pub(super) fn record(
+    previous: Option<u64>,
+    title: &str,
+    outcome: Outcome,
+    message: &str,
+    log: Option<PathBuf>,
+) -> u64 {
+    let mut index = read();
+    prune(&mut index);
+    let id = match previous {
+        Some(id) => {
+            index.runs.retain(|entry| entry.id != id);
+            id
+        }
+        None => index.runs.iter().map(|entry| entry.id).max().unwrap_or(0) + 1,
+    };
+    index.runs.push(HistoryEntry {
+        id,
+        title: title.to_owned(),
+        finished: now(),
+        outcome,
+        message: message.lines().next().unwrap_or_default().to_owned(),
+        log,
+    });
+    write(&index);
+    id
+}
+
+/// Recorded runs, newest first.
+pub(crate) fn load() -> Vec<HistoryEntry> {
+    let mut index = read();
+    if prune(&mut index) {
+        write(&index);
+    }
+    index.runs.reverse();
+    index.runs
+}
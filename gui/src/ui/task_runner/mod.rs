@@ -21,15 +21,25 @@
 //! task_runner::run(&parent, seq, "Setup");
 //! ```
 
+pub(crate) mod history;
+mod bootstrap;
+mod export;
 mod pipeline;
+mod progress;
+mod run_log;
 mod view;
 
+use std::fmt;
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use gtk4::prelude::*;
 use gtk4::Window;
 use log::{error, info, warn};
 
+pub(crate) use self::pipeline::shell_quote;
 use self::pipeline::Pipeline;
 use self::view::{RunnerView, Tag};
 
@@ -44,6 +54,34 @@ pub struct Command {
     pub(super) program: String,
     pub(super) args: Vec<String>,
     pub(super) description: String,
+    pub(super) capture_output: bool,
+    pub(super) allow_failure: bool,
+    pub(super) timeout: Option<Duration>,
+    pub(super) run_if: Option<Condition>,
+    pub(super) env: Vec<(String, String)>,
+    pub(super) cwd: Option<PathBuf>,
+}
+
+/// Predicate set with [`CommandDraft::run_if`], plus the shell test that
+/// stands in for it in exported scripts.
+#[derive(Clone)]
+pub(super) struct Condition {
+    check: Rc<dyn Fn() -> bool>,
+    pub(super) shell: Option<String>,
+}
+
+impl Condition {
+    pub(super) fn holds(&self) -> bool {
+        (self.check)()
+    }
+}
+
+impl fmt::Debug for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condition")
+            .field("shell", &self.shell)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -51,6 +89,13 @@ pub(super) enum Mode {
     Plain,
     Elevated,
     Aur,
+    Flatpak(FlatpakAction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FlatpakAction {
+    Install,
+    Uninstall,
 }
 
 impl Command {
@@ -80,6 +125,18 @@ impl CommandInit {
     pub fn aur(self) -> CommandDraft {
         CommandDraft::fresh(Mode::Aur)
     }
+
+    /// Installs the Flatpak refs given via [`CommandDraft::args`] from
+    /// Flathub. The runner assembles the `flatpak` invocation and adds the
+    /// Flathub remote first if it's missing.
+    pub fn flatpak_install(self) -> CommandDraft {
+        CommandDraft::fresh(Mode::Flatpak(FlatpakAction::Install))
+    }
+
+    /// Uninstalls the Flatpak refs given via [`CommandDraft::args`].
+    pub fn flatpak_uninstall(self) -> CommandDraft {
+        CommandDraft::fresh(Mode::Flatpak(FlatpakAction::Uninstall))
+    }
 }
 
 /// Mutable draft assembled by chained setters.
@@ -89,6 +146,13 @@ pub struct CommandDraft {
     program: Option<String>,
     args: Vec<String>,
     description: Option<String>,
+    capture_output: bool,
+    allow_failure: bool,
+    timeout: Option<Duration>,
+    run_if: Option<Rc<dyn Fn() -> bool>>,
+    run_if_shell: Option<String>,
+    env: Vec<(String, String)>,
+    cwd: Option<PathBuf>,
 }
 
 impl CommandDraft {
@@ -98,11 +162,18 @@ impl CommandDraft {
             program: None,
             args: Vec::new(),
             description: None,
+            capture_output: false,
+            allow_failure: false,
+            timeout: None,
+            run_if: None,
+            run_if_shell: None,
+            env: Vec::new(),
+            cwd: None,
         }
     }
 
-    /// Program to run. Ignored for AUR commands — the helper is picked
-    /// automatically.
+    /// Program to run. Ignored for AUR and Flatpak commands — the helper is
+    /// picked automatically.
     pub fn program(mut self, program: &str) -> Self {
         self.program = Some(program.to_owned());
         self
@@ -120,10 +191,70 @@ impl CommandDraft {
         self
     }
 
+    /// Also write this step's stdout/stderr to the run's log file under
+    /// `~/.cache/cyberxero-toolkit/logs/`. The path is reported in the
+    /// failure message so users can attach it to bug reports.
+    pub fn capture_output(mut self, capture: bool) -> Self {
+        self.capture_output = capture;
+        self
+    }
+
+    /// Mark the step as non-critical: a non-zero exit is shown as a warning
+    /// and the sequence carries on instead of aborting.
+    pub fn allow_failure(mut self, allow: bool) -> Self {
+        self.allow_failure = allow;
+        self
+    }
+
+    /// Stop the step and treat it as failed if it runs longer than `limit`.
+    /// Unset by default: steps that may sit at an authentication prompt or
+    /// run a long build shouldn't be cut short, so only set this where a
+    /// hang (e.g. an unresponsive keyserver) is the realistic failure.
+    pub fn timeout(mut self, limit: Duration) -> Self {
+        self.timeout = Some(limit);
+        self
+    }
+
+    /// Set an environment variable for this step. Works for every flavour:
+    /// privileged steps hand it to the auth daemon explicitly, and the AUR
+    /// helper passes its environment on to makepkg and to the root steps it
+    /// runs through the auth client. Later values for the same key win.
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Run the step inside `dir` instead of wherever the toolkit was
+    /// started from. Relative paths are taken from the user's home. The
+    /// directory must exist by the time the step runs, or the step fails
+    /// before anything is launched.
+    pub fn cwd(mut self, dir: &str) -> Self {
+        self.cwd = Some(PathBuf::from(dir));
+        self
+    }
+
+    /// Only run the step if `condition` holds when the runner reaches it;
+    /// otherwise it is marked as not needed and the sequence moves on.
+    /// Evaluated on the main thread, so keep it to quick checks such as
+    /// [`is_package_installed`](crate::core::is_package_installed).
+    pub fn run_if(mut self, condition: impl Fn() -> bool + 'static) -> Self {
+        self.run_if = Some(Rc::new(condition));
+        self
+    }
+
+    /// Shell test equivalent to the [`run_if`](Self::run_if) predicate,
+    /// used when the sequence is exported as a script. Without one the
+    /// exported step runs unconditionally and its failure is ignored.
+    pub fn run_if_shell(mut self, test: &str) -> Self {
+        self.run_if_shell = Some(test.to_owned());
+        self
+    }
+
     /// Finish the draft. Panics if required fields are missing.
     pub fn build(self) -> Command {
         let program = match self.mode {
             Mode::Aur => String::from("aur"),
+            Mode::Flatpak(_) => String::from("flatpak"),
             _ => self
                 .program
                 .expect("program is required for normal and privileged commands"),
@@ -134,6 +265,15 @@ impl CommandDraft {
             program,
             args: self.args,
             description,
+            capture_output: self.capture_output,
+            allow_failure: self.allow_failure,
+            timeout: self.timeout,
+            run_if: self.run_if.map(|check| Condition {
+                check,
+                shell: self.run_if_shell,
+            }),
+            env: self.env,
+            cwd: self.cwd,
         }
     }
 }
@@ -142,6 +282,10 @@ impl CommandDraft {
 #[derive(Debug, Default)]
 pub struct CommandSequence {
     pub(super) steps: Vec<Command>,
+    /// Ask before running; `None` asks only for destructive sequences.
+    confirm: Option<bool>,
+    /// Shown in the output pane once every step has succeeded.
+    note: Option<String>,
 }
 
 impl CommandSequence {
@@ -155,6 +299,22 @@ impl CommandSequence {
         self
     }
 
+    /// Always (`true`) or never (`false`) list the steps for the user to
+    /// approve before anything runs. Unset, only sequences that remove
+    /// packages or files ask. Pass `false` where the caller's own dialog
+    /// already showed what will happen.
+    pub fn confirm(mut self, ask: bool) -> Self {
+        self.confirm = Some(ask);
+        self
+    }
+
+    /// Text to show after a successful run, for what the user should do
+    /// next (a URL to open, a reboot to schedule). Dry runs don't show it.
+    pub fn completion_note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_owned());
+        self
+    }
+
     /// Identity terminator kept for call-site readability.
     pub fn build(self) -> Self {
         self
@@ -170,14 +330,31 @@ impl CommandSequence {
 // ---------------------------------------------------------------------------
 
 static ACTION_RUNNING: AtomicBool = AtomicBool::new(false);
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
 
 /// Returns true while a sequence is in flight.
 pub fn is_running() -> bool {
     ACTION_RUNNING.load(Ordering::SeqCst)
 }
 
+/// In dry-run mode every step is resolved exactly as it would be spawned
+/// (auth client / AUR helper / flatpak wrapping included), printed to the
+/// output pane and marked done without running anything.
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::SeqCst)
+}
+
 /// Open the runner dialog and drive the sequence to completion.
 ///
+/// Destructive sequences (see [`CommandSequence::confirm`]) first list their
+/// steps in a confirmation dialog and only start once the user agrees.
+/// Sequences with AUR steps on a system without an AUR helper offer to set
+/// up paru first, then run.
+///
 /// A second call while another sequence is already running is ignored and
 /// logged — the caller should gate on [`is_running`] if that matters.
 pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
@@ -190,19 +367,70 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
         return;
     }
 
+    let needs_helper = commands.steps.iter().any(|c| c.mode == Mode::Aur);
+    if needs_helper && crate::core::aur_helper().is_none() && !is_dry_run() {
+        bootstrap::offer(parent, commands, title);
+        return;
+    }
+
+    let ask = commands
+        .confirm
+        .unwrap_or_else(|| commands.steps.iter().any(is_destructive));
+    if !ask || is_dry_run() {
+        start(parent, commands, title);
+        return;
+    }
+
+    let mut message = String::from("The following steps will run:\n");
+    for step in &commands.steps {
+        message.push_str(&format!(
+            "\n• {}",
+            gtk4::glib::markup_escape_text(step.description.trim_end_matches("..."))
+        ));
+    }
+    let parent_owned = parent.clone();
+    let title_owned = title.to_owned();
+    crate::ui::dialogs::warning::show_warning_confirmation(parent, title, &message, move || {
+        start(&parent_owned, commands, &title_owned);
+    });
+}
+
+/// Removes packages, Flatpaks or files: worth a second look before running.
+/// A conditional step that would be skipped right now doesn't count.
+fn is_destructive(step: &Command) -> bool {
+    if step.run_if.as_ref().is_some_and(|c| !c.holds()) {
+        return false;
+    }
+    match step.mode {
+        Mode::Flatpak(FlatpakAction::Uninstall) => true,
+        Mode::Aur => step.args.iter().any(|a| a.starts_with("-R")),
+        Mode::Elevated => {
+            step.program == "rm"
+                || (step.program == "pacman" && step.args.iter().any(|a| a.starts_with("-R")))
+        }
+        Mode::Flatpak(FlatpakAction::Install) | Mode::Plain => false,
+    }
+}
+
+fn start(parent: &Window, commands: CommandSequence, title: &str) {
+    // The user may have started something else while the dialog was open.
+    if is_running() {
+        warn!("run() called while another sequence is active — ignoring");
+        return;
+    }
+
     ACTION_RUNNING.store(true, Ordering::SeqCst);
 
+    let commands = with_flathub_remote(commands);
+
     let builder = gtk4::Builder::from_resource(crate::config::resources::dialogs::TASK_LIST);
     let view = RunnerView::from_builder(&builder, &commands.steps);
     view.window().set_transient_for(Some(parent));
     view.window().set_title(Some(title));
 
-    let wants_daemon = commands
-        .steps
-        .iter()
-        .any(|c| matches!(c.mode, Mode::Elevated | Mode::Aur));
-
-    if wants_daemon {
+    if is_dry_run() {
+        view.show_output();
+    } else if needs_daemon(&commands.steps) {
         if let Err(e) = crate::core::daemon::start_daemon() {
             error!("daemon start failed: {}", e);
             let msg = format!("Failed to start authentication daemon: {}\n", e);
@@ -218,5 +446,48 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
     }
 
     view.window().present();
-    Pipeline::new(view, commands.steps).start();
+    Pipeline::new(view, commands.steps, commands.note).start();
+}
+
+/// Prepend steps that add the Flathub remote when the sequence installs
+/// Flatpaks and the remote isn't configured yet, or enable it when it was
+/// disabled. Without it every install fails with a fairly cryptic "No
+/// remote refs found" error.
+fn with_flathub_remote(mut commands: CommandSequence) -> CommandSequence {
+    use crate::config::flatpak::{FLATHUB, FLATHUB_REPO};
+
+    let installs = commands
+        .steps
+        .iter()
+        .any(|c| c.mode == Mode::Flatpak(FlatpakAction::Install));
+    if !installs || crate::core::package::has_flatpak_remote(FLATHUB) {
+        return commands;
+    }
+
+    info!("Flathub remote missing or disabled, setting it up before the first install");
+    // remote-add is a no-op for a remote that exists but is disabled, so
+    // follow it with an explicit enable; both are harmless when not needed.
+    let setup = [
+        Command::builder()
+            .privileged()
+            .program("flatpak")
+            .args(&["remote-add", "--if-not-exists", FLATHUB, FLATHUB_REPO])
+            .description("Adding the Flathub remote...")
+            .build(),
+        Command::builder()
+            .privileged()
+            .program("flatpak")
+            .args(&["remote-modify", "--enable", FLATHUB])
+            .description("Enabling the Flathub remote...")
+            .build(),
+    ];
+    commands.steps.splice(0..0, setup);
+    commands
+}
+
+/// True if any of `steps` has to be funnelled through the auth daemon.
+fn needs_daemon(steps: &[Command]) -> bool {
+    steps
+        .iter()
+        .any(|c| matches!(c.mode, Mode::Elevated | Mode::Aur))
 }
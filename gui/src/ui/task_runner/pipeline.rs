@@ -5,7 +5,10 @@
 //! single `timeout_add_local` pump; subprocess I/O is handled on worker
 //! threads and forwarded over `mpsc` channels.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command as SysCommand, Stdio};
 use std::rc::Rc;
 use std::sync::atomic::Ordering;
@@ -14,31 +17,66 @@ use std::thread;
 use std::time::Duration;
 
 use cyberxero_auth::utils::read_buffer_with_line_processing;
-use gtk4::glib;
 use gtk4::prelude::*;
+use gtk4::{gio, glib};
 use log::{error, info, warn};
 
+use super::history::{self, Outcome};
+use super::progress;
+use super::run_log::RunLog;
 use super::view::{RunnerView, StepState, Tag};
-use super::{Command, Mode, ACTION_RUNNING};
+use super::{needs_daemon, Command, FlatpakAction, Mode, ACTION_RUNNING};
 
-const MSG_CANCEL_PENDING: &str = "Waiting for current step to finish…";
+const MSG_CANCEL_PENDING: &str = "Stopping current step…";
+const MSG_STALE_LOCK: &str = "Pacman's database lock was left behind by an interrupted \
+     transaction. Remove it with Servicing → Unlock Pacman DB, then retry.";
 const MSG_CANCELLED: &str = "Operation cancelled by user";
 const MSG_SUCCESS: &str = "All steps completed successfully";
+/// Output lines kept per step for the "Copy details" report.
+const DETAILS_TAIL_LINES: usize = 40;
+const MSG_DRY_RUN: &str = "Dry run complete — nothing was executed";
 
 pub(super) struct Pipeline {
     view: Rc<RunnerView>,
     steps: Rc<Vec<Command>>,
     cursor: Cell<usize>,
     cancelled: Cell<bool>,
+    /// Waiting at a step boundary because the user pressed Pause.
+    held: Cell<bool>,
+    skipped: Cell<usize>,
+    log: RefCell<Option<RunLog>>,
+    /// Pending per-step timeout, if the running step has one.
+    deadline: RefCell<Option<glib::SourceId>>,
+    timed_out: Cell<bool>,
+    /// Pid of the running step's process (and process group).
+    running: Cell<Option<u32>>,
+    /// Resolved command line of the current step, for failure reports.
+    command_line: RefCell<Option<String>>,
+    /// Last [`DETAILS_TAIL_LINES`] lines of the current step's output.
+    tail: RefCell<VecDeque<String>>,
+    /// See [`CommandSequence::completion_note`](super::CommandSequence::completion_note).
+    note: Option<String>,
+    /// History entry written when the run first concluded; retries update it.
+    history_id: Cell<Option<u64>>,
 }
 
 impl Pipeline {
-    pub(super) fn new(view: Rc<RunnerView>, steps: Vec<Command>) -> Rc<Self> {
+    pub(super) fn new(view: Rc<RunnerView>, steps: Vec<Command>, note: Option<String>) -> Rc<Self> {
         Rc::new(Self {
             view,
             steps: Rc::new(steps),
             cursor: Cell::new(0),
             cancelled: Cell::new(false),
+            held: Cell::new(false),
+            skipped: Cell::new(0),
+            log: RefCell::new(None),
+            deadline: RefCell::new(None),
+            timed_out: Cell::new(false),
+            running: Cell::new(None),
+            command_line: RefCell::new(None),
+            tail: RefCell::new(VecDeque::new()),
+            note,
+            history_id: Cell::new(None),
         })
     }
 
@@ -50,17 +88,85 @@ impl Pipeline {
             me.cancelled.set(true);
             me.view.disable_cancel();
             me.view.set_title(MSG_CANCEL_PENDING);
+            me.stop_running();
+            // Nothing is running while held, so nothing would call back.
+            if me.held.replace(false) {
+                me.advance();
+            }
+        });
+
+        let me = self.clone();
+        self.view.on_pause_toggled(move |paused| {
+            if paused {
+                me.view.append(
+                    "\nPausing before the next step; the current one will finish first.\n",
+                    Tag::Header,
+                );
+            } else if me.held.replace(false) {
+                me.view.append("\nResuming.\n", Tag::Header);
+                me.view.start_clock();
+                me.advance();
+            }
         });
 
+        let me = self.clone();
+        self.view.on_retry(move || me.retry());
+
         let view_for_close = self.view.clone();
         self.view.on_close(move || view_for_close.window().close());
 
+        let me = self.clone();
+        self.view.on_export(move || me.export_script());
+
         let me = self.clone();
         self.view.on_window_close(move || {
             ACTION_RUNNING.store(false, Ordering::SeqCst);
             me.cancelled.set(true);
+            me.stop_running();
+            me.view.stop_clock();
+            me.flush_log();
         });
 
+        self.view.start_clock();
+        self.advance();
+    }
+
+    /// Resume the sequence at the step that failed or was cancelled. Steps
+    /// before the cursor already succeeded and keep their state.
+    fn retry(self: &Rc<Self>) {
+        let cursor = self.cursor.get();
+        if cursor >= self.steps.len() {
+            return;
+        }
+        if super::is_running() {
+            warn!("retry requested while another sequence is active — ignoring");
+            return;
+        }
+
+        info!("retrying from step {} of {}", cursor + 1, self.steps.len());
+        ACTION_RUNNING.store(true, Ordering::SeqCst);
+        self.cancelled.set(false);
+        // Cleared first so releasing the Pause toggle below doesn't resume.
+        self.held.set(false);
+        self.view.reset_for_retry();
+        self.view.append(
+            &format!("\n--- Retrying from step {} ---\n", cursor + 1),
+            Tag::Header,
+        );
+        self.view.start_clock();
+
+        // The daemon is torn down whenever a run concludes, so bring it back
+        // if anything still ahead of us needs it.
+        if !super::is_dry_run() && needs_daemon(&self.steps[cursor..]) {
+            if let Err(e) = crate::core::daemon::start_daemon() {
+                error!("daemon start failed: {}", e);
+                let msg = format!("Failed to start authentication daemon: {}", e);
+                self.view.set_step_state(cursor, StepState::Failed);
+                self.conclude(false, &msg);
+                return;
+            }
+        }
+
         self.advance();
     }
 
@@ -78,31 +184,108 @@ impl Pipeline {
         }
 
         if cursor >= self.steps.len() {
-            self.conclude(true, MSG_SUCCESS);
+            let msg = match self.skipped.get() {
+                _ if super::is_dry_run() => String::from(MSG_DRY_RUN),
+                0 => String::from(MSG_SUCCESS),
+                1 => String::from("Completed — 1 optional step failed"),
+                n => format!("Completed — {} optional steps failed", n),
+            };
+            self.conclude(true, &msg);
+            return;
+        }
+
+        // A running process can't be paused safely, so the hold happens here,
+        // between steps, and the clock stops with it.
+        if self.view.pause_requested() {
+            info!("paused before step {} of {}", cursor + 1, self.steps.len());
+            self.held.set(true);
+            self.view.stop_clock();
+            self.view.set_title(&format!(
+                "Paused before step {} of {}",
+                cursor + 1,
+                self.steps.len()
+            ));
             return;
         }
 
         let step = &self.steps[cursor];
+        self.command_line.replace(None);
+        self.tail.borrow_mut().clear();
         self.view.set_step_state(cursor, StepState::Running);
         self.view.set_title(&step.description);
         self.view.emit_step_banner(&step.description);
 
-        let (program, args) = match resolve(step) {
-            Ok(pair) => pair,
+        if step.run_if.as_ref().is_some_and(|c| !c.holds()) {
+            info!("step {} not needed, skipping", cursor + 1);
+            self.view.append("Not needed on this system — skipping.\n", Tag::Stdout);
+            self.view.set_step_state(cursor, StepState::NotNeeded);
+            self.cursor.set(cursor + 1);
+            self.advance();
+            return;
+        }
+
+        let dry_run = super::is_dry_run();
+
+        if !dry_run && uses_pacman(step) && crate::core::package::has_stale_pacman_lock() {
+            warn!("stale {} found", crate::core::package::PACMAN_DB_LOCK);
+            self.view.append(&format!("{}\n", MSG_STALE_LOCK), Tag::Error);
+            self.view.set_step_state(cursor, StepState::Failed);
+            self.conclude(false, MSG_STALE_LOCK);
+            return;
+        }
+
+        let resolved =
+            working_dir(step).and_then(|dir| Ok((resolve(step, dir.as_deref())?, dir)));
+        let ((program, args), dir) = match resolved {
+            Ok(resolved) => resolved,
             Err(e) => {
                 let text = format!("Failed to prepare command: {}\n", e);
                 self.view.append(&text, Tag::Error);
                 self.view.set_step_state(cursor, StepState::Failed);
+                self.offer_details(&format!("could not prepare command: {}", e));
                 self.conclude(false, &format!("Failed to prepare command: {}", e));
                 return;
             }
         };
 
+        // Privileged steps already carry theirs as `--env` arguments.
+        let env = if step.mode == Mode::Elevated { &[][..] } else { &step.env[..] };
+        let mut line = command_line(env, &program, &args);
+        if let Some(dir) = dir.as_ref().filter(|_| step.mode != Mode::Elevated) {
+            line = format!("cd {} && {}", shell_quote(&dir.to_string_lossy()), line);
+        }
+        self.command_line.replace(Some(line.clone()));
+
+        if dry_run {
+            info!("dry run: {} {:?}", program, args);
+            self.view.append(&format!("$ {}\n", line), Tag::Stdout);
+            self.view.set_step_state(cursor, StepState::Success);
+            self.cursor.set(cursor + 1);
+            self.advance();
+            return;
+        }
+
         info!("running: {} {:?}", program, args);
 
+        if step.capture_output {
+            self.open_log();
+            self.capture(&format!(
+                "\n=== {} ===\n$ {} {}\n",
+                step.description,
+                program,
+                args.join(" ")
+            ));
+        }
+
         let mut sys = SysCommand::new(&program);
         sys.args(&args).stdout(Stdio::piped()).stderr(Stdio::piped());
+        // Own process group, so a timeout can stop helpers the step spawned.
+        sys.process_group(0);
         install_path_shim(&mut sys);
+        sys.envs(step.env.iter().map(|(k, v)| (k, v)));
+        if let Some(dir) = &dir {
+            sys.current_dir(dir);
+        }
 
         let child = match sys.spawn() {
             Ok(c) => c,
@@ -110,14 +293,59 @@ impl Pipeline {
                 let text = format!("Failed to start operation: {}\n", e);
                 self.view.append(&text, Tag::Error);
                 self.view.set_step_state(cursor, StepState::Failed);
+                self.offer_details(&format!("could not start: {}", e));
                 self.conclude(false, &format!("Failed to start operation: {}", e));
                 return;
             }
         };
 
+        self.running.set(Some(child.id()));
+        if let Some(limit) = step.timeout {
+            self.arm_deadline(child.id(), limit);
+        }
         self.pump(child);
     }
 
+    /// Terminate the step's process group once `limit` elapses. Disarmed by
+    /// [`disarm_deadline`](Self::disarm_deadline) when the step exits first.
+    fn arm_deadline(self: &Rc<Self>, pid: u32, limit: Duration) {
+        let me = self.clone();
+        let id = glib::timeout_add_local_once(limit, move || {
+            // The source is gone once it fires; nothing left to disarm.
+            me.deadline.take();
+            me.timed_out.set(true);
+            warn!("step timed out after {}s, terminating pid {}", limit.as_secs(), pid);
+            me.view.append(
+                &format!("\nNo result after {}s — stopping the step.\n", limit.as_secs()),
+                Tag::Error,
+            );
+            terminate_group(pid);
+        });
+        *self.deadline.borrow_mut() = Some(id);
+    }
+
+    /// Terminate the running step, if any. The reaper thread in
+    /// [`pump`](Self::pump) still collects the exit status, so the step
+    /// finishes through the normal path and is marked cancelled there.
+    ///
+    /// Elevated steps run as children of the auth daemon; killing our auth
+    /// client makes the daemon terminate the root process on its side. A
+    /// pacman transaction stopped this way can leave its database lock
+    /// behind, which [`advance`](Self::advance) reports before the next
+    /// pacman or AUR step.
+    fn stop_running(&self) {
+        if let Some(pid) = self.running.get() {
+            info!("terminating running step (pid {})", pid);
+            terminate_group(pid);
+        }
+    }
+
+    fn disarm_deadline(&self) {
+        if let Some(id) = self.deadline.take() {
+            id.remove();
+        }
+    }
+
     /// Spawn worker threads to drain stdout/stderr and reap the child, then
     /// install a GLib tick that forwards the channels to the text buffer and
     /// hands control back to [`advance`] when the process exits.
@@ -177,16 +405,19 @@ impl Pipeline {
             *slot.lock().unwrap() = Some(code);
         });
 
+        let capture = self.steps[self.cursor.get()].capture_output;
         let me = self.clone();
         glib::timeout_add_local(Duration::from_millis(40), move || {
-            drain(&rx_out, |line| me.view.append_stream(&line, Tag::Stdout));
-            drain(&rx_err, |line| me.view.append_stream(&line, Tag::Stderr));
+            drain(&rx_out, |line| me.forward(&line, Tag::Stdout, capture));
+            drain(&rx_err, |line| me.forward(&line, Tag::Stderr, capture));
 
             let done = exit.lock().unwrap().take();
             if let Some(code) = done {
+                me.running.set(None);
+                me.disarm_deadline();
                 // Drain any remaining residual lines before finalizing.
-                drain(&rx_out, |line| me.view.append_stream(&line, Tag::Stdout));
-                drain(&rx_err, |line| me.view.append_stream(&line, Tag::Stderr));
+                drain(&rx_out, |line| me.forward(&line, Tag::Stdout, capture));
+                drain(&rx_err, |line| me.forward(&line, Tag::Stderr, capture));
                 me.finish_step(code);
                 glib::ControlFlow::Break
             } else {
@@ -195,8 +426,59 @@ impl Pipeline {
         });
     }
 
+    fn forward(&self, line: &str, tag: Tag, capture: bool) {
+        self.view.append_stream(line, tag);
+        if let Some(fraction) = progress::parse(line) {
+            self.view.set_step_progress(self.cursor.get(), fraction);
+        }
+        if capture {
+            self.capture(line);
+        }
+
+        let mut tail = self.tail.borrow_mut();
+        for l in line.lines() {
+            if tail.len() == DETAILS_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(strip_ansi_escapes::strip_str(l));
+        }
+    }
+
+    /// Put a "Copy details" report for the failed current step on offer:
+    /// description, resolved command, outcome, and — for steps that opted
+    /// into output capture — the tail of their output.
+    fn offer_details(&self, outcome: &str) {
+        let cursor = self.cursor.get();
+        let Some(step) = self.steps.get(cursor) else {
+            return;
+        };
+
+        let mut text = format!(
+            "Step {} of {}: {}\n",
+            cursor + 1,
+            self.steps.len(),
+            step.description
+        );
+        if let Some(line) = self.command_line.borrow().as_deref() {
+            text.push_str(&format!("Command: {}\n", line));
+        }
+        text.push_str(&format!("Result: {}\n", outcome));
+
+        let tail = self.tail.borrow();
+        if step.capture_output && !tail.is_empty() {
+            text.push_str("\nLast output:\n");
+            for line in tail.iter() {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+
+        self.view.show_copy_details(text);
+    }
+
     fn finish_step(self: &Rc<Self>, code: Option<i32>) {
         let cursor = self.cursor.get();
+        let timed_out = self.timed_out.replace(false);
 
         if self.cancelled.get() {
             self.view.set_step_state(cursor, StepState::Cancelled);
@@ -204,7 +486,7 @@ impl Pipeline {
             return;
         }
 
-        let success = matches!(code, Some(0));
+        let success = !timed_out && matches!(code, Some(0));
         let exit_line = match code {
             Some(c) => format!("\n[exit code: {}]\n", c),
             None => String::from("\n[exit code: unknown]\n"),
@@ -213,32 +495,200 @@ impl Pipeline {
             &exit_line,
             if success { Tag::Stdout } else { Tag::Stderr },
         );
+        if self.steps[cursor].capture_output {
+            self.capture(&exit_line);
+        }
 
         if success {
             self.view.set_step_state(cursor, StepState::Success);
             self.cursor.set(cursor + 1);
             self.advance();
+        } else if self.steps[cursor].allow_failure {
+            warn!("optional step {} failed, continuing", cursor + 1);
+            self.view.append("Step is optional — continuing.\n", Tag::Stderr);
+            self.view.set_step_state(cursor, StepState::Skipped);
+            self.skipped.set(self.skipped.get() + 1);
+            self.cursor.set(cursor + 1);
+            self.advance();
         } else {
             self.view.set_step_state(cursor, StepState::Failed);
-            let suffix = code
-                .map(|c| format!(" (exit code: {})", c))
-                .unwrap_or_default();
-            let msg = format!(
-                "Operation failed at step {} of {}{}",
-                cursor + 1,
-                self.steps.len(),
-                suffix
-            );
+            let outcome = match (timed_out, code) {
+                (true, _) => String::from("timed out"),
+                (false, Some(c)) => format!("exit code {}", c),
+                (false, None) => String::from("exit code unknown"),
+            };
+            self.offer_details(&outcome);
+            let msg = if timed_out {
+                let limit = self.steps[cursor].timeout.unwrap_or_default();
+                format!(
+                    "Operation timed out at step {} of {} (limit: {}s)",
+                    cursor + 1,
+                    self.steps.len(),
+                    limit.as_secs()
+                )
+            } else {
+                let suffix = code
+                    .map(|c| format!(" (exit code: {})", c))
+                    .unwrap_or_default();
+                format!(
+                    "Operation failed at step {} of {}{}",
+                    cursor + 1,
+                    self.steps.len(),
+                    suffix
+                )
+            };
             self.conclude(false, &msg);
         }
     }
 
     fn conclude(self: &Rc<Self>, success: bool, message: &str) {
         stop_daemon();
+        // Whatever ran may have installed or removed packages.
+        crate::core::package::invalidate_cache();
+
+        let mut message = message.to_owned();
+        let mut log_path = None;
+        if let Some(log) = self.log.borrow_mut().as_mut() {
+            log.write(&format!("\n{}\n", message));
+            log.flush();
+            if !success {
+                message.push_str(&format!("\nLog saved to {}", log.path().display()));
+            }
+            log_path = Some(log.path().to_path_buf());
+        }
+        self.record_history(success, &message, log_path);
+
         let tag = if success { Tag::Stdout } else { Tag::Error };
         self.view.append(&format!("\n{}\n", message), tag);
+        if let Some(note) = self.note.as_deref().filter(|_| success && !super::is_dry_run()) {
+            self.view.append(&format!("{}\n", note), Tag::Stdout);
+            self.view.show_output();
+        }
         ACTION_RUNNING.store(false, Ordering::SeqCst);
-        self.view.finalize(success, message);
+        self.view.finalize(success, &message);
+        self.notify_finished(success, &message);
+        if !success && self.cursor.get() < self.steps.len() {
+            self.view.show_retry();
+        }
+    }
+
+    /// Save the sequence as a bash script wherever the user picks.
+    fn export_script(&self) {
+        let title = self
+            .view
+            .window()
+            .title()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| String::from("Task"));
+        let script = super::export::script(&title, &self.steps);
+
+        let dialog = gtk4::FileDialog::new();
+        dialog.set_title("Export as Script");
+        dialog.set_initial_name(Some(&format!("{}.sh", crate::ui::utils::file_stem(&title, "cyberxero-task"))));
+
+        let parent = self.view.window().clone();
+        let view = self.view.clone();
+        glib::spawn_future_local(async move {
+            let Ok(file) = dialog.save_future(Some(&parent)).await else {
+                return;
+            };
+            let Some(path) = file.path() else {
+                return;
+            };
+            let result = std::fs::write(&path, script).and_then(|()| {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            });
+            match result {
+                Ok(()) => {
+                    info!("exported script to {}", path.display());
+                    view.append(&format!("\nScript saved to {}\n", path.display()), Tag::Header);
+                }
+                Err(e) => {
+                    error!("exporting script to {}: {}", path.display(), e);
+                    view.append(&format!("\nCould not save script: {}\n", e), Tag::Error);
+                    view.show_output();
+                }
+            }
+        });
+    }
+
+    /// Add the run to the History dialog, or update its entry after a retry.
+    /// Dry runs executed nothing, so there is nothing to remember.
+    fn record_history(&self, success: bool, message: &str, log: Option<std::path::PathBuf>) {
+        if super::is_dry_run() {
+            return;
+        }
+        let outcome = if success {
+            Outcome::Succeeded
+        } else if self.cancelled.get() {
+            Outcome::Cancelled
+        } else {
+            Outcome::Failed
+        };
+        let title = self
+            .view
+            .window()
+            .title()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| String::from("Task"));
+        let id = history::record(self.history_id.get(), &title, outcome, message, log);
+        self.history_id.set(Some(id));
+    }
+
+    /// Ping the desktop when a run ends while the user is in another window.
+    /// The failure message already names the step that failed.
+    fn notify_finished(&self, success: bool, message: &str) {
+        if !crate::core::settings::notify_on_finish() || self.view.window().is_active() {
+            return;
+        }
+        let Some(app) = gio::Application::default() else {
+            return;
+        };
+
+        let run = self
+            .view
+            .window()
+            .title()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| String::from("Task"));
+        let heading = if success {
+            format!("{} finished", run)
+        } else {
+            format!("{} failed", run)
+        };
+
+        let notification = gio::Notification::new(&heading);
+        notification.set_body(message.lines().next());
+        app.send_notification(Some("task-runner"), &notification);
+    }
+
+    /// Open the run's log file on first use. Failing to create it only
+    /// costs the transcript, so the step runs regardless.
+    fn open_log(&self) {
+        let mut slot = self.log.borrow_mut();
+        if slot.is_some() {
+            return;
+        }
+        match RunLog::create() {
+            Ok(log) => {
+                info!("capturing output to {}", log.path().display());
+                *slot = Some(log);
+            }
+            Err(e) => warn!("could not create run log: {}", e),
+        }
+    }
+
+    fn capture(&self, text: &str) {
+        if let Some(log) = self.log.borrow_mut().as_mut() {
+            log.write(text);
+        }
+    }
+
+    fn flush_log(&self) {
+        if let Some(log) = self.log.borrow_mut().as_mut() {
+            log.flush();
+        }
     }
 }
 
@@ -248,6 +698,57 @@ fn drain<F: FnMut(String)>(rx: &mpsc::Receiver<String>, mut visit: F) {
     }
 }
 
+/// `program` and `args` as one copy-pasteable shell line.
+fn command_line(env: &[(String, String)], program: &str, args: &[String]) -> String {
+    env.iter()
+        .map(|(k, v)| format!("{}={}", k, shell_quote(v)))
+        .chain(
+            std::iter::once(program)
+                .chain(args.iter().map(String::as_str))
+                .map(shell_quote),
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote `arg` so a POSIX shell reads it back verbatim. Used for the
+/// displayed command line and by pages that have to build `sh -c` scripts.
+pub(crate) fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if plain {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Whether the step drives pacman, directly or through an AUR helper.
+fn uses_pacman(step: &Command) -> bool {
+    match step.mode {
+        Mode::Aur => true,
+        Mode::Elevated => step.program == "pacman",
+        _ => false,
+    }
+}
+
+/// Send SIGTERM to the process group led by `pid` (see `process_group(0)`
+/// in [`Pipeline::advance`]).
+fn terminate_group(pid: u32) {
+    // SAFETY: kill(2) has no memory-safety preconditions; a negative pid
+    // addresses the whole process group.
+    let rc = unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGTERM) };
+    if rc != 0 {
+        warn!(
+            "kill({}) failed: {}",
+            pid,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
 /// Push the bundled scripts directory to the front of `PATH` so the sudo
 /// shim can intercept sudo invocations issued from helper scripts.
 fn install_path_shim(cmd: &mut SysCommand) {
@@ -260,10 +761,29 @@ fn install_path_shim(cmd: &mut SysCommand) {
     }
 }
 
+/// Absolute directory `cmd` should run in, if it asked for one. Checked
+/// here so a typo shows up as a clear error instead of a failed spawn.
+fn working_dir(cmd: &Command) -> Result<Option<PathBuf>, String> {
+    let Some(dir) = &cmd.cwd else {
+        return Ok(None);
+    };
+    let dir = if dir.is_relative() {
+        PathBuf::from(&crate::config::env::get().home).join(dir)
+    } else {
+        dir.clone()
+    };
+    if dir.is_dir() {
+        Ok(Some(dir))
+    } else {
+        Err(format!("working directory {} does not exist", dir.display()))
+    }
+}
+
 /// Translate a logical [`Command`] into the concrete `(program, args)` pair
 /// that gets spawned. Elevated and AUR commands are funnelled through the
-/// auth daemon so users authenticate once per sequence.
-fn resolve(cmd: &Command) -> Result<(String, Vec<String>), String> {
+/// auth daemon so users authenticate once per sequence; Flatpak commands get
+/// their `install`/`uninstall` arguments assembled here.
+fn resolve(cmd: &Command, dir: Option<&Path>) -> Result<(String, Vec<String>), String> {
     use crate::core::daemon::get_cyberxero_auth_path;
 
     let scripts_dir = crate::config::paths::scripts();
@@ -285,22 +805,59 @@ fn resolve(cmd: &Command) -> Result<(String, Vec<String>), String> {
                 args.push(String::from("--env"));
                 args.push(env);
             }
+            for (key, value) in &cmd.env {
+                args.push(String::from("--env"));
+                args.push(format!("{}={}", key, value));
+            }
+            // The daemon doesn't inherit our directory; pass it along.
+            if let Some(dir) = dir {
+                args.push(String::from("--cwd"));
+                args.push(dir.to_string_lossy().into_owned());
+            }
             args.push(cmd.program.clone());
             args.extend(cmd.args.iter().cloned());
             Ok((auth_path(), args))
         }
+        // `--sudo` always points at the auth client: whichever escalation
+        // tool launched the daemon, root access afterwards goes through it.
         Mode::Aur => {
-            let helper = crate::core::aur_helper()
-                .ok_or_else(|| String::from("AUR helper not available (paru or yay required)"))?;
+            let helper = crate::core::aur_helper().ok_or_else(|| {
+                format!(
+                    "AUR helper not available (one of {} required)",
+                    crate::core::aur::AUR_HELPERS.join(", ")
+                )
+            })?;
             let mut args = Vec::with_capacity(cmd.args.len() + 2);
-            args.push(String::from("--sudo"));
-            args.push(auth_path());
+            if let Some(flag) = crate::core::aur::sudo_flag(helper) {
+                args.push(flag.to_owned());
+                args.push(auth_path());
+            }
             args.extend(cmd.args.iter().cloned());
             Ok((helper.to_owned(), args))
         }
+        Mode::Flatpak(action) => {
+            if !crate::core::aur::is_executable_in_path("flatpak") {
+                return Err(String::from("flatpak is not installed (sudo pacman -S flatpak)"));
+            }
+            Ok((cmd.program.clone(), flatpak_args(action, &cmd.args)))
+        }
     }
 }
 
+/// Full `flatpak` argument list for installing or removing `refs`.
+pub(super) fn flatpak_args(action: FlatpakAction, refs: &[String]) -> Vec<String> {
+    let mut args = match action {
+        FlatpakAction::Install => vec![
+            String::from("install"),
+            String::from("-y"),
+            String::from(crate::config::flatpak::FLATHUB),
+        ],
+        FlatpakAction::Uninstall => vec![String::from("uninstall"), String::from("-y")],
+    };
+    args.extend(refs.iter().cloned());
+    args
+}
+
 /// Shut the auth daemon down on a throw-away Tokio runtime. Failures here
 /// are logged but not surfaced to the user since the sequence itself has
 /// already finished one way or another.
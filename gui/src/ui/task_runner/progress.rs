@@ -0,0 +1,54 @@
+//! Best-effort progress extraction from package manager output.
+//!
+//! Neither pacman nor flatpak offer a machine-readable progress channel when
+//! stdout isn't a TTY, but both print enough to estimate how far along a
+//! step is. Lines that don't match anything yield `None` and the step keeps
+//! its indeterminate spinner.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// pacman / paru / yay transaction counters: `(3/12) installing foo`.
+fn counter() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*\((\d+)/(\d+)\)").expect("valid regex"))
+}
+
+/// flatpak's `Installing 2/5… 45%` style percentages.
+fn percent() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b(\d{1,3})%").expect("valid regex"))
+}
+
+/// Return a fraction in `0.0..=1.0` if `line` carries recognisable progress.
+pub(super) fn parse(line: &str) -> Option<f64> {
+    if let Some(caps) = counter().captures(line) {
+        let done: f64 = caps[1].parse().ok()?;
+        let total: f64 = caps[2].parse().ok()?;
+        if total > 0.0 {
+            return Some((done / total).clamp(0.0, 1.0));
+        }
+    }
+
+    let caps = percent().captures_iter(line).last()?;
+    let pct: f64 = caps[1].parse().ok()?;
+    (pct <= 100.0).then(|| pct / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pacman_counter() {
+        assert_eq!(parse("(3/12) installing obs-studio"), Some(0.25));
+        assert_eq!(parse("resolving dependencies..."), None);
+    }
+
+    #[test]
+    fn test_parse_flatpak_percent() {
+        assert_eq!(parse("Installing 2/5… ████▌ 50%  1.2 MB/s"), Some(0.5));
+        assert_eq!(parse("Nothing to do, 250% sure"), None);
+    }
+}
@@ -0,0 +1,78 @@
+//! On-disk transcript for steps built with
+//! [`CommandDraft::capture_output`](super::CommandDraft::capture_output).
+//!
+//! One file is opened lazily per run, the first time a capturing step
+//! starts, so sequences that never opt in don't touch the disk.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+
+pub(super) struct RunLog {
+    path: PathBuf,
+    out: BufWriter<File>,
+}
+
+impl RunLog {
+    /// Create `~/.cache/cyberxero-toolkit/logs/<unix-timestamp>.log`, or
+    /// `<unix-timestamp>-N.log` when another run started in the same second.
+    /// Never reuses a name, since history entries point at these files.
+    pub(super) fn create() -> std::io::Result<Self> {
+        let dir = logs_dir();
+        fs::create_dir_all(&dir)?;
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut attempt = 0u32;
+        loop {
+            let path = match attempt {
+                0 => dir.join(format!("{}.log", stamp)),
+                n => dir.join(format!("{}-{}.log", stamp, n)),
+            };
+            match File::options().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        path,
+                        out: BufWriter::new(file),
+                    })
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append captured text with ANSI escapes stripped.
+    pub(super) fn write(&mut self, text: &str) {
+        let cleaned = strip_ansi_escapes::strip_str(text);
+        if let Err(e) = self.out.write_all(cleaned.as_bytes()) {
+            warn!("writing run log {}: {}", self.path.display(), e);
+        }
+    }
+
+    pub(super) fn flush(&mut self) {
+        if let Err(e) = self.out.flush() {
+            warn!("flushing run log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// `~/.cache/cyberxero-toolkit`, shared with the run history index.
+pub(super) fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(crate::config::app_info::NAME)
+}
+
+fn logs_dir() -> PathBuf {
+    cache_dir().join("logs")
+}
@@ -2,12 +2,15 @@
 //! objects directly — it goes through this struct so the state machine and
 //! the UI can evolve independently.
 
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{
-    Box as GtkBox, Builder, Button, Image, Label, Revealer, ScrolledWindow, Separator, TextBuffer,
-    TextTag, TextView, ToggleButton, Window,
+    Box as GtkBox, Builder, Button, Image, Label, ProgressBar, Revealer, ScrolledWindow, Separator,
+    TextBuffer, TextTag, TextView, ToggleButton, Window,
 };
 
 use crate::ui::utils::extract_widget;
@@ -21,6 +24,10 @@ pub(super) enum StepState {
     Running,
     Success,
     Failed,
+    /// Failed, but the step was marked `allow_failure` so the run went on.
+    Skipped,
+    /// Not run because its `run_if` condition didn't hold.
+    NotNeeded,
     Cancelled,
 }
 
@@ -44,12 +51,17 @@ impl Tag {
     }
 }
 
-/// Each step is a horizontal row holding description + either a spinner
-/// or a terminal status icon.
+/// Each step is a horizontal row holding description, elapsed time, and
+/// either a spinner (or a progress bar once the output gives us something to
+/// measure) or a terminal status icon.
 struct StepRow {
     container: GtkBox,
+    elapsed: Label,
+    progress: ProgressBar,
     spinner: Image,
     result: Image,
+    /// Set while the step is running; the clock label freezes once cleared.
+    started: Cell<Option<Instant>>,
 }
 
 impl StepRow {
@@ -65,6 +77,16 @@ impl StepRow {
         label.set_hexpand(true);
         label.set_wrap(true);
 
+        let elapsed = Label::new(None);
+        elapsed.add_css_class("dim-label");
+        elapsed.add_css_class("numeric");
+        elapsed.set_visible(false);
+
+        let progress = ProgressBar::new();
+        progress.set_valign(gtk4::Align::Center);
+        progress.set_size_request(120, -1);
+        progress.set_visible(false);
+
         let spinner = Image::new();
         spinner.set_icon_name(Some("circle-noth-symbolic"));
         spinner.set_pixel_size(24);
@@ -76,13 +98,18 @@ impl StepRow {
         result.set_visible(false);
 
         container.append(&label);
+        container.append(&elapsed);
+        container.append(&progress);
         container.append(&spinner);
         container.append(&result);
 
         Self {
             container,
+            elapsed,
+            progress,
             spinner,
             result,
+            started: Cell::new(None),
         }
     }
 
@@ -92,8 +119,28 @@ impl StepRow {
             StepState::Running => (true, None),
             StepState::Success => (false, Some("circle-check")),
             StepState::Failed => (false, Some("circle-xmark")),
+            StepState::Skipped => (false, Some("circle-exclamation")),
+            StepState::NotNeeded => (false, Some("circle-noth-symbolic")),
             StepState::Cancelled => (false, Some("circle-stop")),
         };
+        match state {
+            StepState::Pending => {
+                self.started.set(None);
+                self.elapsed.set_visible(false);
+            }
+            StepState::Running => {
+                self.started.set(Some(Instant::now()));
+                self.elapsed.set_text(&format_elapsed(Duration::ZERO));
+                self.elapsed.set_visible(true);
+            }
+            _ => {
+                if let Some(since) = self.started.take() {
+                    self.elapsed.set_text(&format_elapsed(since.elapsed()));
+                }
+            }
+        }
+        self.progress.set_fraction(0.0);
+        self.progress.set_visible(false);
         self.spinner.set_visible(spinner_on);
         match icon {
             Some(name) => {
@@ -105,19 +152,58 @@ impl StepRow {
             }
         }
     }
+
+    /// Swap the spinner for a determinate bar.
+    fn set_progress(&self, fraction: f64) {
+        self.spinner.set_visible(false);
+        self.progress.set_visible(true);
+        self.progress.set_fraction(fraction);
+    }
+
+    fn tick(&self) {
+        if let Some(since) = self.started.get() {
+            self.elapsed.set_text(&format_elapsed(since.elapsed()));
+        }
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` past the hour.
+fn format_elapsed(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
 }
 
 pub(super) struct RunnerView {
     window: Window,
     title: Label,
+    subtitle: Label,
     cancel_btn: Button,
+    pause_btn: ToggleButton,
+    retry_btn: Button,
     close_btn: Button,
+    copy_details_btn: Button,
+    export_btn: Button,
+    /// Failure report the Copy Details button puts on the clipboard.
+    details: Rc<RefCell<String>>,
     scrolled: ScrolledWindow,
+    list: GtkBox,
     rows: Vec<StepRow>,
     output_view: TextView,
     output_buf: TextBuffer,
     sidebar_toggle: ToggleButton,
     sidebar_revealer: Revealer,
+    /// One-second tick refreshing the elapsed labels while a step runs.
+    clock: RefCell<Option<glib::SourceId>>,
+    /// Run time banked by earlier attempts, plus when the current one began.
+    accumulated: Cell<Duration>,
+    active_since: Cell<Option<Instant>>,
+    /// Index of the step that last started, and how far along it reports.
+    position: Cell<Option<(usize, f64)>>,
 }
 
 impl RunnerView {
@@ -127,10 +213,15 @@ impl RunnerView {
     pub(super) fn from_builder(builder: &Builder, steps: &[Command]) -> Rc<Self> {
         let window: Window = extract_widget(builder, "task_window");
         let title: Label = extract_widget(builder, "task_title");
+        let subtitle: Label = extract_widget(builder, "task_subtitle");
         let list: GtkBox = extract_widget(builder, "task_list_container");
         let scrolled: ScrolledWindow = extract_widget(builder, "task_scrolled_window");
         let cancel_btn: Button = extract_widget(builder, "cancel_button");
+        let pause_btn: ToggleButton = extract_widget(builder, "pause_button");
+        let retry_btn: Button = extract_widget(builder, "retry_button");
         let close_btn: Button = extract_widget(builder, "close_button");
+        let copy_details_btn: Button = extract_widget(builder, "copy_details_button");
+        let export_btn: Button = extract_widget(builder, "export_button");
         let sidebar_toggle: ToggleButton = extract_widget(builder, "sidebar_toggle_button");
         let sidebar_revealer: Revealer = extract_widget(builder, "sidebar_revealer");
         let output_view: TextView = extract_widget(builder, "output_text_view");
@@ -153,18 +244,31 @@ impl RunnerView {
         let this = Rc::new(Self {
             window,
             title,
+            subtitle,
             cancel_btn,
+            pause_btn,
+            retry_btn,
             close_btn,
+            copy_details_btn,
+            export_btn,
+            details: Rc::new(RefCell::new(String::new())),
             scrolled,
+            list,
             rows,
             output_view,
             output_buf,
             sidebar_toggle,
             sidebar_revealer,
+            clock: RefCell::new(None),
+            accumulated: Cell::new(Duration::ZERO),
+            active_since: Cell::new(None),
+            position: Cell::new(None),
         });
 
         this.install_tags();
         this.bind_sidebar();
+        this.bind_copy_details();
+        this.bind_pause();
         this.collapse_sidebar();
 
         this
@@ -211,6 +315,26 @@ impl RunnerView {
         });
     }
 
+    fn bind_copy_details(&self) {
+        let details = self.details.clone();
+        let window = self.window.clone();
+        self.copy_details_btn.connect_clicked(move |button| {
+            window.clipboard().set_text(&details.borrow());
+            button.set_label("Copied");
+        });
+    }
+
+    fn bind_pause(&self) {
+        self.pause_btn.connect_toggled(|button| {
+            button.set_label(if button.is_active() { "Resume" } else { "Pause" });
+        });
+    }
+
+    /// Reveal the output pane.
+    pub(super) fn show_output(&self) {
+        self.sidebar_toggle.set_active(true);
+    }
+
     fn collapse_sidebar(&self) {
         self.sidebar_toggle.set_active(false);
         self.sidebar_revealer.set_reveal_child(false);
@@ -234,10 +358,28 @@ impl RunnerView {
         self.cancel_btn.connect_clicked(move |_| handler());
     }
 
+    /// True while the user wants the run held at the next step boundary.
+    pub(super) fn pause_requested(&self) -> bool {
+        self.pause_btn.is_active()
+    }
+
+    pub(super) fn on_pause_toggled<F: Fn(bool) + 'static>(&self, handler: F) {
+        self.pause_btn
+            .connect_toggled(move |button| handler(button.is_active()));
+    }
+
+    pub(super) fn on_retry<F: Fn() + 'static>(&self, handler: F) {
+        self.retry_btn.connect_clicked(move |_| handler());
+    }
+
     pub(super) fn on_close<F: Fn() + 'static>(&self, handler: F) {
         self.close_btn.connect_clicked(move |_| handler());
     }
 
+    pub(super) fn on_export<F: Fn() + 'static>(&self, handler: F) {
+        self.export_btn.connect_clicked(move |_| handler());
+    }
+
     pub(super) fn on_window_close<F: Fn() + 'static>(&self, handler: F) {
         self.window.connect_close_request(move |_| {
             handler();
@@ -245,28 +387,105 @@ impl RunnerView {
         });
     }
 
+    /// Start the elapsed-time tick. Time from earlier attempts (before a
+    /// retry) carries over into the total.
+    pub(super) fn start_clock(self: &Rc<Self>) {
+        if self.clock.borrow().is_some() {
+            return;
+        }
+        self.active_since.set(Some(Instant::now()));
+
+        let weak = Rc::downgrade(self);
+        let id = glib::timeout_add_local(Duration::from_secs(1), move || {
+            let Some(view) = weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            view.tick();
+            glib::ControlFlow::Continue
+        });
+        *self.clock.borrow_mut() = Some(id);
+        self.tick();
+    }
+
+    /// Stop the tick and bank the current attempt's time. Safe to call more
+    /// than once.
+    pub(super) fn stop_clock(&self) {
+        if let Some(id) = self.clock.take() {
+            id.remove();
+        }
+        if let Some(since) = self.active_since.take() {
+            self.accumulated.set(self.accumulated.get() + since.elapsed());
+        }
+    }
+
+    fn total_elapsed(&self) -> Duration {
+        let current = self
+            .active_since
+            .get()
+            .map(|since| since.elapsed())
+            .unwrap_or_default();
+        self.accumulated.get() + current
+    }
+
+    fn tick(&self) {
+        for row in &self.rows {
+            row.tick();
+        }
+        let elapsed = format!("Elapsed {}", format_elapsed(self.total_elapsed()));
+        match self.position.get() {
+            Some((index, fraction)) => {
+                let total = self.rows.len();
+                let percent = ((index as f64 + fraction) / total as f64 * 100.0) as u32;
+                self.subtitle.set_text(&format!(
+                    "Step {} of {} · {}% · {}",
+                    index + 1,
+                    total,
+                    percent,
+                    elapsed
+                ));
+            }
+            None => self.subtitle.set_text(&elapsed),
+        }
+    }
+
     pub(super) fn set_step_state(&self, index: usize, state: StepState) {
         if let Some(row) = self.rows.get(index) {
             row.apply(state);
             self.focus_step(index);
+            if matches!(state, StepState::Running) {
+                self.position.set(Some((index, 0.0)));
+                self.tick();
+            }
+        }
+    }
+
+    /// Show determinate progress for a running step.
+    pub(super) fn set_step_progress(&self, index: usize, fraction: f64) {
+        if let Some(row) = self.rows.get(index) {
+            row.set_progress(fraction);
+            self.position.set(Some((index, fraction.clamp(0.0, 1.0))));
         }
     }
 
     /// Keep the active step in view without jumping the scroll when the user
     /// has manually scrolled to a still-visible location.
+    ///
+    /// Uses the row's real allocation: descriptions wrap, so rows differ in
+    /// height and an averaged estimate drifts on long sequences.
     fn focus_step(&self, index: usize) {
-        let total = self.rows.len();
-        if total == 0 || index >= total {
+        let Some(row) = self.rows.get(index) else {
             return;
-        }
-        let adj = self.scrolled.vadjustment();
-        let span = adj.upper();
-        if span <= 0.0 {
+        };
+        // Not allocated yet (window still mapping); nothing to scroll to.
+        let Some(bounds) = row.container.compute_bounds(&self.list) else {
             return;
-        }
-        let row_height = span / total as f64;
-        let row_top = index as f64 * row_height;
-        let row_bottom = row_top + row_height;
+        };
+        let adj = self.scrolled.vadjustment();
+
+        // The list is the scrolled content, so its top margin sits above
+        // its own coordinate origin.
+        let row_top = f64::from(self.list.margin_top()) + f64::from(bounds.y());
+        let row_bottom = row_top + f64::from(bounds.height());
         let view_top = adj.value();
         let view_bottom = view_top + adj.page_size();
 
@@ -309,10 +528,45 @@ impl RunnerView {
             .scroll_to_iter(&mut end, 0.0, false, 0.0, 0.0);
     }
 
+    /// Offer the Retry button once the window has been finalized as failed.
+    pub(super) fn show_retry(&self) {
+        self.retry_btn.set_visible(true);
+        self.retry_btn.set_sensitive(true);
+        self.retry_btn.add_css_class("suggested-action");
+        self.close_btn.remove_css_class("suggested-action");
+    }
+
+    /// Show the Copy Details button with `report` as its clipboard text.
+    pub(super) fn show_copy_details(&self, report: String) {
+        *self.details.borrow_mut() = report;
+        self.copy_details_btn.set_label("Copy Details");
+        self.copy_details_btn.set_visible(true);
+    }
+
+    /// Undo [`finalize`](Self::finalize) so the window can drive another
+    /// attempt: Cancel comes back, Retry, Copy Details and Close are hidden
+    /// again, and a Pause left on from the last attempt is released.
+    pub(super) fn reset_for_retry(&self) {
+        self.title.remove_css_class("success");
+        self.title.remove_css_class("error");
+
+        self.retry_btn.set_visible(false);
+        self.copy_details_btn.set_visible(false);
+        self.close_btn.set_visible(false);
+        self.close_btn.set_sensitive(false);
+        self.cancel_btn.set_visible(true);
+        self.cancel_btn.set_sensitive(true);
+        self.pause_btn.set_active(false);
+        self.pause_btn.set_visible(true);
+    }
+
     /// Flip the window into its terminal state: hide Cancel, show Close, and
     /// style the title according to success/failure.
     pub(super) fn finalize(&self, success: bool, message: &str) {
         self.set_title(message);
+        self.stop_clock();
+        self.subtitle
+            .set_text(&format!("Total time {}", format_elapsed(self.total_elapsed())));
 
         let (add, remove) = if success {
             ("success", "error")
@@ -329,6 +583,8 @@ impl RunnerView {
         }
 
         self.cancel_btn.set_visible(false);
+        self.pause_btn.set_visible(false);
+        self.retry_btn.set_visible(false);
         self.close_btn.set_visible(true);
         self.close_btn.set_sensitive(true);
     }
@@ -0,0 +1,148 @@
+//! Sidebar search across the tools offered on every page.
+//!
+//! The index is static: each page lists its tools in a `TOOLS` table that is
+//! attached to its [`PageConfig`](crate::ui::navigation::PageConfig). Picking
+//! a result clicks the page's sidebar tab (so lazy loading runs as usual),
+//! then focuses and briefly highlights the tool's button once the page is in.
+
+use crate::ui::navigation::{find_child_by_name, Tool, PAGES};
+use crate::ui::utils::extract_widget;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, Builder, Button, Label, ListBox, ListBoxRow, Orientation, SearchEntry, Stack,
+};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Most results shown at once.
+const MAX_RESULTS: usize = 8;
+
+/// How long a revealed button stays highlighted.
+const HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+
+/// How often, and how many times, to look for the button while its page loads.
+const REVEAL_POLL: Duration = Duration::from_millis(50);
+const REVEAL_ATTEMPTS: u32 = 100;
+
+/// A search hit: index into [`PAGES`] plus the tool on that page.
+type Hit = (usize, &'static Tool);
+
+/// Wire the sidebar search entry to the page tabs.
+///
+/// `buttons` are the sidebar tab buttons, in [`PAGES`] order.
+pub fn setup(main_builder: &Builder, stack: &Stack, buttons: Rc<Vec<Button>>) {
+    let entry = extract_widget::<SearchEntry>(main_builder, "tool_search_entry");
+    let results = extract_widget::<ListBox>(main_builder, "tool_search_results");
+    let hits: Rc<RefCell<Vec<Hit>>> = Rc::new(RefCell::new(Vec::new()));
+
+    {
+        let results = results.clone();
+        let hits = Rc::clone(&hits);
+        entry.connect_search_changed(move |entry| {
+            let found = search(&entry.text());
+            render_results(&results, &found);
+            *hits.borrow_mut() = found;
+        });
+    }
+
+    let open = {
+        let entry = entry.clone();
+        let stack = stack.clone();
+        let hits = Rc::clone(&hits);
+        Rc::new(move |index: usize| {
+            let hit = hits.borrow().get(index).copied();
+            let Some((page, tool)) = hit else {
+                return;
+            };
+            info!("Tool search: opening '{}' on '{}'", tool.name, PAGES[page].id);
+            if let Some(tab) = buttons.get(page) {
+                tab.emit_clicked();
+            }
+            reveal_tool(&stack, PAGES[page].id, tool.button);
+            entry.set_text("");
+        })
+    };
+
+    {
+        let open = Rc::clone(&open);
+        results.connect_row_activated(move |_, row| open(row.index() as usize));
+    }
+    entry.connect_activate(move |_| open(0));
+
+    entry.connect_stop_search(|entry| entry.set_text(""));
+}
+
+/// Tools whose name, keywords or page title contain every word of `query`.
+fn search(query: &str) -> Vec<Hit> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    PAGES
+        .iter()
+        .enumerate()
+        .flat_map(|(i, page)| page.tools.iter().map(move |tool| (i, tool)))
+        .filter(|(i, tool)| {
+            let haystack =
+                format!("{} {} {}", tool.name, tool.keywords, PAGES[*i].title).to_lowercase();
+            words.iter().all(|w| haystack.contains(w.as_str()))
+        })
+        .take(MAX_RESULTS)
+        .collect()
+}
+
+fn render_results(results: &ListBox, hits: &[Hit]) {
+    results.remove_all();
+    for &(page, tool) in hits {
+        let content = GtkBox::new(Orientation::Vertical, 2);
+        content.set_margin_top(6);
+        content.set_margin_bottom(6);
+        content.set_margin_start(10);
+        content.set_margin_end(10);
+
+        let name = Label::new(Some(tool.name));
+        name.set_xalign(0.0);
+        let page_title = Label::new(Some(PAGES[page].title));
+        page_title.set_xalign(0.0);
+        page_title.add_css_class("dim-label");
+        page_title.add_css_class("caption");
+
+        content.append(&name);
+        content.append(&page_title);
+
+        let row = ListBoxRow::new();
+        row.set_child(Some(&content));
+        results.append(&row);
+    }
+    results.set_visible(!hits.is_empty());
+}
+
+/// Focus and highlight `button_id` on `page_id`, waiting for a lazy load
+/// still in flight to swap the real page in.
+fn reveal_tool(stack: &Stack, page_id: &str, button_id: &'static str) {
+    let Some(page) = stack.child_by_name(page_id) else {
+        return;
+    };
+
+    let mut attempts = 0;
+    glib::timeout_add_local(REVEAL_POLL, move || {
+        attempts += 1;
+        if let Some(button) = find_child_by_name::<gtk4::Widget>(&page, button_id) {
+            button.grab_focus();
+            button.add_css_class("search-highlight");
+            glib::timeout_add_local_once(HIGHLIGHT_DURATION, move || {
+                button.remove_css_class("search-highlight");
+            });
+            return glib::ControlFlow::Break;
+        }
+        if attempts >= REVEAL_ATTEMPTS {
+            glib::ControlFlow::Break
+        } else {
+            glib::ControlFlow::Continue
+        }
+    });
+}
@@ -1,15 +1,249 @@
 //! UI utility functions for widget extraction and common operations.
 
+use crate::core::{is_flatpak_installed, FlatpakKind};
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::Builder;
+use gtk4::{ApplicationWindow, Builder, Button};
+use std::cell::Cell;
 use std::process::Command;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Minimum spacing between two focus-triggered refreshes of one page.
+const REFOCUS_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Why a widget id could not be resolved from a builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WidgetError {
+    /// No object with this id exists in the builder.
+    Missing(String),
+    /// The id exists but names an object of another type.
+    WrongType {
+        id: String,
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl std::fmt::Display for WidgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WidgetError::Missing(id) => write!(f, "No widget with id '{}'", id),
+            WidgetError::WrongType {
+                id,
+                expected,
+                found,
+            } => write!(f, "Widget '{}' is a {}, expected {}", id, found, expected),
+        }
+    }
+}
+
+impl std::error::Error for WidgetError {}
+
+/// Fallible counterpart of [`extract_widget`], for widgets a page can live
+/// without: the caller decides whether to skip, disable or report.
+pub fn try_extract_widget<T: IsA<glib::Object>>(
+    builder: &Builder,
+    name: &str,
+) -> Result<T, WidgetError> {
+    let object = builder
+        .object::<glib::Object>(name)
+        .ok_or_else(|| WidgetError::Missing(name.to_owned()))?;
+    let found = object.type_().name().to_owned();
+    object.downcast::<T>().map_err(|_| WidgetError::WrongType {
+        id: name.to_owned(),
+        expected: T::static_type().name(),
+        found,
+    })
+}
 
 /// Helper to extract widgets from builder with consistent error handling.
+/// Panics when the widget is missing; use it only for widgets the app
+/// cannot run without, and [`try_extract_widget`] everywhere else.
 pub fn extract_widget<T: IsA<glib::Object>>(builder: &Builder, name: &str) -> T {
-    builder
-        .object(name)
-        .unwrap_or_else(|| panic!("Failed to get widget with id '{}'", name))
+    try_extract_widget(builder, name).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Call `refresh` whenever `window` regains focus, at most once per
+/// [`REFOCUS_DEBOUNCE`]. A refocus inside that window is not dropped but
+/// deferred to its end, so rapid alt-tabbing collapses into one trailing
+/// refresh that still sees whatever changed meanwhile.
+pub fn on_refocus(window: &ApplicationWindow, refresh: impl Fn() + 'static) {
+    let refresh = Rc::new(refresh);
+    let last: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+    let deferred = Rc::new(Cell::new(false));
+
+    window.connect_is_active_notify(move |window| {
+        if !window.is_active() || deferred.get() {
+            return;
+        }
+        let wait = last
+            .get()
+            .map(|at| REFOCUS_DEBOUNCE.saturating_sub(at.elapsed()))
+            .unwrap_or(Duration::ZERO);
+        if wait.is_zero() {
+            last.set(Some(Instant::now()));
+            refresh();
+            return;
+        }
+
+        deferred.set(true);
+        let (refresh, last, deferred) = (refresh.clone(), last.clone(), deferred.clone());
+        glib::timeout_add_local_once(wait, move || {
+            deferred.set(false);
+            last.set(Some(Instant::now()));
+            refresh();
+        });
+    });
+}
+
+/// What makes a tool count as installed for [`refresh_button_states`].
+#[derive(Debug, Clone, Copy)]
+pub enum InstallCheck {
+    /// A pacman package.
+    Package(&'static str),
+    /// A Flatpak app id.
+    Flatpak(&'static str),
+    /// A systemd unit that is currently running.
+    Service(&'static str),
+    /// A pacman package or, failing that, its Flatpak app id.
+    PackageOrFlatpak(&'static str, &'static str),
+    /// A pacman package whose systemd unit, system or user, is running.
+    /// Installed but stopped shows as "(stopped)" with uninstall available.
+    PackageAndService(&'static str, &'static str),
+}
+
+/// Result of one [`InstallCheck`]: installed, and if so whether stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckState {
+    Missing,
+    Installed,
+    Stopped,
+}
+
+impl InstallCheck {
+    fn state(self) -> CheckState {
+        let installed = match self {
+            InstallCheck::Package(name) => is_package_installed(name),
+            InstallCheck::Flatpak(app_id) => is_flatpak_installed(app_id, FlatpakKind::App),
+            InstallCheck::Service(unit) => is_service_active(unit),
+            InstallCheck::PackageOrFlatpak(name, app_id) => {
+                is_package_installed(name) || is_flatpak_installed(app_id, FlatpakKind::App)
+            }
+            InstallCheck::PackageAndService(name, unit) => {
+                if !is_package_installed(name) {
+                    return CheckState::Missing;
+                }
+                if !is_service_active(unit) && !is_user_service_active(unit) {
+                    return CheckState::Stopped;
+                }
+                true
+            }
+        };
+        if installed {
+            CheckState::Installed
+        } else {
+            CheckState::Missing
+        }
+    }
+
+    /// The pacman package whose version can describe this check, if any.
+    fn package(self) -> Option<&'static str> {
+        match self {
+            InstallCheck::Package(name)
+            | InstallCheck::PackageOrFlatpak(name, _)
+            | InstallCheck::PackageAndService(name, _) => Some(name),
+            InstallCheck::Flatpak(_) | InstallCheck::Service(_) => None,
+        }
+    }
+}
+
+/// Update install / uninstall button pair based on installation status.
+///
+/// Installed  → install button greyed with "✓", uninstall visible.
+/// Not installed → install button active, uninstall hidden.
+pub fn update_button_state(
+    install_button: &Button,
+    uninstall_button: &Button,
+    is_installed: bool,
+    default_label: &str,
+) {
+    if is_installed {
+        install_button.set_label(&format!("{} ✓", default_label));
+        install_button.set_sensitive(false);
+        install_button.remove_css_class("suggested-action");
+        install_button.add_css_class("dim-label");
+        uninstall_button.set_visible(true);
+    } else {
+        install_button.set_label(default_label);
+        install_button.set_sensitive(true);
+        install_button.add_css_class("suggested-action");
+        install_button.remove_css_class("dim-label");
+        uninstall_button.set_visible(false);
+    }
+}
+
+/// Run every check on one background thread, then apply
+/// [`update_button_state`] to each `(install, uninstall, check, label)` pair
+/// on the main loop. Never blocks the GTK main loop, and the batch shares
+/// one warm package-query cache.
+pub fn refresh_button_states(checks: Vec<(Button, Button, InstallCheck, &'static str)>) {
+    refresh_states(checks, false);
+}
+
+/// [`refresh_button_states`], plus a version tooltip on each install button
+/// whose check names a pacman package (see [`version_tooltip`]).
+pub fn refresh_versioned_button_states(checks: Vec<(Button, Button, InstallCheck, &'static str)>) {
+    refresh_states(checks, true);
+}
+
+fn refresh_states(checks: Vec<(Button, Button, InstallCheck, &'static str)>, versions: bool) {
+    let kinds: Vec<(InstallCheck, &'static str)> =
+        checks.iter().map(|(_, _, check, label)| (*check, *label)).collect();
+    let (tx, rx) = async_channel::bounded::<Vec<(CheckState, Option<String>)>>(1);
+
+    std::thread::spawn(move || {
+        let states = kinds
+            .into_iter()
+            .map(|(check, label)| {
+                let tooltip = check
+                    .package()
+                    .filter(|_| versions)
+                    .and_then(|package| version_tooltip(package, label));
+                (check.state(), tooltip)
+            })
+            .collect();
+        let _ = tx.send_blocking(states);
+    });
+
+    glib::MainContext::default().spawn_local(async move {
+        if let Ok(states) = rx.recv().await {
+            for ((install, uninstall, check, label), (state, tooltip)) in checks.iter().zip(states) {
+                update_button_state(install, uninstall, state == CheckState::Installed, label);
+                if state == CheckState::Stopped {
+                    install.set_label(&format!("{} (stopped)", label));
+                    uninstall.set_visible(true);
+                }
+                if versions && check.package().is_some() {
+                    install.set_tooltip_text(tooltip.as_deref());
+                }
+            }
+        }
+    });
+}
+
+/// "Docker 1:26.1.0-1", plus the repo version when it's newer. `None` when
+/// the package isn't installed.
+pub fn version_tooltip(package: &str, label: &str) -> Option<String> {
+    use crate::core::package::{get_installed_version, get_repo_version, is_version_newer};
+
+    let installed = get_installed_version(package)?;
+    Some(match get_repo_version(package) {
+        Some(repo) if is_version_newer(&repo, &installed) => {
+            format!("{} {} (update to {} available)", label, installed, repo)
+        }
+        _ => format!("{} {}", label, installed),
+    })
 }
 
 /// Run a command and return stdout as a trimmed string.
@@ -36,16 +270,61 @@ pub fn is_user_service_enabled(service: &str) -> bool {
         .unwrap_or(false)
 }
 
-/// Check if a pacman package is installed.
+/// Check if a systemd service is currently running.
+pub fn is_service_active(service: &str) -> bool {
+    run_command("systemctl", &["is-active", service]).is_some_and(|s| s == "active")
+}
+
+/// Check if a systemd user service is currently running.
+pub fn is_user_service_active(service: &str) -> bool {
+    run_command("systemctl", &["--user", "is-active", service]).is_some_and(|s| s == "active")
+}
+
+/// Check if a pacman package is installed. Shares the short-lived query
+/// cache in [`crate::core::package`].
 pub fn is_package_installed(package: &str) -> bool {
-    Command::new("pacman")
-        .args(["-Q", package])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+    crate::core::is_package_installed(package)
 }
 
 /// Check if a path exists.
 pub fn path_exists(path: &str) -> bool {
     std::path::Path::new(path).exists()
 }
+
+/// File name stem for a window title: `Install Docker` → `install-docker`.
+/// Titles with no usable characters get `fallback`.
+pub fn file_stem(title: &str, fallback: &str) -> String {
+    let stem = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if stem.is_empty() {
+        fallback.to_owned()
+    } else {
+        stem
+    }
+}
+
+/// Quote one Exec argument per the desktop entry spec: double quotes with
+/// `"`, `` ` ``, `$` and `\` backslash-escaped (the backslash doubled again
+/// for the string-value unescaping), and `%` doubled so it isn't read as a
+/// field code.
+pub fn desktop_exec_quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}